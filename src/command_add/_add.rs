@@ -16,12 +16,19 @@ const DEPRECATED_COMPONENTS: &[DeprecatedComponent] = &[DeprecatedComponent {
 
 use clap::{Arg, ArgMatches, Command};
 
+use super::component_dirs::ComponentDirs;
 use super::components::Components;
+use super::dependencies::{
+    CargoDepsPlan, apply_cargo_deps_plan, confirm_cargo_deps_plan, format_cargo_deps_plan, plan_cargo_deps,
+};
 use super::installed::get_installed_components;
 use super::registry::RegistryComponent;
 use super::tree_parser::TreeParser;
 use crate::command_diff::_diff::{diff_components, format_diff_human};
-use crate::command_init::config::UiConfig;
+use crate::command_init::config::{Aliases, UiConfig};
+use crate::command_init::install::{PackageManager, install_packages};
+use crate::command_init::tailwind_sources::ensure_tailwind_sources;
+use crate::command_init::workspace_utils::{analyze_workspace, analyze_workspace_for_package};
 use crate::command_view::_view::view_components;
 use crate::shared::cli_error::{CliError, CliResult};
 use crate::shared::rust_ui_client::RustUIClient;
@@ -34,7 +41,7 @@ pub fn command_add() -> Command {
             Arg::new("yes")
                 .short('y')
                 .long("yes")
-                .help("Overwrite existing files without prompting")
+                .help("Overwrite existing files and apply Cargo.toml changes without prompting")
                 .action(clap::ArgAction::SetTrue),
         )
         .arg(
@@ -50,6 +57,19 @@ pub fn command_add() -> Command {
                 .help("Override the output directory for components (default: base_path_components from ui_config.toml)")
                 .value_name("PATH"),
         )
+        .arg(
+            Arg::new("package")
+                .short('p')
+                .long("package")
+                .help("Workspace member to install into (default: package from ui_config.toml)")
+                .value_name("NAME"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Run rustfmt on the written components (default: format_on_write from ui_config.toml)")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("view")
                 .long("view")
@@ -75,35 +95,43 @@ pub async fn process_add_components(components: Vec<String>, base_path: &str) ->
         return Ok(());
     }
 
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
+    let tree_parser = TreeParser::fetch().await?;
 
     let resolved_set = tree_parser.resolve_dependencies(&components)?;
     let all_resolved_components: Vec<String> = resolved_set.components.into_iter().collect();
     let all_resolved_parent_dirs: Vec<String> = resolved_set.parent_dirs.into_iter().collect();
     let all_resolved_cargo_dependencies: Vec<String> = resolved_set.cargo_deps.into_iter().collect();
+    let all_resolved_npm_deps: HashSet<String> = resolved_set.npm_deps;
     let all_resolved_js_files: HashSet<String> = resolved_set.js_files;
     let user_requested: HashSet<String> = components.into_iter().collect();
 
-    Components::create_components_mod_if_not_exists_with_pub_mods(
-        base_path.to_string(),
-        all_resolved_parent_dirs,
-    )?;
-
-    let components_path = Path::new(base_path);
-    let parent_path = components_path
-        .parent()
-        .ok_or_else(|| CliError::invalid_path(base_path, "no parent directory"))?;
-    let entry_file_path = if parent_path.join("lib.rs").exists() {
-        parent_path.join("lib.rs")
-    } else {
-        parent_path.join("main.rs")
+    // Reinstalls go where ui_config.toml puts them, `[paths]` and `[aliases]` included
+    let ui_config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML).ok();
+    let format = ui_config.as_ref().is_some_and(|config| config.format_on_write == Some(true));
+    let (dirs, aliases) = match ui_config {
+        Some(config) if config.base_path_components == base_path => {
+            (ComponentDirs::from_config(&config), config.aliases)
+        }
+        _ => (ComponentDirs::from_base_path(base_path), Aliases::default()),
     };
-    Components::register_components_in_application_entry(
-        entry_file_path.to_string_lossy().as_ref(),
-    )?;
 
-    let installed = get_installed_components(base_path);
+    if Components::declare_category_modules(&dirs, all_resolved_parent_dirs)? {
+        let components_path = Path::new(base_path);
+        let parent_path = components_path
+            .parent()
+            .ok_or_else(|| CliError::invalid_path(base_path, "no parent directory"))?;
+        let entry_file_path = if parent_path.join("lib.rs").exists() {
+            parent_path.join("lib.rs")
+        } else {
+            parent_path.join("main.rs")
+        };
+        Components::register_components_in_application_entry(
+            entry_file_path.to_string_lossy().as_ref(),
+            dirs.spans_crates(),
+        )?;
+    }
+
+    let installed = get_installed_components(&dirs, &dirs.categories(&tree_parser.get_categories()));
     let mut written: Vec<String> = Vec::new();
     let mut skipped: Vec<String> = Vec::new();
     let mut already_installed: Vec<String> = Vec::new();
@@ -114,9 +142,14 @@ pub async fn process_add_components(components: Vec<String>, base_path: &str) ->
             continue;
         }
 
-        let outcome = RegistryComponent::fetch_from_registry(component_name.clone())
+        let category = tree_parser.category_of(&component_name);
+        let outcome = RegistryComponent::fetch_from_registry(
+            component_name.clone(),
+            category,
+            tree_parser.files_of(&component_name),
+        )
             .await?
-            .then_write_to_file_to(true, base_path) // force = always overwrite on reinstall
+            .then_write_to_file_to(true, &dirs, &aliases, format) // force = always overwrite on reinstall
             .await?;
 
         match outcome {
@@ -127,8 +160,15 @@ pub async fn process_add_components(components: Vec<String>, base_path: &str) ->
 
     print_add_summary(&written, &skipped, &already_installed);
 
+    ensure_tailwind_sources(&dirs.source_dirs())?;
+
     if !all_resolved_cargo_dependencies.is_empty() {
-        super::dependencies::process_cargo_deps(&all_resolved_cargo_dependencies)?;
+        // Reinstalls follow a confirmed `ui init`: the Cargo.toml changes are shown, not asked for
+        let workspace_info = analyze_workspace().ok();
+        super::dependencies::process_cargo_deps(&all_resolved_cargo_dependencies, &workspace_info, true)?;
+    }
+    if !all_resolved_npm_deps.is_empty() {
+        process_npm_deps(&all_resolved_npm_deps)?;
     }
     if !all_resolved_js_files.is_empty() {
         process_js_files(&all_resolved_js_files).await?;
@@ -144,23 +184,40 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
     let force = matches.get_flag("yes");
     let dry_run = matches.get_flag("dry-run");
     let view_flag = matches.get_flag("view");
+    let format_flag = matches.get_flag("format");
     let diff_flag = matches.get_flag("diff");
     let path_override: Option<String> = matches.get_one::<String>("path").cloned();
     let has_path_override = path_override.is_some();
+    let package: Option<&String> = matches.get_one::<String>("package");
+
+    // Target crate: --package, else the member stored in ui_config.toml
+    let workspace_info = match package {
+        Some(name) => Some(analyze_workspace_for_package(Some(name))?),
+        None => analyze_workspace().ok(),
+    };
 
-    // Fetch and parse tree.md
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
+    // Fetch the registry index (registry.json, else tree.md)
+    let tree_parser = TreeParser::fetch().await?;
 
-    // Get base path for components: --path flag takes priority over ui_config.toml
-    let base_path = path_override.unwrap_or_else(|| {
-        UiConfig::try_reading_ui_config(UI_CONFIG_TOML)
-            .map(|c| c.base_path_components)
-            .unwrap_or_else(|_| "src/components".to_string())
+    // Get base path for components: --path, then the --package member, then ui_config.toml
+    let package_path = package.and(workspace_info.as_ref()).map(|info| info.components_base_path.clone());
+    let ui_config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML).ok();
+    let base_path = path_override.or(package_path).unwrap_or_else(|| {
+        ui_config.as_ref().map_or_else(|| "src/components".to_string(), |c| c.base_path_components.clone())
     });
 
+    // `[paths]` and `[aliases]` describe the configured layout; a --path target holds every
+    // category and gets its module path inferred
+    let (dirs, aliases) = match &ui_config {
+        Some(config) if !has_path_override => {
+            (ComponentDirs::new(&base_path, &config.paths), config.aliases.clone())
+        }
+        _ => (ComponentDirs::from_base_path(&base_path), Aliases::default()),
+    };
+    let format = format_flag || ui_config.as_ref().is_some_and(|config| config.format_on_write == Some(true));
+
     // Detect already installed components
-    let installed = get_installed_components(&base_path);
+    let installed = get_installed_components(&dirs, &dirs.categories(&tree_parser.get_categories()));
 
     // If no components provided, launch TUI
     let user_components = if user_components.is_empty() {
@@ -197,6 +254,7 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
     let all_resolved_components: Vec<String> = resolved_set.components.into_iter().collect();
     let all_resolved_parent_dirs: Vec<String> = resolved_set.parent_dirs.into_iter().collect();
     let all_resolved_cargo_dependencies: Vec<String> = resolved_set.cargo_deps.into_iter().collect();
+    let all_resolved_npm_deps: HashSet<String> = resolved_set.npm_deps;
     let all_resolved_js_files: HashSet<String> = resolved_set.js_files;
 
     // Track which components the user explicitly requested for prompt decisions
@@ -213,11 +271,18 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
     if diff_flag {
         let mut names = all_resolved_components.clone();
         names.sort();
-        let diffs = diff_components(&names, &base_path).await?;
+        let diffs = diff_components(&names, &tree_parser, &dirs, &aliases, format).await?;
         println!("{}", format_diff_human(&diffs));
         return Ok(());
     }
 
+    // The exact Cargo.toml edits, `dep.workspace = true` member entries included
+    let cargo_plan = if all_resolved_cargo_dependencies.is_empty() {
+        CargoDepsPlan::default()
+    } else {
+        plan_cargo_deps(&all_resolved_cargo_dependencies, &workspace_info)?
+    };
+
     // Dry-run: show what would happen without touching the filesystem
     if dry_run {
         let summary = compute_dry_run_summary(
@@ -225,21 +290,28 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
             &installed,
             &user_requested,
             &all_resolved_cargo_dependencies,
+            &all_resolved_npm_deps,
             &all_resolved_js_files,
         );
         println!("{}", format_dry_run_summary(&summary));
+        if !cargo_plan.is_empty() {
+            println!("{}", format_cargo_deps_plan(&cargo_plan, "[dry-run] "));
+        }
         return Ok(());
     }
 
-    // Create components/mod.rs if it does not exist
-    Components::create_components_mod_if_not_exists_with_pub_mods(
-        base_path.clone(),
-        all_resolved_parent_dirs,
-    )?;
+    // Ask about Cargo.toml before writing anything: declining leaves the project untouched
+    if !confirm_cargo_deps_plan(&cargo_plan, force)? {
+        println!("⏭️  Nothing added: the Cargo.toml changes were declined.");
+        return Ok(());
+    }
+
+    // Create components/mod.rs if it does not exist, and declare categories mapped elsewhere
+    let uses_base_path = Components::declare_category_modules(&dirs, all_resolved_parent_dirs)?;
 
     // Register `components` module in lib.rs/main.rs — skip when --path overrides the directory
     // because the custom path may not correspond to any Rust entry file.
-    if !has_path_override {
+    if uses_base_path && !has_path_override {
         let components_path = Path::new(&base_path);
         let parent_path = components_path
             .parent()
@@ -252,7 +324,8 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
         };
 
         let entry_file_path = entry_file_path.to_string_lossy().to_string();
-        Components::register_components_in_application_entry(entry_file_path.as_str())?;
+        // Exported when a category mapped into another crate imports the components by crate name
+        Components::register_components_in_application_entry(entry_file_path.as_str(), dirs.spans_crates())?;
     }
 
     // Components to add
@@ -267,9 +340,14 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
             continue;
         }
 
-        let outcome = RegistryComponent::fetch_from_registry(component_name.clone())
+        let category = tree_parser.category_of(&component_name);
+        let outcome = RegistryComponent::fetch_from_registry(
+            component_name.clone(),
+            category,
+            tree_parser.files_of(&component_name),
+        )
             .await?
-            .then_write_to_file_to(force, &base_path)
+            .then_write_to_file_to(force, &dirs, &aliases, format)
             .await?;
 
         match outcome {
@@ -280,9 +358,17 @@ pub async fn process_add(matches: &ArgMatches) -> CliResult<()> {
 
     print_add_summary(&written, &skipped, &already_installed);
 
-    // Handle cargo dependencies if any exist
+    // Make sure Tailwind scans the components directories
+    ensure_tailwind_sources(&dirs.source_dirs())?;
+
+    // Apply the Cargo.toml changes confirmed above
     if !all_resolved_cargo_dependencies.is_empty() {
-        super::dependencies::process_cargo_deps(&all_resolved_cargo_dependencies)?;
+        apply_cargo_deps_plan(&cargo_plan, &workspace_info)?;
+    }
+
+    // Install npm packages with the project's package manager
+    if !all_resolved_npm_deps.is_empty() {
+        process_npm_deps(&all_resolved_npm_deps)?;
     }
 
     // Handle JS file dependencies if any exist
@@ -302,6 +388,7 @@ struct DryRunSummary {
     would_overwrite: Vec<String>,
     already_installed: Vec<String>,
     cargo_deps: Vec<String>,
+    npm_deps: Vec<String>,
     js_files: Vec<String>,
 }
 
@@ -310,6 +397,7 @@ fn compute_dry_run_summary(
     installed: &HashSet<String>,
     user_requested: &HashSet<String>,
     cargo_deps: &[String],
+    npm_deps: &HashSet<String>,
     js_files: &HashSet<String>,
 ) -> DryRunSummary {
     let mut would_add = Vec::new();
@@ -334,10 +422,13 @@ fn compute_dry_run_summary(
     let mut cargo_deps = cargo_deps.to_vec();
     cargo_deps.sort();
 
+    let mut npm_deps: Vec<String> = npm_deps.iter().cloned().collect();
+    npm_deps.sort();
+
     let mut js_files: Vec<String> = js_files.iter().cloned().collect();
     js_files.sort();
 
-    DryRunSummary { would_add, would_overwrite, already_installed, cargo_deps, js_files }
+    DryRunSummary { would_add, would_overwrite, already_installed, cargo_deps, npm_deps, js_files }
 }
 
 fn format_dry_run_summary(s: &DryRunSummary) -> String {
@@ -355,6 +446,9 @@ fn format_dry_run_summary(s: &DryRunSummary) -> String {
     if !s.cargo_deps.is_empty() {
         lines.push(format!("[dry-run] Would add cargo deps:   {}", s.cargo_deps.join(", ")));
     }
+    if !s.npm_deps.is_empty() {
+        lines.push(format!("[dry-run] Would add npm packages: {}", s.npm_deps.join(", ")));
+    }
     if !s.js_files.is_empty() {
         lines.push(format!("[dry-run] Would install JS files: {}", s.js_files.join(", ")));
     }
@@ -502,6 +596,7 @@ mod tests {
             &make_set(installed),
             &make_set(requested),
             &cargo.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            &HashSet::new(),
             &make_set(js),
         )
     }
@@ -631,6 +726,22 @@ mod tests {
         assert!(m.get_flag("view"));
         assert!(!m.get_flag("diff"));
     }
+
+    #[test]
+    fn command_add_package_flag_is_registered() {
+        let m = command_add().try_get_matches_from(["add", "button", "--package", "admin"]).unwrap();
+        assert_eq!(m.get_one::<String>("package").map(String::as_str), Some("admin"));
+    }
+}
+
+/// Install npm packages components depend on, with the package manager `ui init` resolves.
+fn process_npm_deps(npm_deps: &HashSet<String>) -> CliResult<()> {
+    let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML).ok();
+    let configured = config.and_then(|config| config.package_manager);
+    let package_manager = PackageManager::resolve(None, configured.as_deref())?;
+    let mut packages: Vec<&str> = npm_deps.iter().map(String::as_str).collect();
+    packages.sort();
+    install_packages("component", &packages, package_manager)
 }
 
 /// Download and install JS files to the user's public directory
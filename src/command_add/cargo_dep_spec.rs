@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::registry_index::IndexCargoDependency;
+use crate::shared::cli_error::{CliError, CliResult};
+
+/// A `cargo:` dependency of a component: `name[@version][[feature1,feature2]]`, optionally
+/// followed by `optional` and / or `no-default-features` (the `cargo add` flags), e.g.
+/// `strum@0.27[derive]` or `leptos-use@0.16[storage] optional no-default-features`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoDepSpec {
+    pub name: String,
+    pub version: Option<String>,
+    pub features: Vec<String>,
+    pub optional: bool,
+    pub default_features: bool,
+}
+
+impl CargoDepSpec {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: None,
+            features: Vec::new(),
+            optional: false,
+            default_features: true,
+        }
+    }
+
+    pub fn parse(spec: &str) -> CliResult<Self> {
+        let invalid =
+            |reason: &str| CliError::validation(&format!("Invalid cargo dependency '{spec}': {reason}"));
+
+        let spec_trimmed = spec.trim();
+        // Features may contain spaces after commas, so the crate part ends at `]` when there is one
+        let (crate_part, flags) = match spec_trimmed.find(']') {
+            Some(end) if spec_trimmed[..end].contains('[') => spec_trimmed.split_at(end + 1),
+            _ => spec_trimmed.split_once(char::is_whitespace).unwrap_or((spec_trimmed, "")),
+        };
+        if crate_part.is_empty() {
+            return Err(invalid("empty"));
+        }
+
+        let (crate_part, features) = match crate_part.split_once('[') {
+            Some((crate_part, features)) => {
+                let features = features.strip_suffix(']').ok_or_else(|| invalid("missing `]`"))?;
+                let features: Vec<String> = features
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if let Some(feature) = features.iter().find(|f| !is_valid_name(f.trim_start_matches("dep:")))
+                {
+                    return Err(invalid(&format!("invalid feature '{feature}'")));
+                }
+                (crate_part, features)
+            }
+            None => (crate_part, Vec::new()),
+        };
+
+        let (name, version) = match crate_part.split_once('@') {
+            Some((_, "")) => return Err(invalid("empty version")),
+            Some((name, version)) => (name, Some(version.to_string())),
+            None => (crate_part, None),
+        };
+        if !is_valid_name(name) {
+            return Err(invalid("invalid crate name"));
+        }
+
+        let mut parsed = Self { features, version, ..Self::new(name) };
+        for flag in flags.split_whitespace() {
+            match flag {
+                "optional" => parsed.optional = true,
+                "no-default-features" => parsed.default_features = false,
+                _ => return Err(invalid(&format!("unknown flag '{flag}'"))),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Combine the requirements of two components on the same crate: every feature either needs,
+    /// optional and without default features only when both ask for it.
+    pub fn merge(&mut self, other: &Self) {
+        if self.version.is_none() {
+            self.version = other.version.clone();
+        }
+        for feature in &other.features {
+            if !self.features.contains(feature) {
+                self.features.push(feature.clone());
+            }
+        }
+        self.optional &= other.optional;
+        self.default_features |= other.default_features;
+    }
+
+    /// `cargo add` arguments for the crate: `name[@version]` and its flags.
+    pub fn cargo_add_args(&self) -> Vec<String> {
+        let mut args = vec![match &self.version {
+            Some(version) => format!("{}@{version}", self.name),
+            None => self.name.clone(),
+        }];
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.optional {
+            args.push("--optional".to_string());
+        }
+        if !self.default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args
+    }
+}
+
+impl fmt::Display for CargoDepSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{version}")?;
+        }
+        if !self.features.is_empty() {
+            write!(f, "[{}]", self.features.join(","))?;
+        }
+        if self.optional {
+            write!(f, " optional")?;
+        }
+        if !self.default_features {
+            write!(f, " no-default-features")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&IndexCargoDependency> for CargoDepSpec {
+    fn from(dep: &IndexCargoDependency) -> Self {
+        Self {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            features: dep.features.clone(),
+            optional: dep.optional,
+            default_features: dep.default_features,
+        }
+    }
+}
+
+/// Parse `specs` and merge those naming the same crate, sorted by crate name.
+pub fn merge_specs(specs: &[String]) -> CliResult<Vec<CargoDepSpec>> {
+    let mut by_name: BTreeMap<String, CargoDepSpec> = BTreeMap::new();
+    for spec in specs {
+        let spec = CargoDepSpec::parse(spec)?;
+        match by_name.get_mut(&spec.name) {
+            Some(existing) => existing.merge(&spec),
+            None => {
+                by_name.insert(spec.name.clone(), spec);
+            }
+        }
+    }
+    Ok(by_name.into_values().collect())
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_name_version_features_and_flags() {
+        assert_eq!(CargoDepSpec::parse("strum").unwrap(), CargoDepSpec::new("strum"));
+
+        let spec = CargoDepSpec::parse("strum@0.27[derive, phf]").unwrap();
+        assert_eq!(spec.version.as_deref(), Some("0.27"));
+        assert_eq!(spec.features, vec!["derive", "phf"]);
+        assert!(!spec.optional && spec.default_features);
+
+        let spec = CargoDepSpec::parse("leptos-use[storage] optional no-default-features").unwrap();
+        assert_eq!(spec.version, None);
+        assert_eq!(spec.features, vec!["storage"]);
+        assert!(spec.optional && !spec.default_features);
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        for spec in ["", "strum@", "strum[derive", "str um", "strum optionnal", "strum[de rive]", "@0.27"] {
+            assert!(CargoDepSpec::parse(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for spec in
+            ["strum", "strum@0.27[derive,phf]", "leptos-use@0.16[storage] optional no-default-features"]
+        {
+            assert_eq!(CargoDepSpec::parse(spec).unwrap().to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn cargo_add_args_carry_version_and_flags() {
+        let spec = CargoDepSpec::parse("strum@0.27[derive,phf] optional no-default-features").unwrap();
+        assert_eq!(
+            spec.cargo_add_args(),
+            vec!["strum@0.27", "--features", "derive,phf", "--optional", "--no-default-features"]
+        );
+        assert_eq!(CargoDepSpec::new("serde").cargo_add_args(), vec!["serde"]);
+    }
+
+    #[test]
+    fn merge_specs_unions_features_per_crate() {
+        let specs = ["strum[derive]", "serde", "strum@0.27[phf] optional", "strum"].map(String::from);
+        let merged = merge_specs(&specs).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], CargoDepSpec::new("serde"));
+        assert_eq!(merged[1].to_string(), "strum@0.27[derive,phf]");
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use strum::IntoEnumIterator;
+
+use super::component_type::ComponentType;
+use super::import_paths::module_path_from;
+use crate::command_init::config::{ComponentPaths, UiConfig};
+
+/// Where each category of components is installed: the `[paths]` directory of the category,
+/// else `<base_path>/<category>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDirs {
+    pub base_path: String,
+    paths: ComponentPaths,
+}
+
+impl ComponentDirs {
+    pub fn new(base_path: &str, paths: &ComponentPaths) -> Self {
+        Self { base_path: base_path.to_string(), paths: paths.clone() }
+    }
+
+    /// Every category under `base_path`, as with `--path`.
+    pub fn from_base_path(base_path: &str) -> Self {
+        Self::new(base_path, &ComponentPaths::default())
+    }
+
+    pub fn from_config(config: &UiConfig) -> Self {
+        Self::new(&config.base_path_components, &config.paths)
+    }
+
+    pub fn dir(&self, category: &str) -> PathBuf {
+        self.custom_dir(category).unwrap_or_else(|| Path::new(&self.base_path).join(category))
+    }
+
+    /// The `[paths]` directory of `category` (`ui`, `demos`, `blocks`, ...), when it has one.
+    pub fn custom_dir(&self, category: &str) -> Option<PathBuf> {
+        self.paths.category(category).map(PathBuf::from)
+    }
+
+    pub fn component_file(&self, component_name: &str, category: &str) -> PathBuf {
+        self.dir(category).join(format!("{component_name}.rs"))
+    }
+
+    /// Categories to look for installed components in: the built-in ones, those mapped in
+    /// `[paths]` and `registry_categories`.
+    pub fn categories(&self, registry_categories: &[String]) -> Vec<String> {
+        let mut categories: Vec<String> =
+            ComponentType::iter().map(|component_type| component_type.to_path()).collect();
+        for category in self.paths.categories().into_iter().chain(registry_categories.iter().cloned()) {
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+        categories
+    }
+
+    /// `categories` without a registry at hand: every subdirectory of `base_path` counts as one.
+    pub fn local_categories(&self) -> Vec<String> {
+        let mut subdirs: Vec<String> = std::fs::read_dir(&self.base_path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_dir())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        subdirs.sort();
+        self.categories(&subdirs)
+    }
+
+    /// Whether a `[paths]` directory sits in another crate than `base_path`: that crate imports
+    /// the components by crate name, so they have to be public.
+    pub fn spans_crates(&self) -> bool {
+        let base_path = Path::new(&self.base_path);
+        self.paths.categories().iter().filter_map(|category| self.custom_dir(category)).any(|dir| {
+            module_path_from(base_path, &dir)
+                .is_some_and(|path| path != "crate" && !path.starts_with("crate::"))
+        })
+    }
+
+    /// `base_path` and the `[paths]` directories outside it: what Tailwind has to scan.
+    pub fn source_dirs(&self) -> Vec<PathBuf> {
+        let base_path = PathBuf::from(&self.base_path);
+        let mut dirs = vec![base_path.clone()];
+        for dir in self.paths.categories().iter().filter_map(|category| self.custom_dir(category)) {
+            if !dir.starts_with(&base_path) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dirs() -> ComponentDirs {
+        let paths = ComponentPaths {
+            demos: Some("examples/src/demos".to_string()),
+            hooks: Some("src/hooks".to_string()),
+            extensions: Some("src/components/ext".to_string()),
+            ..ComponentPaths::default()
+        };
+        ComponentDirs::new("src/components", &paths)
+    }
+
+    #[test]
+    fn categories_without_a_path_go_under_the_base_path() {
+        let dirs = dirs();
+        assert_eq!(dirs.dir("ui"), PathBuf::from("src/components/ui"));
+        assert_eq!(dirs.custom_dir("ui"), None);
+        assert_eq!(dirs.component_file("button", "ui"), PathBuf::from("src/components/ui/button.rs"));
+        assert_eq!(dirs.component_file("hero", "blocks"), PathBuf::from("src/components/blocks/hero.rs"));
+    }
+
+    #[test]
+    fn mapped_categories_go_to_their_directory() {
+        let dirs = dirs();
+        assert_eq!(
+            dirs.component_file("demo_button", "demos"),
+            PathBuf::from("examples/src/demos/demo_button.rs")
+        );
+        assert_eq!(dirs.component_file("use_random", "hooks"), PathBuf::from("src/hooks/use_random.rs"));
+        assert_eq!(dirs.custom_dir("hooks"), Some(PathBuf::from("src/hooks")));
+        assert_eq!(dirs.custom_dir("unknown"), None);
+    }
+
+    #[test]
+    fn registry_categories_can_be_mapped_too() {
+        let paths: ComponentPaths = toml::from_str("blocks = \"src/blocks\"\nhooks = \"src/hooks\"").unwrap();
+        let dirs = ComponentDirs::new("src/components", &paths);
+        assert_eq!(dirs.component_file("hero", "blocks"), PathBuf::from("src/blocks/hero.rs"));
+        assert_eq!(dirs.dir("charts"), PathBuf::from("src/components/charts"));
+        assert_eq!(
+            dirs.categories(&["charts".to_string(), "ui".to_string()]),
+            vec!["ui", "demos", "hooks", "extensions", "blocks", "charts"]
+        );
+    }
+
+    #[test]
+    fn local_categories_include_subdirectories_of_the_base_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("blocks")).unwrap();
+        std::fs::create_dir_all(dir.path().join("ui")).unwrap();
+        std::fs::write(dir.path().join("mod.rs"), "").unwrap();
+        let dirs = ComponentDirs::from_base_path(&dir.path().to_string_lossy());
+        assert_eq!(dirs.local_categories(), vec!["ui", "demos", "hooks", "extensions", "blocks"]);
+    }
+
+    #[test]
+    fn source_dirs_skip_directories_inside_the_base_path() {
+        assert_eq!(
+            dirs().source_dirs(),
+            vec![
+                PathBuf::from("src/components"),
+                PathBuf::from("examples/src/demos"),
+                PathBuf::from("src/hooks")
+            ]
+        );
+        assert_eq!(ComponentDirs::from_base_path("src/ui").source_dirs(), vec![PathBuf::from("src/ui")]);
+    }
+
+    #[test]
+    fn spans_crates_when_a_category_lives_in_another_crate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let examples = dir.path().join("examples");
+        std::fs::create_dir_all(&examples).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"my-ui\"\n").unwrap();
+        std::fs::write(examples.join("Cargo.toml"), "[package]\nname = \"examples\"\n").unwrap();
+        let base_path = dir.path().join("src/components").to_string_lossy().to_string();
+
+        let same_crate = ComponentPaths {
+            hooks: Some(dir.path().join("src/hooks").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(!ComponentDirs::new(&base_path, &same_crate).spans_crates());
+
+        let other_crate = ComponentPaths {
+            demos: Some(examples.join("src/demos").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(ComponentDirs::new(&base_path, &other_crate).spans_crates());
+        assert!(!ComponentDirs::from_base_path(&base_path).spans_crates());
+    }
+}
@@ -1,5 +1,5 @@
-use std::io::Write;
-
+use super::component_dirs::ComponentDirs;
+use super::mod_items::{declare_module_dir, insert_mod_item_in_file};
 use crate::shared::cli_error::{CliError, CliResult};
 
 /* ========================================================== */
@@ -24,43 +24,35 @@ impl Components {
             .ok_or_else(|| CliError::file_operation("Failed to get parent directory"))?;
         std::fs::create_dir_all(dir).map_err(|_| CliError::directory_create_failed())?;
 
-        // Initialize mod_content
-        let mut mod_content = String::new();
+        let parent_dirs: Vec<&str> = parent_dirs.iter().map(String::as_str).collect();
+        insert_mod_item_in_file(&components_mod_path, &parent_dirs, true)?;
+        Ok(())
+    }
 
-        // Check if the mod.rs file already exists
-        if components_mod_path.exists() {
-            mod_content =
-                std::fs::read_to_string(&components_mod_path).map_err(|_| CliError::file_read_failed())?;
+    /// Declare the module of each category: in `<base_path>/mod.rs` for categories installed under
+    /// it, in their parent module for categories `[paths]` maps elsewhere. Returns whether any
+    /// category lives under the base path.
+    pub fn declare_category_modules(dirs: &ComponentDirs, categories: Vec<String>) -> CliResult<bool> {
+        let (mapped, under_base_path): (Vec<String>, Vec<String>) =
+            categories.into_iter().partition(|category| dirs.custom_dir(category).is_some());
+        for dir in mapped.iter().filter_map(|category| dirs.custom_dir(category)) {
+            declare_module_dir(&dir)?;
         }
-
-        // Create or open the mod.rs file for writing
-        let mut mod_rs_file = std::fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&components_mod_path)
-            .map_err(|_| CliError::file_operation("Failed to open mod.rs file"))?;
-
-        // Add each parent directory as a module if it doesn't already exist
-        for parent_dir in parent_dirs {
-            if !mod_content.contains(&format!("pub mod {parent_dir};")) {
-                writeln!(mod_rs_file, "pub mod {parent_dir};").map_err(|_| CliError::file_write_failed())?;
-            }
+        if under_base_path.is_empty() {
+            return Ok(false);
         }
-        Ok(())
+        Self::create_components_mod_if_not_exists_with_pub_mods(dirs.base_path.clone(), under_base_path)?;
+        Ok(true)
     }
 
-    pub fn register_components_in_application_entry(entry_file_path: &str) -> CliResult<()> {
-        let file_content =
-            std::fs::read_to_string(entry_file_path).map_err(|_| CliError::file_read_failed())?;
-
-        const MOD_COMPONENTS: &str = "mod components;";
-
-        if file_content.contains(MOD_COMPONENTS) {
-            return Ok(());
+    /// Declare `mod components;` in the crate entry, `pub mod components;` when `is_pub`.
+    pub fn register_components_in_application_entry(entry_file_path: &str, is_pub: bool) -> CliResult<()> {
+        let entry_file = std::path::Path::new(entry_file_path);
+        if !entry_file.exists() {
+            return Err(CliError::file_read_failed());
         }
-        let mod_components_import = format!("{MOD_COMPONENTS}\n{file_content}");
-        std::fs::write(entry_file_path, mod_components_import.as_bytes())
-            .map_err(|_| CliError::file_write_failed())?;
+        // `mod components;` goes after inner attributes, in sorted position among other modules
+        insert_mod_item_in_file(entry_file, &["components"], is_pub)?;
         Ok(())
     }
 }
@@ -83,7 +75,7 @@ mod tests {
         let path = tmp.path().join("main.rs");
         std::fs::write(&path, "fn main() {}").unwrap();
 
-        Components::register_components_in_application_entry(path.to_str().unwrap()).unwrap();
+        Components::register_components_in_application_entry(path.to_str().unwrap(), false).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert!(content.starts_with("mod components;"));
@@ -96,20 +88,32 @@ mod tests {
         let path = tmp.path().join("main.rs");
         std::fs::write(&path, "mod components;\nfn main() {}").unwrap();
 
-        Components::register_components_in_application_entry(path.to_str().unwrap()).unwrap();
+        Components::register_components_in_application_entry(path.to_str().unwrap(), false).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(content.matches("mod components;").count(), 1);
     }
 
+    #[test]
+    fn inserts_mod_components_after_inner_attributes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("lib.rs");
+        std::fs::write(&path, "#![recursion_limit = \"256\"]\n\npub mod app;\n").unwrap();
+
+        Components::register_components_in_application_entry(path.to_str().unwrap(), false).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "#![recursion_limit = \"256\"]\n\npub mod app;\nmod components;\n");
+    }
+
     #[test]
     fn is_idempotent() {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("lib.rs");
         std::fs::write(&path, "pub fn foo() {}").unwrap();
 
-        Components::register_components_in_application_entry(path.to_str().unwrap()).unwrap();
-        Components::register_components_in_application_entry(path.to_str().unwrap()).unwrap();
+        Components::register_components_in_application_entry(path.to_str().unwrap(), false).unwrap();
+        Components::register_components_in_application_entry(path.to_str().unwrap(), false).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(content.matches("mod components;").count(), 1);
@@ -1,68 +1,180 @@
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use cargo_toml::Manifest;
-use toml_edit::{DocumentMut, Item, Value};
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Value};
 
-use crate::command_init::workspace_utils::{WorkspaceInfo, analyze_workspace};
+use super::cargo_dep_spec::{CargoDepSpec, merge_specs};
+use crate::command_init::crate_versions::resolve_crate_version;
+use crate::command_init::dry_run::{FileChange, format_file_diff};
+use crate::command_init::workspace_utils::{WorkspaceInfo, existing_dependency_names};
 use crate::shared::cli_error::{CliError, CliResult};
 use crate::shared::task_spinner::TaskSpinner;
 
-pub fn process_cargo_deps(cargo_deps: &[String]) -> CliResult<()> {
-    let spinner = TaskSpinner::new("Checking dependencies...");
+/// What `process_cargo_deps` would do, computed without touching the project.
+#[derive(Debug, Clone, Default)]
+pub struct CargoDepsPlan {
+    /// Manifests that change: the target crate's `Cargo.toml` and, with `[workspace.dependencies]`,
+    /// the workspace root's.
+    pub changes: Vec<ManifestChange>,
+    /// Crates without a version known offline, added with `cargo add` once the changes are written.
+    pub cargo_add: Vec<CargoDepSpec>,
+    pub added: Vec<String>,
+    /// Crates already declared that get the features they miss.
+    pub extended: Vec<String>,
+    /// Crates already declared with every feature needed.
+    pub skipped: Vec<String>,
+}
 
-    // Detect workspace to determine how to add dependencies
-    let workspace_info = analyze_workspace().ok();
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestChange {
+    pub path: PathBuf,
+    pub file: FileChange,
+}
 
-    // Get existing dependencies from the target Cargo.toml
-    let existing_deps = get_existing_dependencies(&workspace_info)?;
+impl CargoDepsPlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.cargo_add.is_empty()
+    }
+}
 
-    // Filter out dependencies that already exist
-    let (new_deps, existing_deps_found): (Vec<_>, Vec<_>) =
-        cargo_deps.iter().partition(|dep| !existing_deps.contains(*dep));
+/// Add `cargo_deps` (`cargo:` specs, see `CargoDepSpec`) to the crate described by `workspace_info`
+/// (see `analyze_workspace`); crates it already declares get the features they miss.
+/// The manifest changes are shown first and applied once confirmed, or right away with `yes`.
+pub fn process_cargo_deps(
+    cargo_deps: &[String],
+    workspace_info: &Option<WorkspaceInfo>,
+    yes: bool,
+) -> CliResult<()> {
+    let plan = plan_cargo_deps(cargo_deps, workspace_info)?;
+
+    if !confirm_cargo_deps_plan(&plan, yes)? {
+        println!("⏭️  Cargo.toml left unchanged; add the crates above yourself.");
+        return Ok(());
+    }
 
-    if !existing_deps_found.is_empty() {
-        let existing_str = existing_deps_found.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ");
-        spinner.set_message(&format!("⏭️  Skipping existing dependencies: [{existing_str}]"));
+    apply_cargo_deps_plan(&plan, workspace_info)
+}
+
+/// Show the manifest changes of `plan` and ask whether to apply them; `yes` answers for the user.
+/// An empty plan is accepted without asking.
+pub fn confirm_cargo_deps_plan(plan: &CargoDepsPlan, yes: bool) -> CliResult<bool> {
+    if plan.is_empty() {
+        return Ok(true);
+    }
+
+    println!("{}", format_cargo_deps_plan(plan, ""));
+    if yes {
+        return Ok(true);
     }
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Apply these Cargo.toml changes?")
+        .default(true)
+        .interact()
+        .map_err(|err| CliError::validation(&format!("Failed to get user input: {err}")))
+}
 
-    if new_deps.is_empty() {
-        spinner.finish_with_message("All dependencies already exist in Cargo.toml");
+/// Write the manifests of `plan`, then run `cargo add` for the crates left to it.
+pub fn apply_cargo_deps_plan(plan: &CargoDepsPlan, workspace_info: &Option<WorkspaceInfo>) -> CliResult<()> {
+    if plan.is_empty() {
+        TaskSpinner::new("Checking dependencies...")
+            .finish_with_message("All dependencies already exist in Cargo.toml");
         return Ok(());
     }
 
-    spinner.set_message("Adding new crates to Cargo.toml...");
+    let spinner = TaskSpinner::new("Adding crates to Cargo.toml...");
+
+    for change in &plan.changes {
+        fs::write(&change.path, &change.file.after)?;
+    }
+
+    for spec in &plan.cargo_add {
+        spinner.set_message(&format!("📦 Adding crate: {spec}"));
+        add_dependency_with_cargo(spec, workspace_info)?;
+    }
+
+    let mut finish_message = String::new();
+    if !plan.added.is_empty() {
+        finish_message.push_str(&format!("Successfully added to Cargo.toml: [{}] !", plan.added.join(", ")));
+    }
+    if !plan.extended.is_empty() {
+        let extended_str = plan.extended.join(", ");
+        finish_message.push_str(&format!(" Added missing features: [{extended_str}]"));
+    }
+    spinner.finish_success(finish_message.trim_start());
+
+    Ok(())
+}
+
+/// The manifest changes adding `cargo_deps` would make (see `process_cargo_deps`).
+pub fn plan_cargo_deps(
+    cargo_deps: &[String],
+    workspace_info: &Option<WorkspaceInfo>,
+) -> CliResult<CargoDepsPlan> {
+    let specs = merge_specs(cargo_deps)?;
+
+    // Get existing dependencies from the target Cargo.toml
+    let existing_deps = get_existing_dependencies(workspace_info)?;
 
     // Check if we should use workspace dependencies
-    let use_workspace_deps = should_use_workspace_deps(&workspace_info);
+    let use_workspace_deps = should_use_workspace_deps(workspace_info);
 
-    let mut added_deps = Vec::new();
+    let mut plan = CargoDepsPlan::default();
+    let mut manifests = Manifests::default();
 
-    for dep in &new_deps {
-        spinner.set_message(&format!("📦 Adding crate: {dep}"));
+    for spec in &specs {
+        if existing_deps.contains(&spec.name) {
+            if extend_dependency_features(&mut manifests, spec, workspace_info)? {
+                plan.extended.push(spec.name.clone());
+            } else {
+                plan.skipped.push(spec.name.clone());
+            }
+            continue;
+        }
 
-        let result = if use_workspace_deps {
+        if use_workspace_deps {
             // Safe: use_workspace_deps is only true when workspace_info is Some with valid data
             let Some(info) = workspace_info.as_ref() else {
                 return Err(CliError::cargo_operation("Workspace info unavailable"));
             };
-            add_workspace_dependency(dep, info)
+            if !add_workspace_dependency(&mut manifests, spec, info)? {
+                plan.cargo_add.push(spec.clone());
+            }
         } else {
-            add_dependency_with_cargo(dep, &workspace_info)
-        };
-
-        match result {
-            Ok(()) => added_deps.push(dep.as_str()),
-            Err(e) => return Err(e),
+            let crate_dir = target_crate_dir(workspace_info);
+            match resolve_crate_version(&spec.name, &crate_dir, spec.version.as_deref()) {
+                Ok(version) => {
+                    let doc = manifests.doc_mut(&crate_dir.join("Cargo.toml"))?;
+                    add_to_dependencies(doc, spec, &version)?;
+                }
+                // cargo add picks the version (from the network) when none is known locally
+                Err(_) => plan.cargo_add.push(spec.clone()),
+            }
         }
+        plan.added.push(spec.name.clone());
     }
 
-    let dependencies_str = added_deps.join(", ");
-    let finish_message = format!("Successfully added to Cargo.toml: [{dependencies_str}] !");
-    spinner.finish_success(&finish_message);
+    plan.changes = manifests.into_changes();
+    Ok(plan)
+}
 
-    Ok(())
+/// Manifest diffs and `cargo add` commands of `plan`, each line of the headers led by `prefix`
+/// (`[dry-run] ` for `ui add --dry-run`).
+pub fn format_cargo_deps_plan(plan: &CargoDepsPlan, prefix: &str) -> String {
+    let mut lines = Vec::new();
+    for change in &plan.changes {
+        lines.push(format!("{prefix}Cargo.toml changes ({}):", change.file.path));
+        lines.push(format_file_diff(&change.file));
+    }
+    for spec in &plan.cargo_add {
+        lines.push(format!("{prefix}Would run: cargo add {}", spec.cargo_add_args().join(" ")));
+    }
+    if !plan.skipped.is_empty() {
+        lines.push(format!("{prefix}Cargo deps already present: {}", plan.skipped.join(", ")));
+    }
+    lines.join("\n")
 }
 
 /* ========================================================== */
@@ -102,9 +214,16 @@ fn should_use_workspace_deps(workspace_info: &Option<WorkspaceInfo>) -> bool {
 }
 
 /// Add dependency using workspace pattern:
-/// 1. Add to [workspace.dependencies] in root Cargo.toml
-/// 2. Add dep.workspace = true to member Cargo.toml
-fn add_workspace_dependency(dep: &str, info: &WorkspaceInfo) -> CliResult<()> {
+/// 1. Add to [workspace.dependencies] in root Cargo.toml (version, features, default-features)
+/// 2. Add dep.workspace = true to member Cargo.toml (with `optional` and the features the
+///    workspace entry lacks)
+///
+/// Returns `false`, leaving both untouched, when no version of the crate is known offline.
+fn add_workspace_dependency(
+    manifests: &mut Manifests,
+    spec: &CargoDepSpec,
+    info: &WorkspaceInfo,
+) -> CliResult<bool> {
     let workspace_root =
         info.workspace_root.as_ref().ok_or_else(|| CliError::cargo_operation("Workspace root not found"))?;
 
@@ -113,27 +232,31 @@ fn add_workspace_dependency(dep: &str, info: &WorkspaceInfo) -> CliResult<()> {
         .as_ref()
         .ok_or_else(|| CliError::cargo_operation("Target crate path not found"))?;
 
-    // First, get the latest version from crates.io
-    let version = fetch_latest_version(dep)?;
-
-    // Add to workspace root [workspace.dependencies]
     let root_cargo_toml = workspace_root.join("Cargo.toml");
-    add_to_workspace_dependencies(&root_cargo_toml, dep, &version)?;
+    let root_doc = manifests.doc_mut(&root_cargo_toml)?;
+    let workspace_features = match workspace_dependency_features(root_doc, &spec.name) {
+        Some(features) => features,
+        None => {
+            // First, get the version to require: locked or cached locally, else the spec's
+            let Ok(version) = resolve_crate_version(&spec.name, member_path, spec.version.as_deref()) else {
+                return Ok(false);
+            };
+
+            // Add to workspace root [workspace.dependencies]
+            add_to_workspace_dependencies(root_doc, spec, &version)?;
+            spec.features.clone()
+        }
+    };
 
     // Add to member [dependencies] with workspace = true
     let member_cargo_toml = member_path.join("Cargo.toml");
-    add_workspace_ref_to_member(&member_cargo_toml, dep)?;
+    add_workspace_ref_to_member(manifests.doc_mut(&member_cargo_toml)?, spec, &workspace_features)?;
 
-    Ok(())
+    Ok(true)
 }
 
 /// Add dependency to [workspace.dependencies] in root Cargo.toml
-fn add_to_workspace_dependencies(cargo_toml_path: &Path, dep: &str, version: &str) -> CliResult<()> {
-    let contents = fs::read_to_string(cargo_toml_path)?;
-    let mut doc: DocumentMut = contents
-        .parse()
-        .map_err(|e| CliError::cargo_operation(&format!("Failed to parse Cargo.toml: {e}")))?;
-
+fn add_to_workspace_dependencies(doc: &mut DocumentMut, spec: &CargoDepSpec, version: &str) -> CliResult<()> {
     // Get or create [workspace.dependencies]
     let workspace = doc.entry("workspace").or_insert(Item::Table(toml_edit::Table::new()));
 
@@ -147,26 +270,38 @@ fn add_to_workspace_dependencies(cargo_toml_path: &Path, dep: &str, version: &st
         .ok_or_else(|| CliError::cargo_operation("[workspace.dependencies] is not a table"))?;
 
     // Check if already exists
-    if deps_table.contains_key(dep) {
+    if deps_table.contains_key(&spec.name) {
         return Ok(());
     }
 
-    // Add the dependency with version
-    deps_table.insert(dep, Item::Value(Value::String(toml_edit::Formatted::new(version.to_string()))));
-
-    // Write back
-    fs::write(cargo_toml_path, doc.to_string())?;
+    // Add the dependency with version; `optional` belongs to the members
+    deps_table.insert(&spec.name, dependency_item(spec, version, false));
 
     Ok(())
 }
 
-/// Add dep.workspace = true to member's [dependencies]
-fn add_workspace_ref_to_member(cargo_toml_path: &Path, dep: &str) -> CliResult<()> {
-    let contents = fs::read_to_string(cargo_toml_path)?;
-    let mut doc: DocumentMut = contents
-        .parse()
-        .map_err(|e| CliError::cargo_operation(&format!("Failed to parse member Cargo.toml: {e}")))?;
+/// Add dependency to the [dependencies] of a crate that does not use the workspace pattern
+fn add_to_dependencies(doc: &mut DocumentMut, spec: &CargoDepSpec, version: &str) -> CliResult<()> {
+    // Get or create [dependencies]
+    let deps = doc.entry("dependencies").or_insert(Item::Table(toml_edit::Table::new()));
 
+    let deps_table =
+        deps.as_table_mut().ok_or_else(|| CliError::cargo_operation("[dependencies] is not a table"))?;
+
+    if !deps_table.contains_key(&spec.name) {
+        deps_table.insert(&spec.name, dependency_item(spec, version, spec.optional));
+    }
+
+    Ok(())
+}
+
+/// Add dep.workspace = true to member's [dependencies], with `optional = true` and the features
+/// of `spec` missing from `workspace_features`.
+fn add_workspace_ref_to_member(
+    doc: &mut DocumentMut,
+    spec: &CargoDepSpec,
+    workspace_features: &[String],
+) -> CliResult<()> {
     // Get or create [dependencies]
     let deps = doc.entry("dependencies").or_insert(Item::Table(toml_edit::Table::new()));
 
@@ -174,63 +309,81 @@ fn add_workspace_ref_to_member(cargo_toml_path: &Path, dep: &str) -> CliResult<(
         deps.as_table_mut().ok_or_else(|| CliError::cargo_operation("[dependencies] is not a table"))?;
 
     // Check if already exists
-    if deps_table.contains_key(dep) {
+    if deps_table.contains_key(&spec.name) {
         return Ok(());
     }
 
-    // Add dep.workspace = true using dotted key format
-    let mut dep_table = toml_edit::Table::new();
-    dep_table.set_dotted(true);
-    dep_table.insert("workspace", Item::Value(Value::Boolean(toml_edit::Formatted::new(true))));
-    deps_table.insert(dep, Item::Table(dep_table));
+    let member_features: Vec<String> =
+        spec.features.iter().filter(|feature| !workspace_features.contains(feature)).cloned().collect();
 
-    // Write back
-    fs::write(cargo_toml_path, doc.to_string())?;
+    if member_features.is_empty() && !spec.optional {
+        // Add dep.workspace = true using dotted key format
+        let mut dep_table = toml_edit::Table::new();
+        dep_table.set_dotted(true);
+        dep_table.insert("workspace", Item::Value(Value::Boolean(toml_edit::Formatted::new(true))));
+        deps_table.insert(&spec.name, Item::Table(dep_table));
+    } else {
+        let mut dep_table = InlineTable::new();
+        dep_table.insert("workspace", Value::from(true));
+        if !member_features.is_empty() {
+            dep_table.insert("features", Value::Array(features_array(&member_features)));
+        }
+        if spec.optional {
+            dep_table.insert("optional", Value::from(true));
+        }
+        deps_table.insert(&spec.name, toml_edit::value(dep_table));
+    }
 
     Ok(())
 }
 
-/// Fetch the latest version of a crate from crates.io
-fn fetch_latest_version(crate_name: &str) -> CliResult<String> {
-    // Use cargo search to get the latest version
-    let output = std::process::Command::new("cargo")
-        .args(["search", crate_name, "--limit", "1"])
-        .output()
-        .map_err(|_| CliError::cargo_operation("Failed to execute cargo search"))?;
-
-    if !output.status.success() {
-        return Err(CliError::cargo_operation(&format!("Failed to search for crate '{crate_name}'")));
+/// Add the features of `spec` that the target crate's `[dependencies]` entry (and, for
+/// `workspace = true`, the `[workspace.dependencies]` entry) lacks. Returns whether the manifest
+/// changed; renamed, target-specific or dev-only declarations are left alone.
+fn extend_dependency_features(
+    manifests: &mut Manifests,
+    spec: &CargoDepSpec,
+    workspace_info: &Option<WorkspaceInfo>,
+) -> CliResult<bool> {
+    if spec.features.is_empty() {
+        return Ok(false);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let cargo_toml_path = target_crate_dir(workspace_info).join("Cargo.toml");
+    if !cargo_toml_path.exists() {
+        return Ok(false);
+    }
+    let entry =
+        manifests.doc_mut(&cargo_toml_path)?.get("dependencies").and_then(|deps| deps.get(&spec.name));
+    let Some(entry) = entry else {
+        return Ok(false);
+    };
 
-    // Parse output like: serde = "1.0.219"   # A generic serialization/deserialization framework
-    for line in stdout.lines() {
-        if line.starts_with(crate_name) {
-            // Extract version from format: crate_name = "version"
-            if let Some(version_part) = line.split('=').nth(1) {
-                let version = version_part
-                    .trim()
-                    .trim_matches('"')
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("")
-                    .trim_matches('"');
+    let mut declared = declared_features(entry);
+    if entry.get("workspace").and_then(Item::as_bool) == Some(true)
+        && let Some(workspace_root) = workspace_info.as_ref().and_then(|info| info.workspace_root.as_ref())
+    {
+        let root_doc = manifests.doc_mut(&workspace_root.join("Cargo.toml"))?;
+        declared.extend(workspace_dependency_features(root_doc, &spec.name).unwrap_or_default());
+    }
 
-                if !version.is_empty() {
-                    return Ok(version.to_string());
-                }
-            }
-        }
+    let missing: Vec<String> =
+        spec.features.iter().filter(|feature| !declared.contains(feature)).cloned().collect();
+    if missing.is_empty() {
+        return Ok(false);
     }
 
-    // Fallback: use "*" if we can't determine version
-    Ok("*".to_string())
+    let deps = manifests.doc_mut(&cargo_toml_path)?.get_mut("dependencies").and_then(Item::as_table_like_mut);
+    let entry = deps.and_then(|deps| deps.get_mut(&spec.name)).ok_or_else(|| {
+        CliError::cargo_operation(&format!("'{}' disappeared from {}", spec.name, cargo_toml_path.display()))
+    })?;
+    add_features(entry, &spec.name, &missing)?;
+    Ok(true)
 }
 
 /// Fallback: use cargo add command
-fn add_dependency_with_cargo(dep: &str, workspace_info: &Option<WorkspaceInfo>) -> CliResult<()> {
-    let args = build_cargo_add_args(dep, workspace_info);
+fn add_dependency_with_cargo(spec: &CargoDepSpec, workspace_info: &Option<WorkspaceInfo>) -> CliResult<()> {
+    let args = build_cargo_add_args(spec, workspace_info);
 
     let output = std::process::Command::new("cargo")
         .args(&args)
@@ -241,13 +394,14 @@ fn add_dependency_with_cargo(dep: &str, workspace_info: &Option<WorkspaceInfo>)
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(CliError::cargo_operation(&format!("Failed to add dependency '{dep}': {stderr}")))
+        Err(CliError::cargo_operation(&format!("Failed to add dependency '{spec}': {stderr}")))
     }
 }
 
-/// Build cargo add arguments, adding --package flag for workspaces
-fn build_cargo_add_args(dep: &str, workspace_info: &Option<WorkspaceInfo>) -> Vec<String> {
-    let mut args = vec!["add".to_string(), dep.to_string()];
+/// Build cargo add arguments (version and flags of the spec), adding --package flag for workspaces
+fn build_cargo_add_args(spec: &CargoDepSpec, workspace_info: &Option<WorkspaceInfo>) -> Vec<String> {
+    let mut args = vec!["add".to_string()];
+    args.extend(spec.cargo_add_args());
 
     if let Some(info) = workspace_info.as_ref().filter(|i| i.is_workspace)
         && let Some(crate_name) = &info.target_crate
@@ -259,40 +413,120 @@ fn build_cargo_add_args(dep: &str, workspace_info: &Option<WorkspaceInfo>) -> Ve
     args
 }
 
-/// Check if a crate is already in Cargo.toml dependencies
+/// Check if a crate is already in Cargo.toml dependencies (including renamed and
+/// target-specific ones, see `existing_dependency_names`)
 fn get_existing_dependencies(workspace_info: &Option<WorkspaceInfo>) -> CliResult<HashSet<String>> {
-    // Determine which Cargo.toml to check
-    let cargo_toml_path = if let Some(info) = workspace_info {
-        if let Some(crate_path) = &info.target_crate_path {
-            crate_path.join("Cargo.toml")
-        } else {
-            Path::new("Cargo.toml").to_path_buf()
-        }
-    } else {
-        Path::new("Cargo.toml").to_path_buf()
-    };
+    existing_dependency_names(&target_crate_dir(workspace_info))
+}
 
-    if !cargo_toml_path.exists() {
-        return Ok(HashSet::new());
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// The crate dependencies go to: the target member, else the current directory.
+fn target_crate_dir(workspace_info: &Option<WorkspaceInfo>) -> PathBuf {
+    workspace_info
+        .as_ref()
+        .and_then(|info| info.target_crate_path.clone())
+        .unwrap_or_else(|| Path::new(".").to_path_buf())
+}
+
+/// Manifests edited in memory, with their content on disk: the plan's changes are the difference.
+#[derive(Default)]
+struct Manifests {
+    docs: Vec<(PathBuf, String, DocumentMut)>,
+}
+
+impl Manifests {
+    fn doc_mut(&mut self, cargo_toml_path: &Path) -> CliResult<&mut DocumentMut> {
+        let index = match self.docs.iter().position(|(path, ..)| path == cargo_toml_path) {
+            Some(index) => index,
+            None => {
+                let contents = fs::read_to_string(cargo_toml_path)?;
+                let doc = contents.parse().map_err(|e| {
+                    CliError::cargo_operation(&format!("Failed to parse {}: {e}", cargo_toml_path.display()))
+                })?;
+                self.docs.push((cargo_toml_path.to_path_buf(), contents, doc));
+                self.docs.len() - 1
+            }
+        };
+        self.docs.get_mut(index).map(|(_, _, doc)| doc).ok_or_else(|| {
+            CliError::cargo_operation(&format!("Failed to load {}", cargo_toml_path.display()))
+        })
     }
 
-    // Read the file directly to avoid workspace resolution issues
-    let contents = fs::read_to_string(&cargo_toml_path)?;
-    let manifest = Manifest::from_slice(contents.as_bytes())?;
+    fn into_changes(self) -> Vec<ManifestChange> {
+        self.docs
+            .into_iter()
+            .filter_map(|(path, before, doc)| {
+                let after = doc.to_string();
+                let display = path.strip_prefix(".").unwrap_or(&path).display().to_string();
+                let file = FileChange { path: display, before: Some(before.clone()), after };
+                (file.after != before).then_some(ManifestChange { path, file })
+            })
+            .collect()
+    }
+}
 
-    let mut existing_deps = HashSet::new();
+/// Features of the `[workspace.dependencies]` entry for `name`, `None` when there is none.
+fn workspace_dependency_features(root_doc: &DocumentMut, name: &str) -> Option<Vec<String>> {
+    let entry = root_doc.get("workspace").and_then(|w| w.get("dependencies")).and_then(|deps| deps.get(name));
+    entry.map(declared_features)
+}
 
-    // Check [dependencies] section
-    for dep_name in manifest.dependencies.keys() {
-        existing_deps.insert(dep_name.clone());
+/// `"version"`, or `{ version, features, default-features, optional }` when the spec needs more.
+fn dependency_item(spec: &CargoDepSpec, version: &str, optional: bool) -> Item {
+    if spec.features.is_empty() && spec.default_features && !optional {
+        return toml_edit::value(version);
+    }
+    let mut dep_table = InlineTable::new();
+    dep_table.insert("version", Value::from(version));
+    if !spec.features.is_empty() {
+        dep_table.insert("features", Value::Array(features_array(&spec.features)));
+    }
+    if !spec.default_features {
+        dep_table.insert("default-features", Value::from(false));
     }
+    if optional {
+        dep_table.insert("optional", Value::from(true));
+    }
+    toml_edit::value(dep_table)
+}
+
+fn declared_features(entry: &Item) -> Vec<String> {
+    let features = entry.get("features").and_then(Item::as_array);
+    features
+        .map(|array| array.iter().filter_map(|f| f.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
 
-    // Check [dev-dependencies] section
-    for dep_name in manifest.dev_dependencies.keys() {
-        existing_deps.insert(dep_name.clone());
+/// Append `features` to a dependency entry, turning `name = "1.0"` into an inline table.
+fn add_features(entry: &mut Item, name: &str, features: &[String]) -> CliResult<()> {
+    let unsupported =
+        || CliError::cargo_operation(&format!("Cannot add features to the declaration of '{name}'"));
+    match entry {
+        Item::Value(Value::String(version)) => {
+            let mut dep_table = InlineTable::new();
+            dep_table.insert("version", Value::from(version.value().as_str()));
+            dep_table.insert("features", Value::Array(features_array(features)));
+            *dep_table.decor_mut() = version.decor().clone();
+            *entry = toml_edit::value(dep_table);
+        }
+        Item::Value(Value::InlineTable(dep_table)) => {
+            let array = dep_table.get_or_insert("features", Array::new());
+            array.as_array_mut().ok_or_else(unsupported)?.extend(features.iter().map(String::as_str));
+        }
+        Item::Table(dep_table) => {
+            let array = dep_table.entry("features").or_insert(toml_edit::value(Array::new()));
+            array.as_array_mut().ok_or_else(unsupported)?.extend(features.iter().map(String::as_str));
+        }
+        _ => return Err(unsupported()),
     }
+    Ok(())
+}
 
-    Ok(existing_deps)
+fn features_array(features: &[String]) -> Array {
+    features.iter().map(String::as_str).collect()
 }
 
 /* ========================================================== */
@@ -307,9 +541,23 @@ mod tests {
 
     use super::*;
 
+    /// Run `edit` on the manifests and write the changes back, as `apply_cargo_deps_plan` does.
+    fn apply_edits<R>(edit: impl FnOnce(&mut Manifests) -> CliResult<R>) -> R {
+        let mut manifests = Manifests::default();
+        let result = edit(&mut manifests).unwrap();
+        for change in manifests.into_changes() {
+            fs::write(&change.path, &change.file.after).unwrap();
+        }
+        result
+    }
+
+    fn edit_manifest(path: &Path, edit: impl FnOnce(&mut DocumentMut) -> CliResult<()>) {
+        apply_edits(|manifests| edit(manifests.doc_mut(path)?));
+    }
+
     #[test]
     fn test_build_cargo_add_args_no_workspace() {
-        let args = build_cargo_add_args("serde", &None);
+        let args = build_cargo_add_args(&CargoDepSpec::new("serde"), &None);
         assert_eq!(args, vec!["add", "serde"]);
     }
 
@@ -323,7 +571,7 @@ mod tests {
             components_base_path: "src/components".to_string(),
         };
 
-        let args = build_cargo_add_args("serde", &Some(info));
+        let args = build_cargo_add_args(&CargoDepSpec::new("serde"), &Some(info));
         assert_eq!(args, vec!["add", "serde"]);
     }
 
@@ -337,7 +585,7 @@ mod tests {
             components_base_path: "frontend/src/components".to_string(),
         };
 
-        let args = build_cargo_add_args("serde", &Some(info));
+        let args = build_cargo_add_args(&CargoDepSpec::new("serde"), &Some(info));
         assert_eq!(args, vec!["add", "serde", "--package", "frontend"]);
     }
 
@@ -351,7 +599,7 @@ mod tests {
             components_base_path: "src/components".to_string(),
         };
 
-        let args = build_cargo_add_args("serde", &Some(info));
+        let args = build_cargo_add_args(&CargoDepSpec::new("serde"), &Some(info));
         assert_eq!(args, vec!["add", "serde"]);
     }
 
@@ -445,7 +693,9 @@ leptos = "0.7"
         .unwrap();
 
         // Add serde
-        add_to_workspace_dependencies(&cargo_toml, "serde", "1.0").unwrap();
+        edit_manifest(&cargo_toml, |doc| {
+            add_to_workspace_dependencies(doc, &CargoDepSpec::new("serde"), "1.0")
+        });
 
         // Verify
         let contents = fs::read_to_string(&cargo_toml).unwrap();
@@ -472,7 +722,7 @@ leptos.workspace = true
         .unwrap();
 
         // Add serde.workspace = true
-        add_workspace_ref_to_member(&cargo_toml, "serde").unwrap();
+        edit_manifest(&cargo_toml, |doc| add_workspace_ref_to_member(doc, &CargoDepSpec::new("serde"), &[]));
 
         // Verify
         let contents = fs::read_to_string(&cargo_toml).unwrap();
@@ -501,7 +751,9 @@ version = "0.1.0"
         .unwrap();
 
         // Add validator.workspace = true
-        add_workspace_ref_to_member(&cargo_toml, "validator").unwrap();
+        edit_manifest(&cargo_toml, |doc| {
+            add_workspace_ref_to_member(doc, &CargoDepSpec::new("validator"), &[])
+        });
 
         // Verify it uses dotted format (validator.workspace = true) not inline ({ workspace = true })
         let contents = fs::read_to_string(&cargo_toml).unwrap();
@@ -626,9 +878,13 @@ leptos.workspace = true
             components_base_path: "app/src/components".to_string(),
         };
 
-        // Test the individual functions since fetch_latest_version requires network
-        add_to_workspace_dependencies(&root.join("Cargo.toml"), "serde", "1.0").unwrap();
-        add_workspace_ref_to_member(&app_dir.join("Cargo.toml"), "serde").unwrap();
+        // Test the individual functions since resolve_crate_version reads the user's cargo registry cache
+        edit_manifest(&root.join("Cargo.toml"), |doc| {
+            add_to_workspace_dependencies(doc, &CargoDepSpec::new("serde"), "1.0")
+        });
+        edit_manifest(&app_dir.join("Cargo.toml"), |doc| {
+            add_workspace_ref_to_member(doc, &CargoDepSpec::new("serde"), &[])
+        });
 
         // Verify root Cargo.toml
         let root_contents = fs::read_to_string(root.join("Cargo.toml")).unwrap();
@@ -638,4 +894,226 @@ leptos.workspace = true
         let app_contents = fs::read_to_string(app_dir.join("Cargo.toml")).unwrap();
         assert!(app_contents.contains("serde"), "App should have serde ref: {app_contents}");
     }
+
+    fn workspace_fixture(root_deps: &str, member_deps: &str) -> (TempDir, WorkspaceInfo) {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            format!("[workspace]\nmembers = [\"app\"]\n\n[workspace.dependencies]\n{root_deps}"),
+        )
+        .unwrap();
+        let app_dir = root.join("app");
+        fs::create_dir_all(&app_dir).unwrap();
+        fs::write(
+            app_dir.join("Cargo.toml"),
+            format!("[package]\nname = \"app\"\n\n[dependencies]\n{member_deps}"),
+        )
+        .unwrap();
+        let info = WorkspaceInfo {
+            is_workspace: true,
+            workspace_root: Some(root.to_path_buf()),
+            target_crate: Some("app".to_string()),
+            target_crate_path: Some(app_dir),
+            components_base_path: "app/src/components".to_string(),
+        };
+        (temp, info)
+    }
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn build_cargo_add_args_carry_the_spec() {
+        let spec = CargoDepSpec::parse("strum@0.27[derive] optional").unwrap();
+        assert_eq!(
+            build_cargo_add_args(&spec, &None),
+            vec!["add", "strum@0.27", "--features", "derive", "--optional"]
+        );
+    }
+
+    #[test]
+    fn workspace_dependency_gets_version_features_and_flags() {
+        let (temp, info) = workspace_fixture("leptos = \"0.8\"\n", "leptos.workspace = true\n");
+        // The locked version wins over the spec's, whatever the local registry cache holds
+        fs::write(
+            temp.path().join("Cargo.lock"),
+            "[[package]]\nname = \"leptos-use\"\nversion = \"0.16.3\"\n",
+        )
+        .unwrap();
+        let spec = CargoDepSpec::parse("leptos-use@0.16[storage] optional no-default-features").unwrap();
+        assert!(apply_edits(|manifests| add_workspace_dependency(manifests, &spec, &info)));
+
+        assert!(read(temp.path().join("Cargo.toml")).contains(
+            "leptos-use = { version = \"0.16.3\", features = [\"storage\"], default-features = false }"
+        ));
+        assert!(
+            read(temp.path().join("app/Cargo.toml"))
+                .contains("leptos-use = { workspace = true, optional = true }")
+        );
+    }
+
+    #[test]
+    fn workspace_dependency_already_in_the_root_gets_missing_features_on_the_member() {
+        let (temp, info) = workspace_fixture("strum = { version = \"0.27\", features = [\"derive\"] }\n", "");
+        assert!(apply_edits(|manifests| {
+            add_workspace_dependency(manifests, &CargoDepSpec::parse("strum[derive,phf]").unwrap(), &info)
+        }));
+
+        assert!(
+            read(temp.path().join("Cargo.toml"))
+                .contains("strum = { version = \"0.27\", features = [\"derive\"] }")
+        );
+        assert!(
+            read(temp.path().join("app/Cargo.toml"))
+                .contains("strum = { workspace = true, features = [\"phf\"] }")
+        );
+    }
+
+    #[test]
+    fn existing_plain_dependencies_get_missing_features() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[dependencies]\nstrum = \"0.27\" # enums\nserde = { version = \"1\", features = [\"derive\"] }\n\n[dependencies.tw_merge]\nversion = \"0.1\"\n",
+        )
+        .unwrap();
+        let info = Some(WorkspaceInfo {
+            is_workspace: false,
+            workspace_root: None,
+            target_crate: None,
+            target_crate_path: Some(temp.path().to_path_buf()),
+            components_base_path: "src/components".to_string(),
+        });
+
+        for spec in ["strum[derive]", "serde[derive,rc]", "tw_merge[variant]"] {
+            assert!(
+                apply_edits(|manifests| extend_dependency_features(
+                    manifests,
+                    &CargoDepSpec::parse(spec).unwrap(),
+                    &info
+                )),
+                "{spec}"
+            );
+        }
+        assert!(!apply_edits(|manifests| extend_dependency_features(
+            manifests,
+            &CargoDepSpec::parse("serde[rc]").unwrap(),
+            &info
+        )));
+        assert!(!apply_edits(|manifests| extend_dependency_features(
+            manifests,
+            &CargoDepSpec::parse("unknown[x]").unwrap(),
+            &info
+        )));
+
+        let contents = read(temp.path().join("Cargo.toml"));
+        assert!(
+            contents.contains("strum = { version = \"0.27\", features = [\"derive\"] } # enums"),
+            "{contents}"
+        );
+        assert!(
+            contents.contains("serde = { version = \"1\", features = [\"derive\", \"rc\"] }"),
+            "{contents}"
+        );
+        assert!(
+            contents.contains("[dependencies.tw_merge]\nversion = \"0.1\"\nfeatures = [\"variant\"]"),
+            "{contents}"
+        );
+    }
+
+    #[test]
+    fn existing_workspace_dependencies_get_features_the_workspace_entry_lacks() {
+        let (temp, info) = workspace_fixture(
+            "strum = { version = \"0.27\", features = [\"derive\"] }\n",
+            "strum.workspace = true\n",
+        );
+        let info = Some(info);
+        assert!(!apply_edits(|manifests| extend_dependency_features(
+            manifests,
+            &CargoDepSpec::parse("strum[derive]").unwrap(),
+            &info
+        )));
+        assert!(apply_edits(|manifests| extend_dependency_features(
+            manifests,
+            &CargoDepSpec::parse("strum[derive,phf]").unwrap(),
+            &info
+        )));
+
+        let contents = read(temp.path().join("app/Cargo.toml"));
+        assert!(contents.contains("strum.workspace = true\nstrum.features = [\"phf\"]"), "{contents}");
+    }
+
+    #[test]
+    fn plan_shows_root_and_member_changes_without_writing_them() {
+        let (temp, info) = workspace_fixture("leptos = \"0.8\"\n", "leptos.workspace = true\n");
+        fs::write(temp.path().join("Cargo.lock"), "[[package]]\nname = \"strum\"\nversion = \"0.27.2\"\n")
+            .unwrap();
+        let root_before = read(temp.path().join("Cargo.toml"));
+
+        let plan =
+            plan_cargo_deps(&["strum[derive]".to_string(), "leptos".to_string()], &Some(info)).unwrap();
+        assert_eq!(plan.added, vec!["strum"]);
+        assert_eq!(plan.skipped, vec!["leptos"]);
+        assert!(plan.cargo_add.is_empty());
+        assert_eq!(read(temp.path().join("Cargo.toml")), root_before);
+
+        let out = format_cargo_deps_plan(&plan, "[dry-run] ");
+        assert!(out.contains("[dry-run] Cargo.toml changes ("), "{out}");
+        assert!(out.contains("+strum = { version = \"0.27.2\", features = [\"derive\"] }"), "{out}");
+        assert!(out.contains("+strum.workspace = true"), "{out}");
+        assert!(out.contains("[dry-run] Cargo deps already present: leptos"), "{out}");
+        assert_eq!(plan.changes.len(), 2);
+    }
+
+    #[test]
+    fn plan_for_a_workspace_leaves_crates_without_a_known_version_to_cargo_add() {
+        let (temp, info) = workspace_fixture("leptos = \"0.8\"\n", "leptos.workspace = true\n");
+        let root_before = read(temp.path().join("Cargo.toml"));
+        let info = Some(info);
+
+        let plan = plan_cargo_deps(&["ui-cli-unpublished-crate".to_string()], &info).unwrap();
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.cargo_add, vec![CargoDepSpec::new("ui-cli-unpublished-crate")]);
+        assert_eq!(
+            build_cargo_add_args(&plan.cargo_add[0], &info),
+            vec!["add", "ui-cli-unpublished-crate", "--package", "app"]
+        );
+        assert_eq!(read(temp.path().join("Cargo.toml")), root_before);
+    }
+
+    #[test]
+    fn plan_for_a_single_crate_writes_dependencies_or_falls_back_to_cargo_add() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("Cargo.toml"), "[package]\nname = \"app\"\n\n[dependencies]\n").unwrap();
+        fs::write(temp.path().join("Cargo.lock"), "[[package]]\nname = \"strum\"\nversion = \"0.27.2\"\n")
+            .unwrap();
+        let info = Some(WorkspaceInfo {
+            is_workspace: false,
+            workspace_root: None,
+            target_crate: Some("app".to_string()),
+            target_crate_path: Some(temp.path().to_path_buf()),
+            components_base_path: "src/components".to_string(),
+        });
+
+        let deps = ["strum optional".to_string(), "ui-cli-unpublished-crate".to_string()];
+        let plan = plan_cargo_deps(&deps, &info).unwrap();
+        assert_eq!(plan.changes.len(), 1);
+        assert!(plan.changes[0].file.after.contains("strum = { version = \"0.27.2\", optional = true }"));
+        assert_eq!(plan.cargo_add, vec![CargoDepSpec::new("ui-cli-unpublished-crate")]);
+        assert!(format_cargo_deps_plan(&plan, "").contains("Would run: cargo add ui-cli-unpublished-crate"));
+    }
+
+    #[test]
+    fn confirm_does_not_ask_for_an_empty_plan_or_with_yes() {
+        assert!(confirm_cargo_deps_plan(&CargoDepsPlan::default(), false).unwrap());
+
+        let plan = CargoDepsPlan {
+            cargo_add: vec![CargoDepSpec::new("strum")],
+            added: vec!["strum".to_string()],
+            ..Default::default()
+        };
+        assert!(confirm_cargo_deps_plan(&plan, true).unwrap());
+    }
 }
@@ -0,0 +1,344 @@
+use std::path::{Component, Path};
+use std::str::FromStr;
+
+use proc_macro2::{LineColumn, Spacing, Span, TokenStream, TokenTree};
+
+use super::component_dirs::ComponentDirs;
+use crate::command_init::config::Aliases;
+use crate::command_init::workspace_utils::load_cargo_manifest;
+
+/// Module path registry components use to import each other.
+pub const REGISTRY_COMPONENTS_PATH: &str = "crate::components";
+
+/// Rewrites the `crate::components::...` paths of registry components to the module paths
+/// of the project, as configured by `[aliases]` or inferred from the components directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportAliases {
+    /// `(registry path, project path)`, most specific registry path first.
+    rewrites: Vec<(String, String)>,
+}
+
+impl ImportAliases {
+    /// Aliases for a component of `category` installed into `dirs`: `[aliases]` when set,
+    /// else the module paths of the directories as seen from the crate the component is written
+    /// into (`src/ui_kit` -> `crate::ui_kit`, `crates/my_ui/src/ui` -> `my_ui::ui`).
+    pub fn resolve(aliases: &Aliases, dirs: &ComponentDirs, category: &str) -> Self {
+        let from = dirs.dir(category);
+        let mut rewrites = Vec::new();
+        for other in dirs.categories(&aliases.categories()) {
+            let alias = aliases
+                .category(&other)
+                .cloned()
+                .or_else(|| dirs.custom_dir(&other).and_then(|dir| module_path_from(&dir, &from)));
+            if let Some(alias) = alias {
+                rewrites.push((format!("{REGISTRY_COMPONENTS_PATH}::{other}"), alias));
+            }
+        }
+        let components =
+            aliases.components.clone().or_else(|| module_path_from(Path::new(&dirs.base_path), &from));
+        if let Some(components) = components {
+            rewrites.push((REGISTRY_COMPONENTS_PATH.to_string(), components));
+        }
+        rewrites.retain(|(from, to)| from != to);
+        Self { rewrites }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.rewrites.is_empty()
+    }
+
+    /// `source` with every path starting with an aliased registry path rewritten.
+    /// Comments and string literals are left alone; sources that do not lex are returned as is.
+    pub fn rewrite(&self, source: &str) -> String {
+        if self.is_identity() {
+            return source.to_string();
+        }
+        let Ok(tokens) = TokenStream::from_str(source) else {
+            return source.to_string();
+        };
+        let mut leaves = Vec::new();
+        flatten(tokens, &mut leaves);
+
+        let line_starts: Vec<usize> =
+            std::iter::once(0).chain(source.match_indices('\n').map(|(index, _)| index + 1)).collect();
+        let mut edits: Vec<(usize, usize, &str)> = Vec::new();
+        let mut index = 0;
+        while let Some(rest) = leaves.get(index..).filter(|rest| !rest.is_empty()) {
+            let found =
+                self.rewrites.iter().find_map(|(from, to)| match_path(rest, from).map(|len| (len, to)));
+            let Some((len, to)) = found else {
+                index += 1;
+                continue;
+            };
+            let start = rest.first().and_then(|leaf| byte_offset(source, &line_starts, leaf.span.start()));
+            let end = rest.get(len - 1).and_then(|leaf| byte_offset(source, &line_starts, leaf.span.end()));
+            if let (Some(start), Some(end)) = (start, end) {
+                edits.push((start, end, to.as_str()));
+            }
+            index += len;
+        }
+
+        let mut rewritten = source.to_string();
+        for (start, end, to) in edits.into_iter().rev() {
+            rewritten.replace_range(start..end, to);
+        }
+        rewritten
+    }
+}
+
+/// Module path of the directory `dir` (`<crate>/src/a/b`) as seen from the directory `from`:
+/// `crate::a::b` inside the same crate, `<crate name>::a::b` from another one. `None` when
+/// `dir` is not inside the `src/` directory of a crate or a segment is not a Rust identifier.
+pub fn module_path_from(dir: &Path, from: &Path) -> Option<String> {
+    let crate_root = crate_root_of(dir)?;
+    let relative = dir.strip_prefix(crate_root.join("src")).ok()?;
+
+    let same_crate = crate_root_of(from).is_some_and(|from_root| same_dir(from_root, crate_root));
+    let mut module_path = if same_crate { "crate".to_string() } else { extern_crate_name(crate_root)? };
+    for component in relative.components() {
+        let Component::Normal(segment) = component else { return None };
+        let segment = segment.to_str().filter(|segment| is_identifier(segment))?;
+        module_path.push_str("::");
+        module_path.push_str(segment);
+    }
+    Some(module_path)
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+struct Leaf {
+    kind: LeafKind,
+    span: Span,
+}
+
+enum LeafKind {
+    Ident(String),
+    Punct(char, Spacing),
+    /// Literals and group delimiters: never part of a path.
+    Other,
+}
+
+fn flatten(tokens: TokenStream, leaves: &mut Vec<Leaf>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                leaves.push(Leaf { kind: LeafKind::Other, span: group.span_open() });
+                flatten(group.stream(), leaves);
+                leaves.push(Leaf { kind: LeafKind::Other, span: group.span_close() });
+            }
+            TokenTree::Ident(ident) => {
+                leaves.push(Leaf { kind: LeafKind::Ident(ident.to_string()), span: ident.span() })
+            }
+            TokenTree::Punct(punct) => leaves
+                .push(Leaf { kind: LeafKind::Punct(punct.as_char(), punct.spacing()), span: punct.span() }),
+            TokenTree::Literal(literal) => leaves.push(Leaf { kind: LeafKind::Other, span: literal.span() }),
+        }
+    }
+}
+
+/// Number of leaves `path` (`a::b::c`) spans at the start of `leaves`, if it is there.
+fn match_path(leaves: &[Leaf], path: &str) -> Option<usize> {
+    let mut len = 0;
+    for (position, segment) in path.split("::").enumerate() {
+        if position > 0 {
+            let separator = leaves.get(len..len + 2)?;
+            let is_separator = matches!(
+                separator,
+                [
+                    Leaf { kind: LeafKind::Punct(':', Spacing::Joint), .. },
+                    Leaf { kind: LeafKind::Punct(':', _), .. }
+                ]
+            );
+            if !is_separator {
+                return None;
+            }
+            len += 2;
+        }
+        match leaves.get(len) {
+            Some(Leaf { kind: LeafKind::Ident(ident), .. }) if ident == segment => len += 1,
+            _ => return None,
+        }
+    }
+    Some(len)
+}
+
+/// Byte offset of a span position (1-based line, column in chars) in `source`.
+fn byte_offset(source: &str, line_starts: &[usize], position: LineColumn) -> Option<usize> {
+    let line_start = *line_starts.get(position.line.checked_sub(1)?)?;
+    let line = source.get(line_start..)?;
+    let column = line.char_indices().nth(position.column).map_or(line.len(), |(offset, _)| offset);
+    Some(line_start + column)
+}
+
+fn crate_root_of(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|ancestor| ancestor.join("Cargo.toml").is_file())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    // `Path::new("src").parent()` is the empty path, which does not canonicalize
+    let current_dir = |path: &Path| {
+        if path.as_os_str().is_empty() { Path::new(".").to_path_buf() } else { path.to_path_buf() }
+    };
+    match (current_dir(a).canonicalize(), current_dir(b).canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Name other crates import the crate at `crate_root` by: its `[lib] name`, else its package name.
+fn extern_crate_name(crate_root: &Path) -> Option<String> {
+    let manifest = load_cargo_manifest(&crate_root.join("Cargo.toml")).ok().flatten()?;
+    let name =
+        manifest.lib.and_then(|lib| lib.name).or_else(|| manifest.package.map(|package| package.name))?;
+    Some(name.replace('-', "_"))
+}
+
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::command_init::config::ComponentPaths;
+
+    const BUTTON_GROUP: &str = "use leptos::prelude::*;\n\nuse crate::components::ui::button::{Button, ButtonVariant};\nuse crate::components::hooks::use_random::use_random_id;\n\n// see crate::components::ui::button\n#[component]\npub fn ButtonGroup() -> impl IntoView {\n    let label = \"crate::components\";\n    view! { <Button /> }\n}\n";
+
+    fn resolve(aliases: &Aliases, base_path: &str) -> ImportAliases {
+        ImportAliases::resolve(aliases, &ComponentDirs::from_base_path(base_path), "ui")
+    }
+
+    fn aliases(components: Option<&str>, ui: Option<&str>) -> Aliases {
+        Aliases {
+            components: components.map(str::to_string),
+            ui: ui.map(str::to_string),
+            ..Aliases::default()
+        }
+    }
+
+    #[test]
+    fn rewrites_paths_but_not_comments_or_strings() {
+        let import_aliases = resolve(&aliases(Some("crate::ui_kit"), None), "src/ui_kit");
+        let rewritten = import_aliases.rewrite(BUTTON_GROUP);
+        assert_eq!(rewritten, BUTTON_GROUP.replace("use crate::components::", "use crate::ui_kit::"));
+        assert!(rewritten.contains("// see crate::components::ui::button"));
+        assert!(rewritten.contains("\"crate::components\""));
+    }
+
+    #[test]
+    fn category_alias_takes_precedence() {
+        let import_aliases = resolve(&aliases(Some("crate::kit"), Some("my_ui::ui")), "src/kit");
+        let rewritten = import_aliases.rewrite(BUTTON_GROUP);
+        assert!(rewritten.contains("use my_ui::ui::button::{Button, ButtonVariant};"), "{rewritten}");
+        assert!(rewritten.contains("use crate::kit::hooks::use_random::use_random_id;"), "{rewritten}");
+    }
+
+    #[test]
+    fn only_whole_segments_match() {
+        let import_aliases = resolve(&aliases(Some("crate::kit"), None), "src/kit");
+        let source = "use crate::components_extra::x;\nuse crate::components::{ui::badge::Badge};\n";
+        assert_eq!(
+            import_aliases.rewrite(source),
+            "use crate::components_extra::x;\nuse crate::kit::{ui::badge::Badge};\n"
+        );
+    }
+
+    #[test]
+    fn default_location_is_left_untouched() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let base_path = dir.path().join("src").join("components");
+        let import_aliases = resolve(&Aliases::default(), base_path.to_str().unwrap());
+        assert!(import_aliases.is_identity());
+        assert_eq!(import_aliases.rewrite(BUTTON_GROUP), BUTTON_GROUP);
+    }
+
+    #[test]
+    fn module_path_is_inferred_from_the_crate_layout() {
+        let dir = TempDir::new().unwrap();
+        let crate_dir = dir.path().join("crates").join("my_ui");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(crate_dir.join("Cargo.toml"), "[package]\nname = \"my-ui\"\n").unwrap();
+
+        assert_eq!(module_path_from(&crate_dir.join("src"), &crate_dir).as_deref(), Some("crate"));
+        assert_eq!(
+            module_path_from(&crate_dir.join("src/app/widgets"), &crate_dir).as_deref(),
+            Some("crate::app::widgets")
+        );
+        assert_eq!(module_path_from(&crate_dir.join("assets/components"), &crate_dir), None);
+        assert_eq!(module_path_from(&crate_dir.join("src/my-widgets"), &crate_dir), None);
+
+        let import_aliases = resolve(&Aliases::default(), crate_dir.join("src").to_str().unwrap());
+        assert!(
+            import_aliases.rewrite(BUTTON_GROUP).contains("use crate::ui::button::{Button, ButtonVariant};")
+        );
+    }
+
+    #[test]
+    fn categories_in_another_crate_are_imported_by_crate_name() {
+        let dir = TempDir::new().unwrap();
+        let my_ui = dir.path().join("crates").join("my_ui");
+        let examples = dir.path().join("examples");
+        fs::create_dir_all(&my_ui).unwrap();
+        fs::create_dir_all(&examples).unwrap();
+        fs::write(my_ui.join("Cargo.toml"), "[package]\nname = \"my-ui\"\n").unwrap();
+        fs::write(examples.join("Cargo.toml"), "[package]\nname = \"examples\"\n").unwrap();
+
+        let paths = ComponentPaths {
+            ui: Some(my_ui.join("src/ui").to_string_lossy().to_string()),
+            demos: Some(examples.join("src/demos").to_string_lossy().to_string()),
+            ..ComponentPaths::default()
+        };
+        let dirs = ComponentDirs::new(&my_ui.join("src").to_string_lossy(), &paths);
+        let demo = "use crate::components::ui::button::Button;\nuse crate::components::demos::demo_card::DemoCard;\n";
+
+        let in_examples = ImportAliases::resolve(&Aliases::default(), &dirs, "demos");
+        assert_eq!(
+            in_examples.rewrite(demo),
+            "use my_ui::ui::button::Button;\nuse crate::demos::demo_card::DemoCard;\n"
+        );
+        let in_my_ui = ImportAliases::resolve(&Aliases::default(), &dirs, "ui");
+        assert_eq!(
+            in_my_ui.rewrite(demo),
+            "use crate::ui::button::Button;\nuse examples::demos::demo_card::DemoCard;\n"
+        );
+    }
+
+    #[test]
+    fn registry_categories_use_their_alias_or_mapped_directory() {
+        let mut aliases = aliases(Some("crate::kit"), None);
+        aliases.other.insert("charts".to_string(), "my_charts".to_string());
+        let paths = ComponentPaths {
+            other: [("blocks".to_string(), "src/blocks".to_string())].into(),
+            ..ComponentPaths::default()
+        };
+        let dirs = ComponentDirs::new("src/kit", &paths);
+        let source = "use crate::components::blocks::hero::Hero;\nuse crate::components::charts::bar::Bar;\n";
+        assert_eq!(
+            ImportAliases::resolve(&aliases, &dirs, "ui").rewrite(source),
+            "use crate::blocks::hero::Hero;\nuse my_charts::bar::Bar;\n"
+        );
+    }
+
+    #[test]
+    fn multibyte_characters_before_a_path_keep_offsets_right() {
+        let import_aliases = resolve(&aliases(Some("crate::kit"), None), "src/kit");
+        let source = "const S: &str = \"✨é\"; use crate::components::ui::badge::Badge;\n";
+        assert_eq!(
+            import_aliases.rewrite(source),
+            "const S: &str = \"✨é\"; use crate::kit::ui::badge::Badge;\n"
+        );
+    }
+}
@@ -1,20 +1,14 @@
 use std::collections::HashSet;
-use std::path::Path;
 
-/// Scan the components directory and return a set of installed component names
-pub fn get_installed_components(base_path: &str) -> HashSet<String> {
-    let mut installed = HashSet::new();
-    let base = Path::new(base_path);
-
-    if !base.exists() {
-        return installed;
-    }
+use super::component_dirs::ComponentDirs;
 
-    // Scan subdirectories: ui/, demos/, hooks/, extensions/
-    let subdirs = ["ui", "demos", "hooks", "extensions"];
+/// Scan the directory of every category (`ui/`, `blocks/`, ... or their `[paths]`)
+/// and return a set of installed component names
+pub fn get_installed_components(dirs: &ComponentDirs, categories: &[String]) -> HashSet<String> {
+    let mut installed = HashSet::new();
 
-    for subdir in subdirs {
-        let dir_path = base.join(subdir);
+    for category in categories {
+        let dir_path = dirs.dir(category);
         if let Ok(entries) = std::fs::read_dir(&dir_path) {
             for entry in entries.flatten() {
                 let path = entry.path();
@@ -51,16 +45,26 @@ mod tests {
         dir
     }
 
+    fn categories() -> Vec<String> {
+        ["ui", "demos", "hooks", "extensions"].map(String::from).to_vec()
+    }
+
     #[test]
     fn returns_empty_for_nonexistent_path() {
-        let result = get_installed_components("/nonexistent/path/12345");
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path("/nonexistent/path/12345"),
+            &categories(),
+        );
         assert!(result.is_empty());
     }
 
     #[test]
     fn returns_empty_for_empty_directory() {
         let temp_dir = create_temp_dir("empty");
-        let result = get_installed_components(temp_dir.to_str().unwrap());
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path(temp_dir.to_str().unwrap()),
+            &categories(),
+        );
         assert!(result.is_empty());
         let _ = fs::remove_dir_all(&temp_dir);
     }
@@ -73,7 +77,10 @@ mod tests {
         fs::write(ui_dir.join("button.rs"), "// button").unwrap();
         fs::write(ui_dir.join("card.rs"), "// card").unwrap();
 
-        let result = get_installed_components(temp_dir.to_str().unwrap());
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path(temp_dir.to_str().unwrap()),
+            &categories(),
+        );
         assert!(result.contains("button"));
         assert!(result.contains("card"));
         assert_eq!(result.len(), 2);
@@ -89,7 +96,10 @@ mod tests {
         fs::write(ui_dir.join("mod.rs"), "// mod").unwrap();
         fs::write(ui_dir.join("button.rs"), "// button").unwrap();
 
-        let result = get_installed_components(temp_dir.to_str().unwrap());
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path(temp_dir.to_str().unwrap()),
+            &categories(),
+        );
         assert!(!result.contains("mod"));
         assert!(result.contains("button"));
         assert_eq!(result.len(), 1);
@@ -108,7 +118,10 @@ mod tests {
         fs::write(temp_dir.join("demos/demo_button.rs"), "").unwrap();
         fs::write(temp_dir.join("hooks/use_click.rs"), "").unwrap();
 
-        let result = get_installed_components(temp_dir.to_str().unwrap());
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path(temp_dir.to_str().unwrap()),
+            &categories(),
+        );
         assert!(result.contains("button"));
         assert!(result.contains("demo_button"));
         assert!(result.contains("use_click"));
@@ -126,10 +139,54 @@ mod tests {
         fs::write(ui_dir.join("readme.md"), "").unwrap();
         fs::write(ui_dir.join("style.css"), "").unwrap();
 
-        let result = get_installed_components(temp_dir.to_str().unwrap());
+        let result = get_installed_components(
+            &ComponentDirs::from_base_path(temp_dir.to_str().unwrap()),
+            &categories(),
+        );
         assert_eq!(result.len(), 1);
         assert!(result.contains("button"));
 
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn finds_components_in_mapped_category_dirs() {
+        let temp_dir = create_temp_dir("mapped_dirs");
+        let base = temp_dir.join("components");
+        let hooks = temp_dir.join("hooks");
+        fs::create_dir_all(base.join("ui")).unwrap();
+        fs::create_dir_all(base.join("hooks")).unwrap();
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(base.join("ui/button.rs"), "").unwrap();
+        fs::write(base.join("hooks/use_stale.rs"), "").unwrap();
+        fs::write(hooks.join("use_click.rs"), "").unwrap();
+
+        let paths = crate::command_init::config::ComponentPaths {
+            hooks: Some(hooks.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result =
+            get_installed_components(&ComponentDirs::new(base.to_str().unwrap(), &paths), &categories());
+        let mut names: Vec<String> = result.into_iter().collect();
+        names.sort();
+        assert_eq!(names, vec!["button", "use_click"]);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn scans_only_the_given_categories() {
+        let temp_dir = create_temp_dir("registry_categories");
+        fs::create_dir_all(temp_dir.join("ui")).unwrap();
+        fs::create_dir_all(temp_dir.join("blocks")).unwrap();
+        fs::write(temp_dir.join("ui/button.rs"), "").unwrap();
+        fs::write(temp_dir.join("blocks/hero.rs"), "").unwrap();
+
+        let dirs = ComponentDirs::from_base_path(temp_dir.to_str().unwrap());
+        assert!(!get_installed_components(&dirs, &categories()).contains("hero"));
+        let result = get_installed_components(&dirs, &["ui".to_string(), "blocks".to_string()]);
+        assert!(result.contains("hero") && result.contains("button"));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
 }
@@ -1,9 +1,15 @@
 pub mod _add;
 
+pub mod cargo_dep_spec;
+pub mod component_dirs;
 pub mod component_type;
 pub mod components;
 pub mod dependencies;
+pub mod import_paths;
 pub mod installed;
+pub mod mod_items;
 pub mod ratatui;
 pub mod registry;
+pub mod registry_index;
+pub mod rustfmt;
 pub mod tree_parser;
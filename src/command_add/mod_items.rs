@@ -0,0 +1,249 @@
+use std::fs;
+use std::path::Path;
+
+use syn::spanned::Spanned;
+use syn::{Item, ItemMod};
+
+use super::import_paths::module_path_from;
+use crate::shared::cli_error::{CliError, CliResult};
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Declare `mod name;` (`pub mod name;` when `is_pub`) in the Rust `source`.
+///
+/// Returns `None` when a module with exactly that name is already declared, whatever its
+/// visibility or `#[cfg]`. Otherwise the declaration goes in sorted position among the
+/// existing `mod` declarations, or right after the inner attributes (`#![...]`, `//!`) and
+/// the `//` comments heading the file when there are none. The rest of the file is left
+/// byte for byte.
+pub fn insert_mod_item(source: &str, name: &str, is_pub: bool) -> CliResult<Option<String>> {
+    let file = syn::parse_file(source).map_err(|e| CliError::validation(&e.to_string()))?;
+
+    let mods: Vec<&ItemMod> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Mod(item_mod) => Some(item_mod),
+            _ => None,
+        })
+        .collect();
+    if mods.iter().any(|item_mod| item_mod.ident == name) {
+        return Ok(None);
+    }
+
+    // Lines are 1-based in spans; `insert_at` is the 0-based index of the new line
+    let declarations: Vec<&&ItemMod> = mods.iter().filter(|item_mod| item_mod.content.is_none()).collect();
+    let insert_at = match declarations.iter().find(|item_mod| item_mod.ident.to_string().as_str() > name) {
+        Some(next) => next.span().start().line.saturating_sub(1),
+        None => match declarations.last() {
+            Some(last) => last.span().end().line,
+            None => {
+                let after_attrs =
+                    file.attrs.iter().map(|attr| attr.span().end().line).max().unwrap_or_default();
+                // `///` documents the item below, so it stays with it
+                let is_comment = |line: &&str| {
+                    let line = line.trim_start();
+                    line.starts_with("//") && (!line.starts_with("///") || line.starts_with("////"))
+                };
+                after_attrs + source.lines().skip(after_attrs).take_while(is_comment).count()
+            }
+        },
+    };
+
+    let visibility = if is_pub { "pub " } else { "" };
+    let mut lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let declaration = format!("{visibility}mod {name};\n");
+    let needs_newline = insert_at >= lines.len() && !source.is_empty() && !source.ends_with('\n');
+    let insert_at = insert_at.min(lines.len());
+    lines.insert(insert_at, &declaration);
+
+    let mut updated = String::with_capacity(source.len() + declaration.len() + 1);
+    for (index, line) in lines.iter().enumerate() {
+        if needs_newline && index == insert_at {
+            updated.push('\n');
+        }
+        updated.push_str(line);
+    }
+    Ok(Some(updated))
+}
+
+/// `insert_mod_item` on the file at `path`, created when missing. Returns whether it was written.
+pub fn insert_mod_item_in_file(path: &Path, names: &[&str], is_pub: bool) -> CliResult<bool> {
+    let exists = path.exists();
+    let mut source = if exists {
+        fs::read_to_string(path).map_err(|_| CliError::file_read_failed())?
+    } else {
+        String::new()
+    };
+    syn::parse_file(&source).map_err(|e| {
+        CliError::invalid_path(&path.display().to_string(), &format!("cannot parse Rust: {e}"))
+    })?;
+
+    let mut changed = !exists;
+    for name in names {
+        if let Some(updated) = insert_mod_item(&source, name, is_pub)? {
+            source = updated;
+            changed = true;
+        }
+    }
+    if changed {
+        fs::write(path, source).map_err(|_| CliError::file_write_failed())?;
+    }
+    Ok(changed)
+}
+
+/// Declare the module of the directory `dir` in its parent module: the crate entry (`lib.rs`,
+/// else `main.rs`) when `dir` sits directly in `src/`, else `<parent>.rs` or `<parent>/mod.rs`.
+/// A missing parent `mod.rs` is created and declared in turn. Directories outside a crate's
+/// `src/` are left alone.
+pub fn declare_module_dir(dir: &Path) -> CliResult<()> {
+    if module_path_from(dir, dir).is_none() {
+        return Ok(());
+    }
+    let (Some(name), Some(parent)) = (dir.file_name().and_then(|name| name.to_str()), dir.parent()) else {
+        return Ok(());
+    };
+
+    if module_path_from(parent, parent).is_some_and(|path| path == "crate") {
+        // Library modules are public so other crates (e.g. an examples crate) can import them
+        let lib_rs = parent.join("lib.rs");
+        let main_rs = parent.join("main.rs");
+        if lib_rs.exists() {
+            insert_mod_item_in_file(&lib_rs, &[name], true)?;
+        } else if main_rs.exists() {
+            insert_mod_item_in_file(&main_rs, &[name], false)?;
+        }
+        return Ok(());
+    }
+
+    let parent_file = parent.with_extension("rs");
+    if parent_file.is_file() {
+        insert_mod_item_in_file(&parent_file, &[name], true)?;
+        return Ok(());
+    }
+    let mod_rs = parent.join("mod.rs");
+    let is_new = !mod_rs.exists();
+    fs::create_dir_all(parent).map_err(|_| CliError::directory_create_failed())?;
+    insert_mod_item_in_file(&mod_rs, &[name], true)?;
+    if is_new {
+        declare_module_dir(parent)?;
+    }
+    Ok(())
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(source: &str, name: &str) -> Option<String> {
+        insert_mod_item(source, name, true).unwrap()
+    }
+
+    #[test]
+    fn prefix_of_an_existing_module_is_not_a_match() {
+        let updated = insert("pub mod button_group;\n", "button").unwrap();
+        assert_eq!(updated, "pub mod button;\npub mod button_group;\n");
+    }
+
+    #[test]
+    fn existing_module_is_detected_whatever_its_visibility_or_cfg() {
+        assert_eq!(insert("pub mod button;\n", "button"), None);
+        assert_eq!(insert("mod button;\n", "button"), None);
+        assert_eq!(insert("pub(crate) mod button;\n", "button"), None);
+        assert_eq!(insert("#[cfg(feature = \"ssr\")]\npub mod button;\n", "button"), None);
+        assert_eq!(insert("mod button {\n    pub fn x() {}\n}\n", "button"), None);
+        assert_eq!(
+            insert("// pub mod button;\n", "button"),
+            Some("// pub mod button;\npub mod button;\n".into())
+        );
+    }
+
+    #[test]
+    fn inserts_in_sorted_position() {
+        let source = "pub mod alert;\npub mod card;\npub mod input;\n";
+        assert_eq!(
+            insert(source, "badge").unwrap(),
+            "pub mod alert;\npub mod badge;\npub mod card;\npub mod input;\n"
+        );
+        assert_eq!(
+            insert(source, "toast").unwrap(),
+            "pub mod alert;\npub mod card;\npub mod input;\npub mod toast;\n"
+        );
+        assert_eq!(insert(source, "accordion").unwrap(), "pub mod accordion;\n".to_string() + source);
+    }
+
+    #[test]
+    fn inserts_before_the_attributes_of_the_next_module() {
+        let source = "pub mod alert;\n/// Cards\n#[cfg(feature = \"card\")]\npub mod card;\n";
+        assert_eq!(
+            insert(source, "badge").unwrap(),
+            "pub mod alert;\npub mod badge;\n/// Cards\n#[cfg(feature = \"card\")]\npub mod card;\n"
+        );
+    }
+
+    #[test]
+    fn inserts_after_inner_attributes() {
+        let source = "//! My app\n#![allow(dead_code)]\n\nuse leptos::prelude::*;\n\nfn main() {}\n";
+        let updated = insert_mod_item(source, "components", false).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            "//! My app\n#![allow(dead_code)]\nmod components;\n\nuse leptos::prelude::*;\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn inserts_after_the_header_comment() {
+        let source = "// Generated by rust-ui.\n// Edit freely.\n\nuse leptos::prelude::*;\n";
+        assert_eq!(
+            insert(source, "ui").unwrap(),
+            "// Generated by rust-ui.\n// Edit freely.\npub mod ui;\n\nuse leptos::prelude::*;\n"
+        );
+        let source = "//! Components.\n// Keep sorted.\n/// Buttons.\npub fn button() {}\n";
+        assert_eq!(
+            insert(source, "ui").unwrap(),
+            "//! Components.\n// Keep sorted.\npub mod ui;\n/// Buttons.\npub fn button() {}\n"
+        );
+        assert_eq!(insert("// header", "ui").unwrap(), "// header\npub mod ui;\n");
+    }
+
+    #[test]
+    fn empty_and_unterminated_sources() {
+        assert_eq!(insert("", "ui").unwrap(), "pub mod ui;\n");
+        assert_eq!(insert("pub mod ui;", "hooks").unwrap(), "pub mod hooks;\npub mod ui;");
+        assert_eq!(insert("pub mod hooks;", "ui").unwrap(), "pub mod hooks;\npub mod ui;\n");
+    }
+
+    #[test]
+    fn declares_directories_up_to_the_crate_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("app")).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        fs::write(src.join("lib.rs"), "pub mod app;\n").unwrap();
+        fs::write(src.join("app.rs"), "").unwrap();
+
+        declare_module_dir(&src.join("hooks")).unwrap();
+        declare_module_dir(&src.join("app").join("widgets")).unwrap();
+        declare_module_dir(&src.join("shared").join("demos")).unwrap();
+        declare_module_dir(&dir.path().join("assets")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "pub mod app;\npub mod hooks;\npub mod shared;\n"
+        );
+        assert_eq!(fs::read_to_string(src.join("app.rs")).unwrap(), "pub mod widgets;\n");
+        assert_eq!(fs::read_to_string(src.join("shared").join("mod.rs")).unwrap(), "pub mod demos;\n");
+        assert!(!dir.path().join("mod.rs").exists());
+    }
+
+    #[test]
+    fn invalid_source_is_an_error() {
+        assert!(insert_mod_item("pub mod ui", "hooks", true).is_err());
+    }
+}
@@ -1,11 +1,15 @@
-use std::io::Write;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use dialoguer::Confirm;
 use dialoguer::theme::ColorfulTheme;
 
-use super::component_type::ComponentType;
+use super::component_dirs::ComponentDirs;
+use super::import_paths::ImportAliases;
+use super::mod_items::insert_mod_item_in_file;
+use super::rustfmt::{format_file, format_or_keep};
+use crate::command_init::config::Aliases;
 use crate::shared::cli_error::{CliError, CliResult};
+use crate::shared::markdown_utils::RegistryFile;
 use crate::shared::rust_ui_client::RustUIClient;
 
 /* ========================================================== */
@@ -23,32 +27,60 @@ pub enum WriteOutcome {
 /* ========================================================== */
 
 pub struct RegistryComponent {
-    pub registry_md_path: String,
     pub registry_md_content: String,
     pub component_name: String,
+    pub category: String,
+    /// Other files of the component: submodules, CSS, JS, SVG, ... (see `RegistryFile`).
+    pub files: Vec<RegistryFile>,
 }
 
 impl RegistryComponent {
-    pub async fn fetch_from_registry(component_name: String) -> CliResult<RegistryComponent> {
-        let registry_md_content = RustUIClient::fetch_styles_default(&component_name).await?;
-        let component_type = ComponentType::from_component_name(&component_name);
-        let registry_md_path = format!("{}/{}.rs", component_type.to_path(), component_name);
-
-        Ok(RegistryComponent { registry_md_path, registry_md_content, component_name })
+    /// Fetch `component_name`, to be installed as a component of `category` (see `TreeParser::category_of`).
+    /// Its page must declare every file in `index_files`, those the registry index lists for it
+    /// (see `TreeParser::files_of`).
+    pub async fn fetch_from_registry(
+        component_name: String,
+        category: String,
+        index_files: &[String],
+    ) -> CliResult<RegistryComponent> {
+        let (registry_md_content, files) = RustUIClient::fetch_component_files(&component_name).await?;
+        check_index_files(&component_name, index_files, &files)?;
+
+        Ok(RegistryComponent { registry_md_content, component_name, category, files })
     }
 
-    pub async fn then_write_to_file_to(self, force: bool, base_path: &str) -> CliResult<WriteOutcome> {
-        let components_base_path = base_path.to_string();
-        let full_path_component = std::path::Path::new(&components_base_path).join(&self.registry_md_path);
-
-        let full_path_component_without_name_rs = full_path_component
-            .parent()
-            .ok_or_else(|| CliError::file_operation("Failed to get parent directory"))?
+    /// Write the component into the directory of its category in `dirs`, its
+    /// `crate::components` imports rewritten for that location (see `ImportAliases`).
+    /// Its other files follow, Rust ones rewritten the same way. With `format`, the Rust files
+    /// and the `mod.rs` go through rustfmt.
+    pub async fn then_write_to_file_to(
+        self,
+        force: bool,
+        dirs: &ComponentDirs,
+        aliases: &Aliases,
+        format: bool,
+    ) -> CliResult<WriteOutcome> {
+        let component_dir = dirs.dir(&self.category);
+        let full_path_component = component_dir.join(format!("{}.rs", self.component_name));
+
+        let full_path_component_without_name_rs = component_dir
             .to_str()
             .ok_or_else(|| CliError::file_operation("Failed to convert path to string"))?
             .to_string();
 
-        let outcome = write_component_file(&full_path_component, &self.registry_md_content, force)?;
+        let import_aliases = ImportAliases::resolve(aliases, dirs, &self.category);
+        // Checked before anything is written, so a bad path cannot leave a half-installed component
+        let files = self
+            .files
+            .iter()
+            .map(|file| Ok((registry_file_target(&component_dir, &file.path)?, file)))
+            .collect::<CliResult<Vec<_>>>()?;
+
+        let mut content = import_aliases.rewrite(&self.registry_md_content);
+        if format {
+            content = format_or_keep(&content, &component_dir, &format!("{}.rs", self.component_name));
+        }
+        let outcome = write_component_file(&full_path_component, &content, force)?;
 
         if outcome == WriteOutcome::Skipped {
             return Ok(WriteOutcome::Skipped);
@@ -58,6 +90,21 @@ impl RegistryComponent {
             self.component_name,
             full_path_component_without_name_rs,
         )?;
+        if format && let Err(err) = format_file(&component_dir.join("mod.rs")) {
+            eprintln!("⚠️  mod.rs left unformatted: {err}");
+        }
+
+        for (target, file) in files {
+            let mut content = file.content.clone();
+            if file.language == "rust" || target.extension().is_some_and(|ext| ext == "rs") {
+                content = import_aliases.rewrite(&content);
+                if format {
+                    let dir = target.parent().unwrap_or(&component_dir);
+                    content = format_or_keep(&content, dir, &file.path);
+                }
+            }
+            write_component_file(&target, &content, force)?;
+        }
 
         Ok(WriteOutcome::Written)
     }
@@ -67,6 +114,37 @@ impl RegistryComponent {
 /*                     ✨ HELPERS ✨                          */
 /* ========================================================== */
 
+/// Fail when the registry index lists files for `component_name` that its page does not declare,
+/// rather than installing it without them.
+fn check_index_files(component_name: &str, index_files: &[String], files: &[RegistryFile]) -> CliResult<()> {
+    let missing: Vec<&str> = index_files
+        .iter()
+        .filter(|path| !files.iter().any(|file| &file.path == *path))
+        .map(String::as_str)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(CliError::validation(&format!(
+        "registry.json lists {} for '{component_name}', but its page does not declare them",
+        missing.join(", ")
+    )))
+}
+
+/// Where a component file with registry `path` goes: under `component_dir`, or under the project
+/// root for paths starting with `/` (`/style/carousel.css`). Paths may not leave either.
+pub fn registry_file_target(component_dir: &Path, path: &str) -> CliResult<PathBuf> {
+    let (root, relative) = match path.strip_prefix('/') {
+        Some(relative) => (Path::new(""), Path::new(relative)),
+        None => (component_dir, Path::new(path)),
+    };
+    let stays_inside = relative.components().all(|component| matches!(component, Component::Normal(_)));
+    if relative.as_os_str().is_empty() || !stays_inside {
+        return Err(CliError::invalid_path(path, "registry files must stay inside the project"));
+    }
+    Ok(root.join(relative))
+}
+
 /// Write a component file to disk. If the file already exists and `force` is
 /// false, prompt the user. Returns whether the file was written or skipped.
 pub fn write_component_file(path: &Path, content: &str, force: bool) -> CliResult<WriteOutcome> {
@@ -218,9 +296,63 @@ mod tests {
         assert!(mod_rs.contains("pub mod button;"));
         assert!(mod_rs.contains("pub mod badge;"));
     }
+
+    #[test]
+    fn adds_component_whose_name_prefixes_an_existing_one() {
+        let dir = TempDir::new().unwrap();
+        let subdir = dir.path().join("ui");
+        fs::create_dir_all(&subdir).unwrap();
+        fs::write(subdir.join("mod.rs"), "pub mod button_group;\n").unwrap();
+
+        write_component_name_in_mod_rs_if_not_exists(
+            "button".to_string(),
+            subdir.to_str().unwrap().to_string(),
+        )
+        .unwrap();
+
+        let mod_rs = fs::read_to_string(subdir.join("mod.rs")).unwrap();
+        assert_eq!(mod_rs, "pub mod button;\npub mod button_group;\n");
+    }
+
+    // --- registry_file_target ---
+
+    #[test]
+    fn registry_files_go_next_to_the_component_or_under_the_project_root() {
+        let component_dir = Path::new("src/components/ui");
+        assert_eq!(
+            registry_file_target(component_dir, "carousel/slides.rs").unwrap(),
+            Path::new("src/components/ui/carousel/slides.rs")
+        );
+        assert_eq!(
+            registry_file_target(component_dir, "/style/carousel.css").unwrap(),
+            Path::new("style/carousel.css")
+        );
+        assert!(registry_file_target(component_dir, "../../main.rs").is_err());
+        assert!(registry_file_target(component_dir, "//etc/passwd").is_err());
+        assert!(registry_file_target(component_dir, "/").is_err());
+    }
+
+    // --- check_index_files ---
+
+    #[test]
+    fn index_files_must_be_declared_by_the_page() {
+        let file = |path: &str| RegistryFile {
+            path: path.to_string(),
+            language: "rust".to_string(),
+            content: String::new(),
+        };
+        let files = [file("carousel/slides.rs"), file("/style/carousel.css")];
+
+        assert!(check_index_files("carousel", &[], &files).is_ok());
+        assert!(check_index_files("carousel", &["/style/carousel.css".to_string()], &files).is_ok());
+
+        let listed = ["carousel/slides.rs".to_string(), "carousel/dots.rs".to_string()];
+        let err = check_index_files("carousel", &listed, &files).unwrap_err().to_string();
+        assert!(err.contains("carousel/dots.rs") && !err.contains("slides"), "{err}");
+    }
 }
 
-fn write_component_name_in_mod_rs_if_not_exists(
+pub fn write_component_name_in_mod_rs_if_not_exists(
     component_name: String,
     full_path_component_without_name_rs: String,
 ) -> CliResult<()> {
@@ -231,25 +363,7 @@ fn write_component_name_in_mod_rs_if_not_exists(
         mod_rs_path.parent().ok_or_else(|| CliError::file_operation("Failed to get parent directory"))?;
     std::fs::create_dir_all(dir).map_err(|_| CliError::directory_create_failed())?;
 
-    // Check if the mod.rs file already exists
-    let mut mod_rs_content = String::new();
-    if mod_rs_path.exists() {
-        mod_rs_content = std::fs::read_to_string(&mod_rs_path).map_err(|_| CliError::file_read_failed())?;
-    }
-
-    // Check if the component already exists
-    if mod_rs_content.contains(&component_name) {
-        return Ok(());
-    }
-
-    // Append the component name to mod.rs
-    let mut mod_rs_file = std::fs::OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(&mod_rs_path)
-        .map_err(|_| CliError::file_operation("Failed to open mod.rs file"))?;
-
-    // Write the new component name
-    writeln!(mod_rs_file, "pub mod {component_name};").map_err(|_| CliError::file_write_failed())?;
+    // Exact `mod` items only: `button` is not declared by `pub mod button_group;`
+    insert_mod_item_in_file(&mod_rs_path, &[&component_name], true)?;
     Ok(())
 }
@@ -0,0 +1,134 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `registry.json` — structured index of the registry, used instead of `tree.md` when the registry
+/// publishes one. Its JSON Schema is published in `schemas/registry.schema.json`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct RegistryIndex {
+    /// Format version of the index.
+    #[serde(default)]
+    pub version: u32,
+    pub components: Vec<IndexComponent>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct IndexComponent {
+    pub name: String,
+    /// Directory the component is installed into (`ui`, `demos`, `hooks`, `blocks`, ...).
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Files the component page declares besides `<name>.rs` (`carousel/slides.rs`, `/style/carousel.css`);
+    /// `ui add` refuses to install the component when its page lacks one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Registry components it uses directly; theirs are resolved from their own entries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registry_dependencies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cargo_dependencies: Vec<IndexCargoDependency>,
+    /// npm packages, installed with the project's package manager.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub npm_dependencies: Vec<String>,
+    /// Site paths of JS files, downloaded into `public/` (`/hooks/lock_scroll.js`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub js_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct IndexCargoDependency {
+    pub name: String,
+    /// Version requirement, e.g. `0.27`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// Added as an optional dependency (`optional = true`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    /// Set to `false` to add the crate with `default-features = false`.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub default_features: bool,
+}
+
+impl Default for IndexCargoDependency {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            version: None,
+            features: Vec::new(),
+            optional: false,
+            default_features: true,
+        }
+    }
+}
+
+impl RegistryIndex {
+    pub fn parse(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&IndexComponent> {
+        self.components.iter().find(|component| component.name == name)
+    }
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_fields_default_to_empty() {
+        let index = RegistryIndex::parse(
+            r#"{
+                "components": [
+                    { "name": "badge", "category": "ui" },
+                    {
+                        "name": "select",
+                        "category": "ui",
+                        "description": "A list of options",
+                        "tags": ["form"],
+                        "registry_dependencies": ["badge"],
+                        "cargo_dependencies": [{ "name": "strum", "version": "0.27", "features": ["derive"] }],
+                        "js_files": ["/hooks/lock_scroll.js"],
+                        "unknown_field": true
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(index.version, 0);
+        assert_eq!(
+            index.get("badge").unwrap(),
+            &IndexComponent {
+                name: "badge".to_string(),
+                category: "ui".to_string(),
+                ..IndexComponent::default()
+            }
+        );
+        let select = index.get("select").unwrap();
+        assert_eq!(select.description.as_deref(), Some("A list of options"));
+        assert_eq!(select.cargo_dependencies[0].features, vec!["derive".to_string()]);
+        assert!(!select.cargo_dependencies[0].optional && select.cargo_dependencies[0].default_features);
+        assert!(index.get("missing").is_none());
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::shared::cli_error::{CliError, CliResult};
+
+/// Edition rustfmt formats with when neither `rustfmt.toml` nor the crate set one (as `cargo new`).
+const DEFAULT_EDITION: &str = "2021";
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// `source` formatted by `rustfmt` as a file of `dir`: with the `rustfmt.toml` found from there
+/// and its `edition`, else the edition of the crate.
+pub fn format_source(source: &str, dir: &Path) -> CliResult<String> {
+    let work_dir = existing_ancestor(dir);
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", &project_edition(&work_dir), "--quiet"])
+        .current_dir(&work_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| CliError::file_operation(&format!("Failed to run rustfmt: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(source.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or("unknown error");
+        return Err(CliError::file_operation(&format!("rustfmt failed: {reason}")));
+    }
+    String::from_utf8(output.stdout).map_err(|_| CliError::file_operation("rustfmt output is not UTF-8"))
+}
+
+/// `source` formatted for `dir`, or unchanged (with a warning) when rustfmt fails on it.
+pub fn format_or_keep(source: &str, dir: &Path, label: &str) -> String {
+    format_source(source, dir).unwrap_or_else(|err| {
+        eprintln!("⚠️  {label} left unformatted: {err}");
+        source.to_string()
+    })
+}
+
+/// `source` formatted for `dir`, or unchanged when rustfmt fails on it or is not installed:
+/// `ui diff` / `ui update` compare both sides this way.
+pub fn format_or_same(source: &str, dir: &Path) -> String {
+    format_source(source, dir).unwrap_or_else(|_| source.to_string())
+}
+
+/// Format the file at `path` in place; returns whether it changed.
+pub fn format_file(path: &Path) -> CliResult<bool> {
+    let source = std::fs::read_to_string(path).map_err(|_| CliError::file_read_failed())?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let formatted = format_source(&source, dir)?;
+    if formatted == source {
+        return Ok(false);
+    }
+    std::fs::write(path, formatted).map_err(|_| CliError::file_write_failed())?;
+    Ok(true)
+}
+
+/// Edition of the code in `dir`: the `edition` of the nearest `rustfmt.toml` / `.rustfmt.toml`,
+/// else the `[package] edition` of the crate (following `edition.workspace = true`).
+pub fn project_edition(dir: &Path) -> String {
+    let rustfmt_edition = dir.ancestors().find_map(|ancestor| {
+        ["rustfmt.toml", ".rustfmt.toml"]
+            .iter()
+            .find_map(|name| read_table(&ancestor.join(name)))
+            .map(|table| table.get("edition").and_then(|edition| edition.as_str()).map(str::to_string))
+    });
+    if let Some(edition) = rustfmt_edition.flatten() {
+        return edition;
+    }
+    crate_edition(dir).unwrap_or_else(|| DEFAULT_EDITION.to_string())
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn crate_edition(dir: &Path) -> Option<String> {
+    let mut manifests = dir.ancestors().filter_map(|ancestor| read_table(&ancestor.join("Cargo.toml")));
+    let manifest = manifests.find(|manifest| manifest.contains_key("package"))?;
+    match manifest.get("package").and_then(|package| package.get("edition")) {
+        // Cargo's default when the key is missing
+        None => Some("2015".to_string()),
+        Some(toml::Value::String(edition)) => Some(edition.clone()),
+        // `edition.workspace = true`: the closest manifest with a `[workspace]`, this one included
+        Some(_) => std::iter::once(manifest.clone()).chain(manifests).find_map(|manifest| {
+            let edition = manifest.get("workspace")?.get("package")?.get("edition")?;
+            edition.as_str().map(str::to_string)
+        }),
+    }
+}
+
+fn read_table(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// `dir`, or its closest ancestor on disk: rustfmt runs from there before the directory exists.
+fn existing_ancestor(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.is_dir())
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn edition_comes_from_rustfmt_toml_then_the_crate() {
+        let dir = TempDir::new().unwrap();
+        let member = dir.path().join("app");
+        fs::create_dir_all(member.join("src")).unwrap();
+        assert_eq!(project_edition(&member.join("src")), DEFAULT_EDITION);
+
+        fs::write(dir.path().join("Cargo.toml"), "[workspace]\n\n[workspace.package]\nedition = \"2024\"\n")
+            .unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"app\"\nedition.workspace = true\n")
+            .unwrap();
+        assert_eq!(project_edition(&member.join("src/components")), "2024");
+
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"app\"\nedition = \"2018\"\n").unwrap();
+        assert_eq!(project_edition(&member.join("src")), "2018");
+
+        fs::write(dir.path().join("rustfmt.toml"), "edition = \"2021\"\nmax_width = 80\n").unwrap();
+        assert_eq!(project_edition(&member.join("src")), "2021");
+    }
+
+    #[test]
+    fn formats_with_the_project_rustfmt_toml() {
+        let dir = TempDir::new().unwrap();
+        let source = "use leptos::prelude::*;\nfn  main( ){ let label = \"a long enough string\"; }\n";
+
+        let formatted = format_source(source, &dir.path().join("src/components")).unwrap();
+        assert_eq!(
+            formatted,
+            "use leptos::prelude::*;\nfn main() {\n    let label = \"a long enough string\";\n}\n"
+        );
+
+        fs::write(dir.path().join("rustfmt.toml"), "tab_spaces = 2\n").unwrap();
+        let formatted = format_source(source, dir.path()).unwrap();
+        assert!(formatted.contains("\n  let label"), "{formatted}");
+    }
+
+    #[test]
+    fn invalid_source_is_kept_as_is() {
+        let dir = TempDir::new().unwrap();
+        assert!(format_source("fn main( {", dir.path()).is_err());
+        assert_eq!(format_or_keep("fn main( {", dir.path(), "broken.rs"), "fn main( {");
+        assert_eq!(format_or_same("fn main( {", dir.path()), "fn main( {");
+    }
+
+    #[test]
+    fn format_file_rewrites_only_when_needed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mod.rs");
+        fs::write(&path, "pub  mod button;\n").unwrap();
+        assert!(format_file(&path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "pub mod button;\n");
+        assert!(!format_file(&path).unwrap());
+    }
+}
@@ -1,6 +1,10 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 
+use super::cargo_dep_spec::CargoDepSpec;
+use super::component_type::ComponentType;
+use super::registry_index::RegistryIndex;
 use crate::shared::cli_error::CliResult;
+use crate::shared::rust_ui_client::RustUIClient;
 
 #[derive(Debug, Clone)]
 pub struct TreeParser {
@@ -11,8 +15,14 @@ pub struct TreeParser {
 pub struct ComponentEntry {
     pub name: String,
     pub category: String,
+    /// Only `registry.json` carries a description and tags.
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// Files `registry.json` lists besides `<name>.rs`; tree.md does not list them.
+    pub files: Vec<String>,
     pub dependencies: Vec<String>,
     pub cargo_deps: Vec<String>,
+    pub npm_deps: Vec<String>,
     pub js_files: Vec<String>,
 }
 
@@ -21,10 +31,63 @@ pub struct ResolvedSet {
     pub components: HashSet<String>,
     pub cargo_deps: HashSet<String>,
     pub parent_dirs: HashSet<String>,
+    pub npm_deps: HashSet<String>,
     pub js_files: HashSet<String>,
 }
 
 impl TreeParser {
+    /// The registry index: `registry.json` when the registry publishes one, else `tree.md`.
+    pub async fn fetch() -> CliResult<Self> {
+        if let Ok(Some(json)) = RustUIClient::fetch_registry_json().await {
+            match RegistryIndex::parse(&json) {
+                Ok(index) => return Ok(Self::from_index(&index)),
+                Err(err) => eprintln!("⚠️  Ignoring invalid registry.json ({err}), using tree.md"),
+            }
+        }
+        let tree_content = RustUIClient::fetch_tree_md().await?;
+        Self::parse_tree_md(&tree_content)
+    }
+
+    /// Entries of `registry.json`, with the dependencies of each component flattened as in
+    /// tree.md: its registry dependencies at any depth, and their cargo, npm and JS dependencies.
+    pub fn from_index(index: &RegistryIndex) -> Self {
+        let mut components = HashMap::new();
+        for component in &index.components {
+            let mut entry = ComponentEntry {
+                name: component.name.clone(),
+                category: component.category.clone(),
+                description: component.description.clone(),
+                tags: component.tags.clone(),
+                files: component.files.clone(),
+                dependencies: Vec::new(),
+                cargo_deps: Vec::new(),
+                npm_deps: Vec::new(),
+                js_files: Vec::new(),
+            };
+            let mut pending = vec![component];
+            let mut seen = HashSet::from([component.name.as_str()]);
+            while let Some(current) = pending.pop() {
+                for dep in &current.cargo_dependencies {
+                    push_unique(&mut entry.cargo_deps, &CargoDepSpec::from(dep).to_string());
+                }
+                for npm_dep in &current.npm_dependencies {
+                    push_unique(&mut entry.npm_deps, npm_dep);
+                }
+                for js_file in &current.js_files {
+                    push_unique(&mut entry.js_files, js_file);
+                }
+                for dep_name in &current.registry_dependencies {
+                    if seen.insert(dep_name) {
+                        entry.dependencies.push(dep_name.clone());
+                        pending.extend(index.get(dep_name));
+                    }
+                }
+            }
+            components.insert(component.name.clone(), entry);
+        }
+        TreeParser { components }
+    }
+
     pub fn parse_tree_md(content: &str) -> CliResult<Self> {
         let mut components = HashMap::new();
         let mut current_component: Option<ComponentEntry> = None;
@@ -52,8 +115,12 @@ impl TreeParser {
                     current_component = Some(ComponentEntry {
                         name: name.clone(),
                         category,
+                        description: None,
+                        tags: Vec::new(),
+                        files: Vec::new(),
                         dependencies: Vec::new(),
                         cargo_deps: Vec::new(),
+                        npm_deps: Vec::new(),
                         js_files: Vec::new(),
                     });
 
@@ -75,6 +142,11 @@ impl TreeParser {
                     if let Some(ref mut component) = current_component {
                         component.js_files.push(js_file);
                     }
+                } else if let Some(npm_package) = dep_content.strip_prefix("npm: ") {
+                    // npm package dependency
+                    if let Some(ref mut component) = current_component {
+                        component.npm_deps.push(npm_package.trim().to_string());
+                    }
                 } else if let Some((dep_name, _)) = dep_content.rsplit_once(" (") {
                     // Registry dependency
                     let dep_name = dep_name.trim().to_string();
@@ -101,6 +173,11 @@ impl TreeParser {
                     if let Some(ref mut component) = current_component {
                         component.js_files.push(js_file);
                     }
+                } else if let Some(npm_package) = dep_content.strip_prefix("npm: ") {
+                    // Nested npm package dependency - add to root component
+                    if let Some(ref mut component) = current_component {
+                        component.npm_deps.push(npm_package.trim().to_string());
+                    }
                 } else if let Some((dep_name, _)) = dep_content.rsplit_once(" (") {
                     // Nested registry dependency - add to root component
                     let dep_name = dep_name.trim().to_string();
@@ -137,6 +214,44 @@ impl TreeParser {
         map
     }
 
+    /// Components whose name, tag or description contains `query` (case-insensitive), grouped
+    /// like `get_components_by_category`.
+    pub fn search(&self, query: &str) -> BTreeMap<String, Vec<String>> {
+        let query = query.to_lowercase();
+        let matches = |text: &str| text.to_lowercase().contains(&query);
+        let mut by_category = self.get_components_by_category();
+        for names in by_category.values_mut() {
+            names.retain(|name| {
+                self.components.get(name).is_some_and(|entry| {
+                    matches(&entry.name)
+                        || entry.tags.iter().any(|tag| matches(tag))
+                        || entry.description.as_deref().is_some_and(matches)
+                })
+            });
+        }
+        by_category.retain(|_, names| !names.is_empty());
+        by_category
+    }
+
+    /// Categories declared in the registry, sorted.
+    pub fn get_categories(&self) -> Vec<String> {
+        self.get_components_by_category().into_keys().collect()
+    }
+
+    /// Category of `component_name` as declared in the registry; components missing from it
+    /// fall back to the naming convention (`demo_*`, `use_*`, ...).
+    pub fn category_of(&self, component_name: &str) -> String {
+        self.components.get(component_name).map_or_else(
+            || ComponentType::from_component_name(component_name).to_path(),
+            |entry| entry.category.clone(),
+        )
+    }
+
+    /// Files the index lists for `component_name` besides `<name>.rs` (see `ComponentEntry::files`).
+    pub fn files_of(&self, component_name: &str) -> &[String] {
+        self.components.get(component_name).map_or(&[], |entry| entry.files.as_slice())
+    }
+
     pub fn get_dependencies_map(&self) -> HashMap<String, Vec<String>> {
         self.components
             .iter()
@@ -148,6 +263,7 @@ impl TreeParser {
         let mut resolved_components = HashSet::new();
         let mut resolved_cargo_deps = HashSet::new();
         let mut resolved_parent_dirs = HashSet::new();
+        let mut resolved_npm_deps = HashSet::new();
         let mut resolved_js_files = HashSet::new();
 
         // Process each user component
@@ -172,6 +288,11 @@ impl TreeParser {
                     resolved_cargo_deps.insert(cargo_dep.clone());
                 }
 
+                // Add npm package dependencies
+                for npm_dep in &component_entry.npm_deps {
+                    resolved_npm_deps.insert(npm_dep.clone());
+                }
+
                 // Add JS file dependencies
                 for js_file in &component_entry.js_files {
                     resolved_js_files.insert(js_file.clone());
@@ -192,11 +313,18 @@ impl TreeParser {
             components: resolved_components,
             cargo_deps: resolved_cargo_deps,
             parent_dirs: resolved_parent_dirs,
+            npm_deps: resolved_npm_deps,
             js_files: resolved_js_files,
         })
     }
 }
 
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|existing| existing == value) {
+        list.push(value.to_string());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,6 +379,14 @@ mod tests {
         assert!(entry.cargo_deps.contains(&"some-crate".to_string()));
     }
 
+    #[test]
+    fn parse_tree_md_keeps_cargo_dep_specs() {
+        let tree = "* select (ui)\n** cargo: strum@0.27[derive, phf] no-default-features\n";
+        let parser = TreeParser::parse_tree_md(tree).unwrap();
+        let entry = parser.components.get("select").unwrap();
+        assert_eq!(entry.cargo_deps, vec!["strum@0.27[derive, phf] no-default-features"]);
+    }
+
     #[test]
     fn parse_tree_md_extracts_category() {
         let parser = TreeParser::parse_tree_md(SAMPLE_TREE).unwrap();
@@ -258,6 +394,15 @@ mod tests {
         assert_eq!(parser.components.get("demo_button").unwrap().category, "demos");
     }
 
+    #[test]
+    fn category_of_uses_the_registry_category() {
+        let parser = TreeParser::parse_tree_md("* hero (blocks)\n* use_lock_scroll (ui)\n").unwrap();
+        assert_eq!(parser.category_of("hero"), "blocks");
+        assert_eq!(parser.category_of("use_lock_scroll"), "ui");
+        assert_eq!(parser.category_of("use_missing"), "hooks");
+        assert_eq!(parser.get_categories(), vec!["blocks".to_string(), "ui".to_string()]);
+    }
+
     #[test]
     fn get_all_component_names_sorted() {
         let parser = TreeParser::parse_tree_md(SAMPLE_TREE).unwrap();
@@ -363,4 +508,63 @@ mod tests {
         sorted.sort();
         assert_eq!(keys, sorted);
     }
+
+    #[test]
+    fn parse_tree_md_extracts_npm_deps() {
+        let tree = "* chart (ui)\n** npm: chart.js\n** tooltip (ui)\n*** npm: @floating-ui/dom\n";
+        let parser = TreeParser::parse_tree_md(tree).unwrap();
+        let resolved = parser.resolve_dependencies(&["chart".to_string()]).unwrap();
+        let expected = HashSet::from(["chart.js".to_string(), "@floating-ui/dom".to_string()]);
+        assert_eq!(resolved.npm_deps, expected);
+    }
+
+    const SAMPLE_INDEX: &str = r#"{
+        "components": [
+            { "name": "badge", "category": "ui", "cargo_dependencies": [{ "name": "tw_merge" }] },
+            { "name": "button", "category": "ui", "registry_dependencies": ["badge"], "tags": ["form"] },
+            {
+                "name": "card",
+                "category": "ui",
+                "description": "A container with a header",
+                "registry_dependencies": ["button"],
+                "cargo_dependencies": [{ "name": "strum", "version": "0.27", "features": ["derive"] }],
+                "npm_dependencies": ["embla-carousel"],
+                "js_files": ["/hooks/lock_scroll.js"],
+                "files": ["card/header.rs", "/style/card.css"]
+            },
+            { "name": "hero", "category": "blocks", "registry_dependencies": ["card", "button"] }
+        ]
+    }"#;
+
+    #[test]
+    fn from_index_flattens_dependencies_like_tree_md() {
+        let parser = TreeParser::from_index(&RegistryIndex::parse(SAMPLE_INDEX).unwrap());
+        assert_eq!(parser.category_of("hero"), "blocks");
+
+        let deps = parser.get_dependencies_map();
+        assert_eq!(deps.get("card").unwrap(), &vec!["button", "badge"]);
+        assert_eq!(deps.get("hero").unwrap(), &vec!["card", "button", "badge"]);
+
+        let resolved = parser.resolve_dependencies(&["hero".to_string()]).unwrap();
+        assert_eq!(resolved.components.len(), 4);
+        let cargo_deps = HashSet::from(["strum@0.27[derive]".to_string(), "tw_merge".to_string()]);
+        assert_eq!(resolved.cargo_deps, cargo_deps);
+        assert_eq!(resolved.npm_deps, HashSet::from(["embla-carousel".to_string()]));
+        assert_eq!(resolved.parent_dirs, HashSet::from(["blocks".to_string(), "ui".to_string()]));
+
+        // Files stay with their own component
+        assert_eq!(parser.files_of("card"), ["card/header.rs", "/style/card.css"]);
+        assert!(parser.files_of("hero").is_empty());
+        assert!(parser.files_of("unknown").is_empty());
+    }
+
+    #[test]
+    fn search_matches_names_tags_and_descriptions() {
+        let parser = TreeParser::from_index(&RegistryIndex::parse(SAMPLE_INDEX).unwrap());
+        let names = |query: &str| parser.search(query).into_values().flatten().collect::<Vec<_>>();
+        assert_eq!(names("FORM"), vec!["button"]);
+        assert_eq!(names("header"), vec!["card"]);
+        assert_eq!(names("b"), vec!["badge", "button"]);
+        assert!(parser.search("zzz").is_empty());
+    }
 }
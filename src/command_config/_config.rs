@@ -0,0 +1,291 @@
+use std::fs;
+use std::path::Path;
+
+use clap::{Arg, ArgMatches, Command};
+
+use super::keys::{ConfigKey, config_entries, set_config_value};
+use super::layers::{LayeredConfig, load_settings};
+use super::migrate::{CONFIG_VERSION, LoadedConfig, load_ui_config, migrate_ui_config, print_warnings_once};
+use super::schema::{registry_index_schema, ui_config_keys, ui_config_schema};
+use crate::command_init::config::UiConfig;
+use crate::command_init::dry_run::{FileChange, format_file_diff};
+use crate::shared::cli_error::{CliError, CliResult};
+
+const UI_CONFIG_TOML: &str = "ui_config.toml";
+
+/* ========================================================== */
+/*                      🔧 CLAP COMMANDS 🔧                   */
+/* ========================================================== */
+
+pub fn command_config() -> Command {
+    Command::new("config")
+        .about("Inspect, change and upgrade ui_config.toml")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("list").about("List the effective settings").arg(
+                Arg::new("show_origin")
+                    .long("show-origin")
+                    .help("Show where each value comes from (default, user config, ui_config.toml or UI_* variable)")
+                    .action(clap::ArgAction::SetTrue),
+            ),
+        )
+        .subcommand(
+            Command::new("get")
+                .about("Print one setting of ui_config.toml")
+                .arg(Arg::new("key").help("Setting name, e.g. base_color").required(true)),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("Change one setting of ui_config.toml, keeping comments and formatting")
+                .arg(Arg::new("key").help("Setting name, e.g. base_path_components").required(true))
+                .arg(Arg::new("value").help("New value").required(true)),
+        )
+        .subcommand(
+            Command::new("migrate").about("Upgrade ui_config.toml to the current schema version").arg(
+                Arg::new("dry_run")
+                    .long("dry-run")
+                    .help("Print the changes without writing ui_config.toml")
+                    .action(clap::ArgAction::SetTrue),
+            ),
+        )
+        .subcommand(
+            Command::new("schema")
+                .about("Print the JSON Schema of ui_config.toml")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("PATH")
+                        .help("Write the schema to PATH instead of stdout"),
+                )
+                .arg(
+                    Arg::new("registry")
+                        .long("registry")
+                        .help("Print the schema of the registry.json index instead")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+}
+
+/* ========================================================== */
+/*                      🦀 PROCESS FNS 🦀                     */
+/* ========================================================== */
+
+pub fn process_config(matches: &ArgMatches) -> CliResult<()> {
+    match matches.subcommand() {
+        Some(("list", list_matches)) => {
+            if !Path::new(UI_CONFIG_TOML).exists() {
+                return Err(CliError::project_not_initialized());
+            }
+            let layered = load_settings()?;
+            print_warnings_once(&layered.warnings);
+            let entries = config_entries(&layered.ui_config()?)?;
+            let output = if list_matches.get_flag("show_origin") {
+                format_entries_with_origin(&entries, &layered)
+            } else {
+                format_entries(&entries)
+            };
+            println!("{output}");
+            Ok(())
+        }
+        Some(("get", get_matches)) => {
+            let key = get_matches.get_one::<String>("key").map(String::as_str).unwrap_or_default();
+            let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
+            println!("{}", config_value(&config_entries(&config)?, key)?);
+            Ok(())
+        }
+        Some(("set", set_matches)) => {
+            let key = set_matches.get_one::<String>("key").map(String::as_str).unwrap_or_default();
+            let value = set_matches.get_one::<String>("value").map(String::as_str).unwrap_or_default();
+            process_config_set(key, value)
+        }
+        Some(("migrate", migrate_matches)) => process_config_migrate(migrate_matches),
+        Some(("schema", schema_matches)) => {
+            let registry = schema_matches.get_flag("registry");
+            let schema = if registry { registry_index_schema() } else { ui_config_schema() };
+            match schema_matches.get_one::<String>("output") {
+                Some(path) => {
+                    fs::write(path, schema)?;
+                    println!("✔️ JSON Schema written to {path}");
+                }
+                None => print!("{schema}"),
+            }
+            Ok(())
+        }
+        _ => Err(CliError::validation("Unknown config subcommand")),
+    }
+}
+
+fn process_config_set(key: &str, value: &str) -> CliResult<()> {
+    if !Path::new(UI_CONFIG_TOML).exists() {
+        return Err(CliError::project_not_initialized());
+    }
+    let key = ConfigKey::parse(key)?;
+    let value = key.normalize(value, Path::new("."))?;
+    let contents = fs::read_to_string(UI_CONFIG_TOML)?;
+    fs::write(UI_CONFIG_TOML, set_config_value(&contents, key, &value)?)?;
+    println!("✔️ Set {} to {value}", key.name());
+    Ok(())
+}
+
+fn process_config_migrate(matches: &ArgMatches) -> CliResult<()> {
+    if !Path::new(UI_CONFIG_TOML).exists() {
+        return Err(CliError::project_not_initialized());
+    }
+    let before = fs::read_to_string(UI_CONFIG_TOML)?;
+    let loaded = load_ui_config(&before)?;
+    if is_up_to_date(&loaded) {
+        println!("✔️ {UI_CONFIG_TOML} is already at version {CONFIG_VERSION}.");
+        return Ok(());
+    }
+
+    let after = migrate_ui_config(&before, &loaded)?;
+    if matches.get_flag("dry_run") {
+        let change = FileChange { path: UI_CONFIG_TOML.to_string(), before: Some(before), after };
+        println!("{}", format_migration(&loaded));
+        println!("\n{}", format_file_diff(&change));
+        return Ok(());
+    }
+
+    fs::write(UI_CONFIG_TOML, after)?;
+    println!("{}", format_migration(&loaded));
+    Ok(())
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Value of `key` among `entries`; known keys that are unset are an error too.
+fn config_value<'a>(entries: &'a [(String, String)], key: &str) -> CliResult<&'a str> {
+    if let Some((_, value)) = entries.iter().find(|(name, _)| name == key) {
+        return Ok(value);
+    }
+    if ui_config_keys().contains(key) {
+        Err(CliError::validation(&format!("`{key}` is not set in {UI_CONFIG_TOML}")))
+    } else {
+        Err(CliError::validation(&format!("Unknown key '{key}'")))
+    }
+}
+
+/// Nothing to rewrite: current version and no keys to drop.
+fn is_up_to_date(loaded: &LoadedConfig) -> bool {
+    loaded.from_version == CONFIG_VERSION && loaded.unknown_keys.is_empty()
+}
+
+/* ========================================================== */
+/*                      🖨  FORMATTERS 🖨                      */
+/* ========================================================== */
+
+fn format_entries(entries: &[(String, String)]) -> String {
+    let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or_default();
+    entries.iter().map(|(key, value)| format!("{key:<width$} = {value}")).collect::<Vec<_>>().join("\n")
+}
+
+fn format_entries_with_origin(entries: &[(String, String)], layered: &LayeredConfig) -> String {
+    let origins: Vec<String> = entries.iter().map(|(key, _)| layered.origin(key).to_string()).collect();
+    let origin_width = origins.iter().map(String::len).max().unwrap_or_default();
+    let key_width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or_default();
+    entries
+        .iter()
+        .zip(origins)
+        .map(|((key, value), origin)| format!("{origin:<origin_width$}  {key:<key_width$} = {value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_migration(loaded: &LoadedConfig) -> String {
+    let mut lines = if loaded.from_version < CONFIG_VERSION {
+        vec![format!(
+            "✔️ Migrated {UI_CONFIG_TOML} from version {} to {CONFIG_VERSION}:",
+            loaded.from_version
+        )]
+    } else {
+        vec![format!("✔️ Cleaned up {UI_CONFIG_TOML}:")]
+    };
+    lines.extend(loaded.changes.iter().map(|change| format!("  - {change}")));
+    lines.extend(loaded.unknown_keys.iter().map(|key| format!("  - removed unknown key `{key}`")));
+    lines.join("\n")
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_config::layers::load_layers;
+
+    #[test]
+    fn config_subcommands_parse() {
+        let matches = command_config().try_get_matches_from(["config", "migrate", "--dry-run"]).unwrap();
+        let (name, sub) = matches.subcommand().unwrap();
+        assert_eq!(name, "migrate");
+        assert!(sub.get_flag("dry_run"));
+
+        let matches = command_config().try_get_matches_from(["config", "schema", "-o", "s.json"]).unwrap();
+        let (_, sub) = matches.subcommand().unwrap();
+        assert_eq!(sub.get_one::<String>("output").map(String::as_str), Some("s.json"));
+        assert!(!sub.get_flag("registry"));
+
+        let matches = command_config().try_get_matches_from(["config", "set", "base_color", "zinc"]).unwrap();
+        let (_, sub) = matches.subcommand().unwrap();
+        assert_eq!(sub.get_one::<String>("value").map(String::as_str), Some("zinc"));
+
+        assert!(command_config().try_get_matches_from(["config"]).is_err());
+        assert!(command_config().try_get_matches_from(["config", "set", "base_color"]).is_err());
+    }
+
+    #[test]
+    fn get_distinguishes_unset_and_unknown_keys() {
+        let entries = vec![("base_color".to_string(), "zinc".to_string())];
+        assert_eq!(config_value(&entries, "base_color").unwrap(), "zinc");
+        assert!(config_value(&entries, "package").unwrap_err().to_string().contains("not set"));
+        assert!(config_value(&entries, "style").unwrap_err().to_string().contains("Unknown key"));
+        assert_eq!(format_entries(&entries), "base_color = zinc");
+    }
+
+    #[test]
+    fn show_origin_prefixes_each_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join("ui_config.toml");
+        std::fs::write(&project, "version = 1\nbase_color = \"zinc\"\nbase_path_components = \"src/ui\"\n")
+            .unwrap();
+        let layered =
+            load_layers(&project, None, &|name| (name == "UI_COLOR").then(|| "0".to_string())).unwrap();
+        let entries = config_entries(&layered.ui_config().unwrap()).unwrap();
+
+        let output = format_entries_with_origin(&entries, &layered);
+        let project_origin = format!("project:{}", project.display());
+        assert!(
+            output
+                .lines()
+                .any(|l| l.starts_with(&project_origin) && l.ends_with("base_color           = zinc"))
+        );
+        assert!(
+            output.lines().any(|l| l.starts_with("default") && l.ends_with("color_theme          = default"))
+        );
+        assert!(
+            output
+                .lines()
+                .any(|l| l.starts_with("env:UI_COLOR") && l.ends_with("color                = false"))
+        );
+    }
+
+    #[test]
+    fn migration_summary_lists_changes_and_dropped_keys() {
+        let contents = "base_color = \"zinc\"\nbase_path_components = \"src/ui\"\ntailwind_input_file = \"style/t.css\"\nstyle = \"x\"\n";
+        let loaded = load_ui_config(contents).unwrap();
+        assert!(!is_up_to_date(&loaded));
+
+        let summary = format_migration(&loaded);
+        assert!(summary.starts_with("✔️ Migrated ui_config.toml from version 0 to 1:"));
+        assert!(summary.contains("removed `tailwind_input_file = \"style/t.css\"`"));
+        assert!(summary.contains("removed unknown key `style`"));
+
+        let migrated = migrate_ui_config(contents, &loaded).unwrap();
+        assert!(migrated.starts_with("version = 1\n"));
+        assert!(is_up_to_date(&load_ui_config(&migrated).unwrap()));
+    }
+}
@@ -0,0 +1,344 @@
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, value};
+
+use super::migrate::{CONFIG_VERSION, load_ui_config, migrate_ui_config};
+use crate::command_init::colors::{AccentColor, BaseColor};
+use crate::command_init::config::UiConfig;
+use crate::command_init::install::PackageManager;
+use crate::command_init::leptos_mode::LeptosMode;
+use crate::command_init::workspace_utils::{find_member, leptos_members_from_path};
+use crate::command_mcp::config::McpClient;
+use crate::shared::cli_error::{CliError, CliResult};
+
+/// Keys `ui config set` can change. `version` belongs to `ui config migrate`
+/// and `themes` to `ui theme add`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigKey {
+    BaseColor,
+    ColorTheme,
+    BasePathComponents,
+    PackageManager,
+    LeptosMode,
+    Package,
+    Registry,
+    McpClient,
+    Color,
+    Concurrency,
+    FormatOnWrite,
+}
+
+impl ConfigKey {
+    pub const ALL: &'static [ConfigKey] = &[
+        ConfigKey::BaseColor,
+        ConfigKey::ColorTheme,
+        ConfigKey::BasePathComponents,
+        ConfigKey::PackageManager,
+        ConfigKey::LeptosMode,
+        ConfigKey::Package,
+        ConfigKey::Registry,
+        ConfigKey::McpClient,
+        ConfigKey::Color,
+        ConfigKey::Concurrency,
+        ConfigKey::FormatOnWrite,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigKey::BaseColor => "base_color",
+            ConfigKey::ColorTheme => "color_theme",
+            ConfigKey::BasePathComponents => "base_path_components",
+            ConfigKey::PackageManager => "package_manager",
+            ConfigKey::LeptosMode => "leptos_mode",
+            ConfigKey::Package => "package",
+            ConfigKey::Registry => "registry",
+            ConfigKey::McpClient => "mcp_client",
+            ConfigKey::Color => "color",
+            ConfigKey::Concurrency => "concurrency",
+            ConfigKey::FormatOnWrite => "format_on_write",
+        }
+    }
+
+    pub fn parse(name: &str) -> CliResult<Self> {
+        if let Some(key) = Self::ALL.iter().copied().find(|key| key.name() == name) {
+            return Ok(key);
+        }
+        let message = match name {
+            "version" => "`version` is managed by `ui config migrate`".to_string(),
+            "themes" => "`themes` is managed by `ui theme add`".to_string(),
+            _ => format!(
+                "Unknown key '{name}'. Expected one of: {}",
+                Self::ALL.iter().map(ConfigKey::name).collect::<Vec<_>>().join(", ")
+            ),
+        };
+        Err(CliError::validation(&message))
+    }
+
+    /// Check `value` and return it as it should be stored (e.g. `Zinc` -> `zinc`).
+    /// Paths and workspace members are resolved from `config_dir`, the directory of ui_config.toml.
+    pub fn normalize(&self, value: &str, config_dir: &Path) -> CliResult<String> {
+        match self {
+            ConfigKey::BaseColor => Ok(BaseColor::parse(value)?.label().to_lowercase()),
+            ConfigKey::ColorTheme => Ok(AccentColor::parse(value)?.label().to_lowercase()),
+            ConfigKey::BasePathComponents => {
+                if !config_dir.join(value).is_dir() {
+                    return Err(CliError::invalid_path(value, "directory does not exist"));
+                }
+                Ok(value.trim_end_matches('/').to_string())
+            }
+            ConfigKey::PackageManager => Ok(PackageManager::parse(value)?.command().to_string()),
+            ConfigKey::LeptosMode => {
+                LeptosMode::from_str(value).map(|mode| mode.label().to_string()).ok_or_else(|| {
+                    CliError::validation(&format!(
+                        "Unknown Leptos mode '{value}'. Expected one of: csr, ssr, hydrate"
+                    ))
+                })
+            }
+            ConfigKey::Package => {
+                let members = leptos_members_from_path(config_dir)?;
+                find_member(&members, value)?;
+                Ok(value.to_string())
+            }
+            ConfigKey::Registry => {
+                if !value.starts_with("https://") && !value.starts_with("http://") {
+                    return Err(CliError::validation(&format!("Registry '{value}' must be an http(s) URL")));
+                }
+                Ok(value.trim_end_matches('/').to_string())
+            }
+            ConfigKey::McpClient => McpClient::from_str(value).map(|_| value.to_string()).ok_or_else(|| {
+                CliError::validation(&format!(
+                    "Unknown MCP client '{value}'. Expected one of: {}",
+                    McpClient::all_names().join(", ")
+                ))
+            }),
+            ConfigKey::Color | ConfigKey::FormatOnWrite => match value {
+                "true" | "false" => Ok(value.to_string()),
+                _ => Err(CliError::validation(&format!(
+                    "{} must be true or false, not '{value}'",
+                    self.name()
+                ))),
+            },
+            ConfigKey::Concurrency => match value.parse::<u32>() {
+                Ok(n) if n > 0 => Ok(n.to_string()),
+                _ => Err(CliError::validation(&format!(
+                    "concurrency must be a positive integer, not '{value}'"
+                ))),
+            },
+        }
+    }
+
+    /// TOML value of a normalized setting: booleans and integers are not quoted.
+    fn toml_value(&self, normalized: &str) -> Item {
+        match self {
+            ConfigKey::Color | ConfigKey::FormatOnWrite => value(normalized == "true"),
+            ConfigKey::Concurrency => value(normalized.parse::<i64>().unwrap_or(1)),
+            _ => value(normalized),
+        }
+    }
+}
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Effective values of `config` as `(key, value)` pairs, in schema order.
+/// Strings are shown without quotes, other values as TOML.
+pub fn config_entries(config: &UiConfig) -> CliResult<Vec<(String, String)>> {
+    let doc: DocumentMut = toml::to_string(config)?
+        .parse()
+        .map_err(|e| CliError::config(&format!("Failed to render ui_config.toml: {e}")))?;
+    Ok(doc.iter().map(|(key, item)| (key.to_string(), display_item(item))).collect())
+}
+
+/// Set `key` in the ui_config.toml `contents`, keeping comments, ordering and formatting.
+pub fn set_config_value(contents: &str, key: ConfigKey, new_value: &str) -> CliResult<String> {
+    let mut doc: DocumentMut =
+        contents.parse().map_err(|e| CliError::config(&format!("Failed to parse ui_config.toml: {e}")))?;
+
+    replace_keeping_decor(&mut doc, key.name(), key.toml_value(new_value));
+
+    let updated = doc.to_string();
+    load_ui_config(&updated)?;
+    Ok(updated)
+}
+
+/// The ui_config.toml `contents`, migrated when older, updated to hold `config`: keys whose value
+/// changes are replaced and keys `config` leaves unset removed, keeping the comments, ordering and
+/// formatting of everything else.
+pub fn update_config_contents(contents: &str, config: &UiConfig) -> CliResult<String> {
+    let loaded = load_ui_config(contents)?;
+    let contents = if loaded.from_version < CONFIG_VERSION {
+        migrate_ui_config(contents, &loaded)?
+    } else {
+        contents.to_string()
+    };
+    let mut doc: DocumentMut =
+        contents.parse().map_err(|e| CliError::config(&format!("Failed to parse ui_config.toml: {e}")))?;
+    let current: toml::Table = toml::from_str(&contents)?;
+
+    let rendered = toml::to_string(config)?;
+    let wanted: toml::Table = toml::from_str(&rendered)?;
+    let mut wanted_doc: DocumentMut =
+        rendered.parse().map_err(|e| CliError::config(&format!("Failed to render ui_config.toml: {e}")))?;
+
+    for key in current.keys().filter(|key| !wanted.contains_key(*key)) {
+        doc.remove(key);
+    }
+    for (key, wanted_value) in &wanted {
+        if current.get(key) != Some(wanted_value)
+            && let Some(item) = wanted_doc.remove(key)
+        {
+            replace_keeping_decor(&mut doc, key, item);
+        }
+    }
+
+    let updated = doc.to_string();
+    load_ui_config(&updated)?;
+    Ok(updated)
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Set `key` in `doc` to `item`, keeping the comments around the value it replaces.
+fn replace_keeping_decor(doc: &mut DocumentMut, key: &str, mut item: Item) {
+    let decor = doc.get(key).and_then(Item::as_value).map(|existing| existing.decor().clone());
+    if let (Some(decor), Some(new)) = (decor, item.as_value_mut()) {
+        *new.decor_mut() = decor;
+    }
+    doc.insert(key, item);
+}
+
+fn display_item(item: &Item) -> String {
+    match item.clone().into_value() {
+        Ok(toml_edit::Value::String(s)) => s.value().clone(),
+        Ok(mut other) => {
+            other.decor_mut().clear();
+            other.to_string()
+        }
+        Err(item) => item.to_string().trim().to_string(),
+    }
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::command_init::config::NamedTheme;
+
+    const CONFIG: &str = r#"# Managed by ui-cli
+version = 1
+base_color = "neutral" # gray scale
+color_theme = "default"
+
+base_path_components = "src/components"
+"#;
+
+    #[test]
+    fn set_preserves_comments_and_layout() {
+        let updated = set_config_value(CONFIG, ConfigKey::BaseColor, "zinc").unwrap();
+        assert_eq!(updated, CONFIG.replace("\"neutral\"", "\"zinc\""));
+
+        let added = set_config_value(CONFIG, ConfigKey::Package, "app").unwrap();
+        assert!(added.starts_with(CONFIG));
+        assert!(added.ends_with("package = \"app\"\n"));
+
+        let typed = set_config_value(CONFIG, ConfigKey::Color, "false").unwrap();
+        let typed = set_config_value(&typed, ConfigKey::Concurrency, "8").unwrap();
+        assert!(typed.ends_with("color = false\nconcurrency = 8\n"), "{typed}");
+    }
+
+    #[test]
+    fn update_replaces_changed_keys_and_keeps_the_rest() {
+        let contents = format!(
+            "{CONFIG}registry = \"https://ui.example.com\" # mirror\nconcurrency = 4\n\n[aliases]\ncomponents = \"crate::ui_kit\"\n"
+        );
+        let mut config = load_ui_config(&contents).unwrap().config;
+        config.base_color = "zinc".to_string();
+        config.leptos_mode = Some(LeptosMode::Csr);
+        config.concurrency = None;
+
+        let updated = update_config_contents(&contents, &config).unwrap();
+        assert_eq!(
+            updated,
+            format!(
+                "{}registry = \"https://ui.example.com\" # mirror\nleptos_mode = \"csr\"\n\n[aliases]\ncomponents = \"crate::ui_kit\"\n",
+                CONFIG.replace("\"neutral\"", "\"zinc\"")
+            )
+        );
+        assert_eq!(update_config_contents(&updated, &config).unwrap(), updated);
+    }
+
+    #[test]
+    fn normalize_validates_values() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src/ui")).unwrap();
+
+        assert_eq!(ConfigKey::BaseColor.normalize("Zinc", dir.path()).unwrap(), "zinc");
+        assert!(ConfigKey::BaseColor.normalize("blue", dir.path()).is_err());
+        assert_eq!(ConfigKey::ColorTheme.normalize("ROSE", dir.path()).unwrap(), "rose");
+        assert_eq!(ConfigKey::BasePathComponents.normalize("src/ui/", dir.path()).unwrap(), "src/ui");
+        assert!(ConfigKey::BasePathComponents.normalize("src/missing", dir.path()).is_err());
+        assert_eq!(ConfigKey::PackageManager.normalize("PNPM", dir.path()).unwrap(), "pnpm");
+        assert_eq!(ConfigKey::LeptosMode.normalize("ssr", dir.path()).unwrap(), "ssr");
+        assert!(ConfigKey::LeptosMode.normalize("islands", dir.path()).is_err());
+        assert_eq!(
+            ConfigKey::Registry.normalize("https://example.com/registry/", dir.path()).unwrap(),
+            "https://example.com/registry"
+        );
+        assert!(ConfigKey::Registry.normalize("example.com", dir.path()).is_err());
+        assert!(ConfigKey::McpClient.normalize("cursor", dir.path()).is_ok());
+        assert!(ConfigKey::McpClient.normalize("emacs", dir.path()).is_err());
+        assert!(ConfigKey::Color.normalize("yes", dir.path()).is_err());
+        assert_eq!(ConfigKey::FormatOnWrite.normalize("true", dir.path()).unwrap(), "true");
+        assert!(ConfigKey::Concurrency.normalize("0", dir.path()).is_err());
+    }
+
+    #[test]
+    fn package_must_be_a_leptos_member() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[workspace]\nmembers = [\"app\"]\n").unwrap();
+        fs::create_dir_all(dir.path().join("app/src")).unwrap();
+        fs::write(
+            dir.path().join("app/Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nleptos = \"0.8\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(ConfigKey::Package.normalize("app", dir.path()).unwrap(), "app");
+        let err = ConfigKey::Package.normalize("admin", dir.path()).unwrap_err().to_string();
+        assert!(err.contains("Available members: app"), "{err}");
+    }
+
+    #[test]
+    fn parse_rejects_managed_and_unknown_keys() {
+        assert_eq!(ConfigKey::parse("base_path_components").unwrap(), ConfigKey::BasePathComponents);
+        assert!(ConfigKey::parse("version").unwrap_err().to_string().contains("ui config migrate"));
+        assert!(ConfigKey::parse("themes").unwrap_err().to_string().contains("ui theme add"));
+        assert!(ConfigKey::parse("style").unwrap_err().to_string().contains("base_color"));
+    }
+
+    #[test]
+    fn entries_show_effective_values() {
+        let mut config = load_ui_config(CONFIG).unwrap().config;
+        config.themes.push(NamedTheme {
+            name: "ocean".to_string(),
+            base_color: "zinc".to_string(),
+            color_theme: "blue".to_string(),
+        });
+        let entries = config_entries(&config).unwrap();
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["version", "base_color", "color_theme", "base_path_components", "themes"]);
+        assert_eq!(entries[0].1, "1");
+        assert_eq!(entries[1].1, "neutral");
+        assert!(entries[4].1.contains("name = \"ocean\""), "{}", entries[4].1);
+    }
+}
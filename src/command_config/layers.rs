@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::migrate::{load_ui_config, load_warnings};
+use crate::command_init::config::UiConfig;
+use crate::shared::cli_error::{CliError, CliResult};
+use crate::shared::rust_ui_client::RustUIClient;
+
+const UI_CONFIG_TOML: &str = "ui_config.toml";
+
+/// Settings a user-level config.toml may hold: defaults for every project.
+pub const USER_KEYS: &[&str] = &[
+    "registry",
+    "package_manager",
+    "base_color",
+    "color_theme",
+    "mcp_client",
+    "color",
+    "concurrency",
+    "format_on_write",
+];
+
+/// Settings `UI_<KEY>` environment variables override, with the TOML type of their value.
+const ENV_KEYS: &[(&str, EnvKind)] = &[
+    ("base_color", EnvKind::String),
+    ("color_theme", EnvKind::String),
+    ("base_path_components", EnvKind::String),
+    ("package_manager", EnvKind::String),
+    ("leptos_mode", EnvKind::String),
+    ("package", EnvKind::String),
+    ("registry", EnvKind::String),
+    ("mcp_client", EnvKind::String),
+    ("color", EnvKind::Bool),
+    ("concurrency", EnvKind::Integer),
+    ("format_on_write", EnvKind::Bool),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum EnvKind {
+    String,
+    Bool,
+    Integer,
+}
+
+/// Where the effective value of a setting comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigOrigin {
+    Default,
+    User(PathBuf),
+    Project(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::User(path) => write!(f, "user:{}", path.display()),
+            ConfigOrigin::Project(path) => write!(f, "project:{}", path.display()),
+            ConfigOrigin::Env(var) => write!(f, "env:{var}"),
+        }
+    }
+}
+
+/// ui_config.toml layered over the user config, with `UI_*` overrides on top.
+#[derive(Debug)]
+pub struct LayeredConfig {
+    pub values: toml::Table,
+    pub origins: BTreeMap<String, ConfigOrigin>,
+    pub warnings: Vec<String>,
+}
+
+impl LayeredConfig {
+    /// The effective settings as a `UiConfig`; fails when no layer sets a required key.
+    pub fn ui_config(&self) -> CliResult<UiConfig> {
+        Ok(toml::Value::Table(self.values.clone()).try_into()?)
+    }
+
+    pub fn origin(&self, key: &str) -> ConfigOrigin {
+        self.origins.get(key).cloned().unwrap_or(ConfigOrigin::Default)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.values.get(key).and_then(toml::Value::as_str)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.values.get(key).and_then(toml::Value::as_bool)
+    }
+
+    pub fn get_usize(&self, key: &str) -> Option<usize> {
+        self.values.get(key).and_then(toml::Value::as_integer).and_then(|n| usize::try_from(n).ok())
+    }
+}
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// `$XDG_CONFIG_HOME/ui-cli/config.toml`, else `~/.config/ui-cli/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| Path::new(&home).join(".config"))
+        })?;
+    Some(config_home.join("ui-cli").join("config.toml"))
+}
+
+/// Settings of the current directory: ui_config.toml (if any), the user config and `UI_*` variables.
+pub fn load_settings() -> CliResult<LayeredConfig> {
+    load_settings_at(Path::new(UI_CONFIG_TOML))
+}
+
+/// `load_settings` with the project file at `project_path`.
+pub fn load_settings_at(project_path: &Path) -> CliResult<LayeredConfig> {
+    load_layers(project_path, user_config_path().as_deref(), &|name| std::env::var(name).ok())
+}
+
+/// Layer, from lowest to highest priority: the user config at `user_path`, the project
+/// file at `project_path` and the `UI_*` variables `env` returns. Missing files are skipped.
+pub fn load_layers(
+    project_path: &Path,
+    user_path: Option<&Path>,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> CliResult<LayeredConfig> {
+    let mut values = toml::Table::new();
+    let mut origins = BTreeMap::new();
+    let mut warnings = Vec::new();
+
+    if let Some(user_path) = user_path.filter(|path| path.exists()) {
+        let table: toml::Table = toml::from_str(&fs::read_to_string(user_path)?)
+            .map_err(|e| CliError::config(&format!("Failed to parse {}: {e}", user_path.display())))?;
+        for (key, value) in table {
+            if USER_KEYS.contains(&key.as_str()) {
+                origins.insert(key.clone(), ConfigOrigin::User(user_path.to_path_buf()));
+                values.insert(key, value);
+            } else {
+                warnings.push(format!("Unknown key `{key}` in {} is ignored.", user_path.display()));
+            }
+        }
+    }
+
+    if let Ok(contents) = fs::read_to_string(project_path) {
+        let loaded = load_ui_config(&contents)?;
+        let written: toml::Table = toml::from_str(&contents)?;
+        // Keys the file sets shadow the user config; serde defaults only fill gaps
+        for (key, value) in toml::Table::try_from(&loaded.config)? {
+            if written.contains_key(&key) {
+                origins.insert(key.clone(), ConfigOrigin::Project(project_path.to_path_buf()));
+                values.insert(key, value);
+            } else {
+                values.entry(key).or_insert(value);
+            }
+        }
+        warnings.extend(load_warnings(&project_path.display().to_string(), &loaded));
+    }
+
+    for (key, kind) in ENV_KEYS {
+        let var = env_var_name(key);
+        let Some(raw) = env(&var) else { continue };
+        values.insert(key.to_string(), parse_env_value(&var, &raw, *kind)?);
+        origins.insert(key.to_string(), ConfigOrigin::Env(var));
+    }
+
+    Ok(LayeredConfig { values, origins, warnings })
+}
+
+/// Apply the settings every command shares: registry, concurrency and color.
+/// Errors are left to the commands that read the config themselves.
+pub fn apply_global_settings() {
+    let Ok(settings) = load_settings() else { return };
+    RustUIClient::configure(
+        settings.get_str("registry").map(str::to_string),
+        settings.get_usize("concurrency"),
+    );
+    if let Some(color) = settings.get_bool("color") {
+        colored::control::set_override(color);
+    }
+}
+
+/// `base_color` -> `UI_BASE_COLOR`.
+pub fn env_var_name(key: &str) -> String {
+    format!("UI_{}", key.to_uppercase())
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn parse_env_value(var: &str, raw: &str, kind: EnvKind) -> CliResult<toml::Value> {
+    let invalid = |expected: &str| CliError::config(&format!("{var}={raw} is not {expected}"));
+    match kind {
+        EnvKind::String => Ok(toml::Value::String(raw.to_string())),
+        EnvKind::Bool => match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(toml::Value::Boolean(true)),
+            "0" | "false" | "no" | "off" => Ok(toml::Value::Boolean(false)),
+            _ => Err(invalid("a boolean")),
+        },
+        EnvKind::Integer => raw
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(|n| toml::Value::Integer(i64::from(n)))
+            .ok_or_else(|| invalid("a positive integer")),
+    }
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const PROJECT: &str = "version = 1\nbase_color = \"zinc\"\nbase_path_components = \"src/ui\"\n";
+    const USER: &str =
+        "base_color = \"stone\"\ncolor_theme = \"rose\"\npackage_manager = \"bun\"\nconcurrency = 2\n";
+
+    fn layers(
+        dir: &TempDir,
+        project: Option<&str>,
+        user: Option<&str>,
+        env: &[(&str, &str)],
+    ) -> CliResult<LayeredConfig> {
+        let project_path = dir.path().join("ui_config.toml");
+        let user_path = dir.path().join("config.toml");
+        if let Some(project) = project {
+            fs::write(&project_path, project).unwrap();
+        }
+        if let Some(user) = user {
+            fs::write(&user_path, user).unwrap();
+        }
+        let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        load_layers(&project_path, Some(&user_path), &|name| env.get(name).cloned())
+    }
+
+    #[test]
+    fn project_overrides_user_and_env_overrides_both() {
+        let dir = TempDir::new().unwrap();
+        let layered =
+            layers(&dir, Some(PROJECT), Some(USER), &[("UI_PACKAGE_MANAGER", "pnpm"), ("UI_COLOR", "false")])
+                .unwrap();
+        let config = layered.ui_config().unwrap();
+
+        assert_eq!(config.base_color, "zinc");
+        assert_eq!(layered.origin("base_color"), ConfigOrigin::Project(dir.path().join("ui_config.toml")));
+        assert_eq!(config.color_theme, "rose", "unset in the project, so the user default applies");
+        assert_eq!(layered.origin("color_theme"), ConfigOrigin::User(dir.path().join("config.toml")));
+        assert_eq!(config.package_manager.as_deref(), Some("pnpm"));
+        assert_eq!(layered.origin("package_manager"), ConfigOrigin::Env("UI_PACKAGE_MANAGER".to_string()));
+        assert_eq!(config.color, Some(false));
+        assert_eq!(config.concurrency, Some(2));
+        assert_eq!(layered.origin("themes"), ConfigOrigin::Default);
+    }
+
+    #[test]
+    fn user_config_alone_provides_settings() {
+        let dir = TempDir::new().unwrap();
+        let layered = layers(&dir, None, Some(USER), &[]).unwrap();
+        assert!(!layered.origins.values().any(|origin| matches!(origin, ConfigOrigin::Project(_))));
+        assert_eq!(layered.get_str("package_manager"), Some("bun"));
+        assert_eq!(layered.get_usize("concurrency"), Some(2));
+        assert!(layered.ui_config().is_err(), "base_path_components only comes from a project");
+    }
+
+    #[test]
+    fn unknown_user_keys_and_bad_env_values_are_reported() {
+        let dir = TempDir::new().unwrap();
+        let layered = layers(&dir, Some(PROJECT), Some("base_path_components = \"x\"\n"), &[]).unwrap();
+        assert_eq!(layered.warnings.len(), 1);
+        assert!(layered.warnings[0].contains("`base_path_components`"));
+        assert_eq!(layered.ui_config().unwrap().base_path_components, "src/ui");
+
+        assert!(layers(&dir, Some(PROJECT), None, &[("UI_CONCURRENCY", "0")]).is_err());
+        assert!(layers(&dir, Some(PROJECT), None, &[("UI_COLOR", "maybe")]).is_err());
+    }
+
+    #[test]
+    fn env_var_names() {
+        assert_eq!(env_var_name("base_path_components"), "UI_BASE_PATH_COMPONENTS");
+        assert_eq!(ConfigOrigin::Env("UI_COLOR".to_string()).to_string(), "env:UI_COLOR");
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeSet;
+use std::sync::Once;
+
+use serde::Deserialize;
+use toml_edit::{DocumentMut, value};
+
+use super::schema::ui_config_keys;
+use crate::command_init::config::{Aliases, ComponentPaths, NamedTheme, UiConfig};
+use crate::command_init::leptos_mode::LeptosMode;
+use crate::shared::cli_error::{CliError, CliResult};
+
+/// Schema version written by this CLI. Bump it together with a new `UiConfigV*` migration.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Keys of version 0 files that later versions no longer store.
+const V0_REMOVED_KEYS: &[&str] = &["tailwind_input_file", "tailwind_config_file"];
+
+static WARNINGS_PRINTED: Once = Once::new();
+
+/// ui_config.toml as read from disk, upgraded to the current schema.
+#[derive(Debug, PartialEq)]
+pub struct LoadedConfig {
+    pub config: UiConfig,
+    /// Version of the file on disk (0 when it has no `version` key).
+    pub from_version: u32,
+    /// What each migration changed, e.g. removed keys.
+    pub changes: Vec<String>,
+    /// Keys this CLI does not know; they are ignored, and dropped by `ui config migrate`.
+    pub unknown_keys: Vec<String>,
+}
+
+/// Version 0: files written before the `version` key. Early releases also stored the
+/// Tailwind paths, which are now read from `[package.metadata.leptos]` in Cargo.toml.
+#[derive(Debug, Deserialize)]
+struct UiConfigV0 {
+    base_color: String,
+    #[serde(default)]
+    color_theme: Option<String>,
+    base_path_components: String,
+    #[serde(default)]
+    tailwind_input_file: Option<String>,
+    #[serde(default)]
+    tailwind_config_file: Option<String>,
+    #[serde(default)]
+    themes: Vec<NamedTheme>,
+    #[serde(default)]
+    package_manager: Option<String>,
+    #[serde(default)]
+    leptos_mode: Option<LeptosMode>,
+    #[serde(default)]
+    package: Option<String>,
+}
+
+impl UiConfigV0 {
+    fn migrate(self, changes: &mut Vec<String>) -> UiConfig {
+        for (key, value) in [
+            ("tailwind_input_file", &self.tailwind_input_file),
+            ("tailwind_config_file", &self.tailwind_config_file),
+        ] {
+            if let Some(value) = value {
+                changes.push(format!(
+                    "removed `{key} = \"{value}\"` (now read from [package.metadata.leptos] in Cargo.toml)"
+                ));
+            }
+        }
+        if self.color_theme.is_none() {
+            changes.push("added `color_theme = \"default\"`".to_string());
+        }
+        changes.push(format!("added `version = {CONFIG_VERSION}`"));
+
+        UiConfig {
+            version: CONFIG_VERSION,
+            base_color: self.base_color,
+            color_theme: self.color_theme.unwrap_or_else(|| "default".to_string()),
+            base_path_components: self.base_path_components,
+            themes: self.themes,
+            package_manager: self.package_manager,
+            leptos_mode: self.leptos_mode,
+            package: self.package,
+            registry: None,
+            mcp_client: None,
+            color: None,
+            concurrency: None,
+            format_on_write: None,
+            aliases: Aliases::default(),
+            paths: ComponentPaths::default(),
+        }
+    }
+}
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Parse ui_config.toml `contents`, migrating older schema versions to `CONFIG_VERSION`.
+/// Files from a newer CLI are rejected rather than misread.
+pub fn load_ui_config(contents: &str) -> CliResult<LoadedConfig> {
+    let table: toml::Table = toml::from_str(contents)?;
+    let from_version = match table.get("version") {
+        None => 0,
+        Some(value) => value
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| CliError::config("`version` in ui_config.toml must be a non-negative integer"))?,
+    };
+    if from_version > CONFIG_VERSION {
+        return Err(CliError::config(&format!(
+            "ui_config.toml has version {from_version}, but this ui CLI only supports up to version {CONFIG_VERSION}. Please update the CLI."
+        )));
+    }
+
+    let mut changes = Vec::new();
+    let config = match from_version {
+        0 => toml::from_str::<UiConfigV0>(contents)?.migrate(&mut changes),
+        _ => toml::from_str::<UiConfig>(contents)?,
+    };
+
+    let known = ui_config_keys();
+    let removed: &[&str] = if from_version == 0 { V0_REMOVED_KEYS } else { &[] };
+    let unknown_keys = table
+        .keys()
+        .filter(|key| !known.contains(key.as_str()) && !removed.contains(&key.as_str()))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Ok(LoadedConfig { config, from_version, changes, unknown_keys })
+}
+
+/// ui_config.toml `contents` upgraded as `loaded` (its `load_ui_config` result) describes: removed
+/// and unknown keys dropped, missing ones added and `version` set first, keeping the comments,
+/// ordering and formatting of everything else.
+pub fn migrate_ui_config(contents: &str, loaded: &LoadedConfig) -> CliResult<String> {
+    let mut doc: DocumentMut =
+        contents.parse().map_err(|e| CliError::config(&format!("Failed to parse ui_config.toml: {e}")))?;
+
+    let removed: &[&str] = if loaded.from_version == 0 { V0_REMOVED_KEYS } else { &[] };
+    for key in removed.iter().copied().chain(loaded.unknown_keys.iter().map(String::as_str)) {
+        doc.remove(key);
+    }
+    if !doc.contains_key("color_theme") {
+        doc.insert("color_theme", value(&loaded.config.color_theme));
+    }
+
+    if doc.contains_key("version") {
+        doc.insert("version", value(i64::from(CONFIG_VERSION)));
+    } else {
+        // The comment heading the file stays above `version`
+        let first_key = doc.iter().find(|(_, item)| item.is_value()).map(|(key, _)| key.to_string());
+        let heading = first_key.and_then(|key| {
+            let mut key = doc.key_mut(&key)?;
+            let heading = key.leaf_decor().prefix().cloned();
+            key.leaf_decor_mut().set_prefix("");
+            heading
+        });
+        doc.insert("version", value(i64::from(CONFIG_VERSION)));
+        doc.sort_values_by(|a, _, b, _| (a.get() != "version").cmp(&(b.get() != "version")));
+        if let (Some(heading), Some(mut key)) = (heading, doc.key_mut("version")) {
+            key.leaf_decor_mut().set_prefix(heading);
+        }
+    }
+
+    let updated = doc.to_string();
+    load_ui_config(&updated)?;
+    Ok(updated)
+}
+
+/// Print config warnings once per run (the config is read by several steps).
+pub fn print_warnings_once(warnings: &[String]) {
+    WARNINGS_PRINTED.call_once(|| {
+        for warning in warnings {
+            eprintln!("⚠️  {warning}");
+        }
+    });
+}
+
+pub fn load_warnings(toml_path: &str, loaded: &LoadedConfig) -> Vec<String> {
+    let mut warnings: Vec<String> = loaded
+        .unknown_keys
+        .iter()
+        .map(|key| format!("Unknown key `{key}` in {toml_path} is ignored."))
+        .collect();
+    if loaded.from_version < CONFIG_VERSION {
+        warnings.push(format!(
+            "{toml_path} uses schema version {}; run `ui config migrate` to upgrade it to version {CONFIG_VERSION}.",
+            loaded.from_version
+        ));
+    }
+    warnings
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_version_loads_unchanged() {
+        let loaded = load_ui_config(
+            "version = 1\nbase_color = \"zinc\"\ncolor_theme = \"blue\"\nbase_path_components = \"src/ui\"\n",
+        )
+        .unwrap();
+        assert_eq!(loaded.from_version, 1);
+        assert!(loaded.changes.is_empty());
+        assert!(loaded.unknown_keys.is_empty());
+        assert_eq!(loaded.config.base_path_components, "src/ui");
+        assert!(load_warnings("ui_config.toml", &loaded).is_empty());
+    }
+
+    #[test]
+    fn version_0_is_migrated() {
+        let loaded = load_ui_config(
+            r#"base_color = "neutral"
+base_path_components = "src/components"
+tailwind_input_file = "style/tailwind.css"
+tailwind_config_file = "tailwind.config.js"
+"#,
+        )
+        .unwrap();
+        assert_eq!(loaded.from_version, 0);
+        assert_eq!(loaded.config.version, CONFIG_VERSION);
+        assert_eq!(loaded.config.color_theme, "default");
+        assert!(loaded.unknown_keys.is_empty(), "removed keys are not unknown: {:?}", loaded.unknown_keys);
+        assert_eq!(loaded.changes.len(), 4);
+        assert!(loaded.changes[0].contains("tailwind_input_file"));
+
+        let warnings = load_warnings("ui_config.toml", &loaded);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("ui config migrate"));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let loaded = load_ui_config(
+            "version = 1\nbase_color = \"zinc\"\nbase_path_components = \"src/ui\"\nstyle = \"new-york\"\nrsc = false\n",
+        )
+        .unwrap();
+        assert_eq!(loaded.unknown_keys, vec!["rsc".to_string(), "style".to_string()]);
+        assert_eq!(
+            load_warnings("ui_config.toml", &loaded)[1],
+            "Unknown key `style` in ui_config.toml is ignored."
+        );
+    }
+
+    #[test]
+    fn migration_keeps_comments_and_formatting() {
+        const V0: &str = r#"# rust-ui settings
+base_color = "neutral"   # gray scale
+base_path_components = "src/components"
+tailwind_input_file = "style/tailwind.css"
+style = "new-york"
+
+# Extra themes
+[[themes]]
+name = "ocean"
+base_color = "zinc"
+"#;
+        let loaded = load_ui_config(V0).unwrap();
+        let migrated = migrate_ui_config(V0, &loaded).unwrap();
+        assert_eq!(
+            migrated,
+            r#"# rust-ui settings
+version = 1
+base_color = "neutral"   # gray scale
+base_path_components = "src/components"
+color_theme = "default"
+
+# Extra themes
+[[themes]]
+name = "ocean"
+base_color = "zinc"
+"#
+        );
+
+        let reloaded = load_ui_config(&migrated).unwrap();
+        assert_eq!(reloaded.config, loaded.config);
+        assert!(reloaded.unknown_keys.is_empty());
+    }
+
+    #[test]
+    fn newer_and_invalid_versions_are_rejected() {
+        let newer = load_ui_config("version = 99\nbase_color = \"zinc\"\nbase_path_components = \"src\"\n");
+        assert!(newer.unwrap_err().to_string().contains("version 99"));
+        assert!(
+            load_ui_config("version = -1\nbase_color = \"zinc\"\nbase_path_components = \"src\"\n").is_err()
+        );
+    }
+}
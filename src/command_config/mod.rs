@@ -0,0 +1,5 @@
+pub mod _config;
+pub mod keys;
+pub mod layers;
+pub mod migrate;
+pub mod schema;
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+
+use schemars::schema_for;
+
+use crate::command_add::registry_index::RegistryIndex;
+use crate::command_init::config::UiConfig;
+
+/// JSON Schema of ui_config.toml, as published in `schemas/ui_config.schema.json`.
+pub fn ui_config_schema() -> String {
+    let schema = schema_for!(UiConfig);
+    let json = serde_json::to_string_pretty(&schema).unwrap_or_default();
+    format!("{json}\n")
+}
+
+/// JSON Schema of the registry index, as published in `schemas/registry.schema.json`.
+pub fn registry_index_schema() -> String {
+    let schema = schema_for!(RegistryIndex);
+    let json = serde_json::to_string_pretty(&schema).unwrap_or_default();
+    format!("{json}\n")
+}
+
+/// Top-level keys the current schema knows.
+pub fn ui_config_keys() -> HashSet<String> {
+    let schema = schema_for!(UiConfig);
+    schema
+        .get("properties")
+        .and_then(|properties| properties.as_object())
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLISHED_SCHEMA: &str = include_str!("../../schemas/ui_config.schema.json");
+    const PUBLISHED_REGISTRY_SCHEMA: &str = include_str!("../../schemas/registry.schema.json");
+
+    #[test]
+    fn published_schema_is_up_to_date() {
+        assert_eq!(
+            PUBLISHED_SCHEMA,
+            ui_config_schema(),
+            "run `cargo run -- config schema --output schemas/ui_config.schema.json`"
+        );
+    }
+
+    #[test]
+    fn published_registry_schema_is_up_to_date() {
+        assert_eq!(
+            PUBLISHED_REGISTRY_SCHEMA,
+            registry_index_schema(),
+            "run `cargo run -- config schema --registry --output schemas/registry.schema.json`"
+        );
+    }
+
+    #[test]
+    fn keys_cover_every_field() {
+        let keys = ui_config_keys();
+        for key in ["version", "base_color", "color_theme", "base_path_components", "themes", "package"] {
+            assert!(keys.contains(key), "{key}");
+        }
+    }
+}
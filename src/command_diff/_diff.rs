@@ -1,13 +1,14 @@
-use std::path::Path;
-
 use clap::{Arg, ArgMatches, Command};
 use colored::Colorize;
 use serde::Serialize;
 use similar::{ChangeTag, TextDiff};
 
-use crate::command_add::component_type::ComponentType;
+use crate::command_add::component_dirs::ComponentDirs;
+use crate::command_add::import_paths::ImportAliases;
 use crate::command_add::installed::get_installed_components;
-use crate::command_init::config::UiConfig;
+use crate::command_add::rustfmt::format_or_same;
+use crate::command_add::tree_parser::TreeParser;
+use crate::command_init::config::{Aliases, UiConfig};
 use crate::shared::cli_error::CliResult;
 use crate::shared::rust_ui_client::RustUIClient;
 
@@ -43,6 +44,12 @@ pub fn command_diff() -> Command {
         .about("Show line-by-line diff of installed components vs the registry")
         .arg(Arg::new("component").help("Component name to diff (omit to diff all installed)").required(false))
         .arg(Arg::new("json").long("json").help("Output as JSON").action(clap::ArgAction::SetTrue))
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Compare rustfmt-formatted sources (default: format_on_write from ui_config.toml)")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 /* ========================================================== */
@@ -51,14 +58,30 @@ pub fn command_diff() -> Command {
 
 /// Fetch registry content and compute diffs for a list of component names.
 /// Names are processed in the order given; sort before calling if needed.
-pub async fn diff_components(names: &[String], base_path: &str) -> CliResult<Vec<ComponentDiff>> {
+/// Registry sources are compared with their imports rewritten by `aliases`, as `ui add` writes them,
+/// against the file of the category `tree_parser` gives each component.
+/// With `format`, both sides go through rustfmt first, so formatting alone is not a change.
+pub async fn diff_components(
+    names: &[String],
+    tree_parser: &TreeParser,
+    dirs: &ComponentDirs,
+    aliases: &Aliases,
+    format: bool,
+) -> CliResult<Vec<ComponentDiff>> {
     let mut diffs: Vec<ComponentDiff> = Vec::new();
-    for name in names {
-        let component_type = ComponentType::from_component_name(name);
-        let local_path = Path::new(base_path).join(component_type.to_path()).join(format!("{name}.rs"));
-        match RustUIClient::fetch_styles_default(name).await {
+    let fetched = RustUIClient::fetch_styles_default_all(names).await;
+    for (name, fetched) in names.iter().zip(fetched) {
+        let category = tree_parser.category_of(name);
+        let local_path = dirs.component_file(name, &category);
+        match fetched {
             Ok(remote) => {
-                let local = std::fs::read_to_string(&local_path).unwrap_or_default();
+                let mut remote = ImportAliases::resolve(aliases, dirs, &category).rewrite(&remote);
+                let mut local = std::fs::read_to_string(&local_path).unwrap_or_default();
+                if format {
+                    let dir = dirs.dir(&category);
+                    remote = format_or_same(&remote, &dir);
+                    local = format_or_same(&local, &dir);
+                }
                 let status = if local == remote { DiffStatus::UpToDate } else { DiffStatus::Changed };
                 diffs.push(ComponentDiff { name: name.clone(), status, local, remote });
             }
@@ -77,15 +100,18 @@ pub async fn diff_components(names: &[String], base_path: &str) -> CliResult<Vec
 
 pub async fn process_diff(matches: &ArgMatches) -> CliResult<()> {
     let json = matches.get_flag("json");
+    let format_flag = matches.get_flag("format");
     let component_arg: Option<&String> = matches.get_one("component");
 
     let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
-    let base_path = config.base_path_components;
+    let dirs = ComponentDirs::from_config(&config);
+    let tree_parser = TreeParser::fetch().await?;
 
     let names: Vec<String> = if let Some(name) = component_arg {
         vec![name.clone()]
     } else {
-        let mut installed: Vec<String> = get_installed_components(&base_path).into_iter().collect();
+        let categories = dirs.categories(&tree_parser.get_categories());
+        let mut installed: Vec<String> = get_installed_components(&dirs, &categories).into_iter().collect();
         installed.sort();
         installed
     };
@@ -99,7 +125,8 @@ pub async fn process_diff(matches: &ArgMatches) -> CliResult<()> {
         println!("Checking {} installed component{}...\n", names.len(), if names.len() == 1 { "" } else { "s" });
     }
 
-    let diffs = diff_components(&names, &base_path).await?;
+    let format = format_flag || config.format_on_write == Some(true);
+    let diffs = diff_components(&names, &tree_parser, &dirs, &config.aliases, format).await?;
 
     let output = if json { format_diff_json(&diffs)? } else { format_diff_human(&diffs) };
     println!("{output}");
@@ -311,7 +338,9 @@ mod tests {
 
     #[tokio::test]
     async fn diff_components_empty_names_returns_empty_vec() {
-        let result = diff_components(&[], "any/path").await.unwrap();
+        let tree_parser = TreeParser::parse_tree_md("").unwrap();
+        let dirs = ComponentDirs::from_base_path("any/path");
+        let result = diff_components(&[], &tree_parser, &dirs, &Aliases::default(), false).await.unwrap();
         assert!(result.is_empty());
     }
 }
@@ -1,6 +1,7 @@
 use clap::{Arg, ArgMatches, Command};
 use serde::Serialize;
 
+use crate::command_add::component_dirs::ComponentDirs;
 use crate::command_add::installed::get_installed_components;
 use crate::command_init::config::UiConfig;
 use crate::command_init::workspace_utils::analyze_workspace;
@@ -41,7 +42,8 @@ pub fn process_info(matches: &ArgMatches) -> CliResult<()> {
     let json = matches.get_flag("json");
 
     let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
-    let installed = get_installed_components(&config.base_path_components);
+    let dirs = ComponentDirs::from_config(&config);
+    let installed = get_installed_components(&dirs, &dirs.local_categories());
     let workspace = analyze_workspace().ok();
 
     let data = build_info_data(&config.base_color, &config.base_path_components, &installed, workspace.as_ref());
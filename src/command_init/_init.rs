@@ -1,25 +1,75 @@
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Confirm, Select};
 
 const UI_CONFIG_TOML: &str = "ui_config.toml";
 const PACKAGE_JSON: &str = "package.json";
+const TAILWIND_CONFIG_JS: &str = "tailwind.config.js";
 
-use super::backup::FileBackup;
+use super::backup::{BackupSet, format_restore_summary};
 use super::colors::{AccentColor, BaseColor};
-use super::config::{UiConfig, add_init_crates};
-use super::install::InstallType;
-use super::workspace_utils::{check_leptos_dependency, get_tailwind_input_file};
+use super::config::{NamedTheme, UiConfig, add_init_crates, init_manifest_paths, plan_init_crates};
+use super::dry_run::{FileChange, InitPlan, format_init_plan};
+use super::install::{InstallType, PackageManager};
+use super::leptos_mode::{LeptosMode, detect_leptos_setup};
+use super::scaffold::{SCAFFOLD_MODES, scaffold_files, scaffold_project, validate_project_name};
+use super::tailwind_sources::{ensure_tailwind_sources, merge_sources, tailwind_sources};
+use super::tailwind_version::{TailwindVersion, find_config_file};
+use super::workspace_utils::{
+    WorkspaceInfo, check_leptos_dependency, find_member, get_tailwind_input_file, leptos_members_from_path,
+};
+use crate::command_add::component_dirs::ComponentDirs;
 use crate::command_add::installed::get_installed_components;
+use crate::command_config::keys::update_config_contents;
+use crate::command_config::layers::load_settings;
 use crate::command_init::install::install_dependencies;
 use crate::command_init::template::MyTemplate;
 use crate::shared::cli_error::{CliError, CliResult};
 use crate::shared::task_spinner::TaskSpinner;
 
+/// Flags of `ui init`.
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// Skip prompts and accept defaults (`--yes` / `--force`)
+    pub force: bool,
+    /// `Some(true)` = always reinstall components, `Some(false)` = never,
+    /// `None` = prompt when existing components are detected
+    pub reinstall: Option<bool>,
+    /// `--package-manager`; stored in ui_config.toml for later installs
+    pub package_manager: Option<PackageManager>,
+    /// `--dry-run`: print what would change without touching the project
+    pub dry_run: bool,
+    /// `ui init <project_name>`: scaffold a new Leptos project first, then initialize it
+    pub project_name: Option<String>,
+    /// `--mode`: rendering mode of the scaffolded project (CSR when omitted)
+    pub mode: Option<LeptosMode>,
+    /// `--package`: workspace member to install into; prompted when several depend on Leptos
+    pub package: Option<String>,
+}
+
+impl InitOptions {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        Self {
+            force: matches.get_flag("yes") || matches.get_flag("force"),
+            reinstall: if matches.get_flag("reinstall") { Some(true) } else { None },
+            package_manager: matches
+                .get_one::<String>("package_manager")
+                .and_then(|pm| PackageManager::from_str(pm)),
+            dry_run: matches.get_flag("dry_run"),
+            project_name: matches.get_one::<String>("project_name").cloned(),
+            mode: matches.get_one::<String>("mode").map(|mode| match mode.as_str() {
+                "ssr" => LeptosMode::Ssr,
+                _ => LeptosMode::Csr,
+            }),
+            package: matches.get_one::<String>("package").cloned(),
+        }
+    }
+}
+
 /// Returned by `process_init`. Non-empty `to_reinstall` means the caller
 /// should re-download those components (e.g. via `process_add_components`).
 pub struct InitOutcome {
@@ -34,7 +84,19 @@ pub struct InitOutcome {
 pub fn command_init() -> Command {
     Command::new("init")
         .about("Initialize the project")
-        .arg(Arg::new("project_name").help("The name of the project to initialize").required(false))
+        .arg(
+            Arg::new("project_name")
+                .help("Create a new Leptos project with this name, then initialize it")
+                .required(false),
+        )
+        .arg(
+            Arg::new("mode")
+                .long("mode")
+                .value_name("MODE")
+                .help("Rendering mode of the new project: csr (trunk) or ssr (cargo-leptos + axum)")
+                .value_parser(SCAFFOLD_MODES.to_vec())
+                .requires("project_name"),
+        )
         .arg(
             Arg::new("yes")
                 .short('y')
@@ -49,6 +111,26 @@ pub fn command_init() -> Command {
                 .help("Force overwrite existing files without prompting")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("package_manager")
+                .long("package-manager")
+                .value_name("PM")
+                .help("JS package manager to install with (npm, pnpm, yarn, bun); detected when omitted")
+                .value_parser(PackageManager::all_labels()),
+        )
+        .arg(
+            Arg::new("package")
+                .short('p')
+                .long("package")
+                .value_name("NAME")
+                .help("Workspace member to install into (prompted when several crates depend on Leptos)"),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Print the files, crates and packages init would change without touching anything")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("reinstall")
                 .long("reinstall")
@@ -62,12 +144,25 @@ pub fn command_init() -> Command {
 /*                     ✨ FUNCTIONS ✨                        */
 /* ========================================================== */
 
-/// Run project initialisation.
-///
-/// - `force`     – overwrite existing files without prompting (`--yes` / `--force`)
-/// - `reinstall` – `Some(true)` = always reinstall components, `Some(false)` = never,
-///   `None` = prompt when existing components are detected
-pub async fn process_init(force: bool, reinstall: Option<bool>) -> CliResult<InitOutcome> {
+/// Run project initialisation (see `InitOptions` for the flags).
+pub async fn process_init(options: InitOptions) -> CliResult<InitOutcome> {
+    let InitOptions { force, reinstall, package_manager, dry_run, project_name, mode, package } = options;
+
+    // `ui init <project_name>`: create the project from the embedded templates and initialize it
+    if let Some(name) = project_name {
+        let mode = mode.unwrap_or_default();
+        if dry_run {
+            validate_project_name(&name)?;
+            println!("[dry-run] Would create {} project {name}/:", mode.label());
+            for file in scaffold_files(&name, mode) {
+                println!("  create     {name}/{}", file.path);
+            }
+            return Ok(InitOutcome { to_reinstall: vec![], base_path: "src/components".to_string() });
+        }
+        let project_dir = scaffold_project(Path::new("."), &name, mode)?;
+        std::env::set_current_dir(&project_dir)?;
+    }
+
     // Check if Leptos is installed before proceeding
     if !check_leptos_dependency()? {
         return Err(CliError::config(
@@ -77,54 +172,124 @@ pub async fn process_init(force: bool, reinstall: Option<bool>) -> CliResult<Ini
 
     // Get tailwind input file from Cargo.toml metadata
     let tailwind_input_file = get_tailwind_input_file()?;
+    let tailwind_version = TailwindVersion::detect();
 
     // Read the existing config (if any) so we can detect installed components
     // and derive the base_path *before* we overwrite ui_config.toml.
-    let existing_config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML).ok();
+    let existing_config = UiConfig::try_reading_project_config(UI_CONFIG_TOML).ok();
+    let existing_contents =
+        existing_config.as_ref().map(|_| fs::read_to_string(UI_CONFIG_TOML)).transpose()?;
+    // User-level defaults (~/.config/ui-cli/config.toml and UI_* variables) for what the project leaves open
+    let settings = load_settings().ok();
+    let setting = |key: &str| settings.as_ref().and_then(|s| s.get_str(key)).map(str::to_string);
     let base_path = existing_config
         .as_ref()
         .map(|c| c.base_path_components.clone())
         .unwrap_or_else(|| "src/components".to_string());
+    let existing_dirs = existing_config
+        .as_ref()
+        .map_or_else(|| ComponentDirs::from_base_path(&base_path), ComponentDirs::from_config);
+    let (configured_package_manager, configured_package) =
+        existing_config.as_ref().map(|c| (c.package_manager.clone(), c.package.clone())).unwrap_or_default();
+    let resolved_package_manager = PackageManager::resolve(
+        package_manager,
+        configured_package_manager.clone().or_else(|| setting("package_manager")).as_deref(),
+    )?;
 
     // Detect components installed in the current project (empty on first run)
-    let installed: Vec<String> = get_installed_components(&base_path).into_iter().collect();
+    let installed: Vec<String> =
+        get_installed_components(&existing_dirs, &existing_dirs.local_categories()).into_iter().collect();
+
+    // Crate components and UI crates are installed into
+    let workspace_info = select_workspace_member(package.as_deref(), configured_package.as_deref(), force)?;
 
     // Prompt for base + accent colors (or use defaults when --yes/--force)
+    let default_base = setting("base_color").and_then(|c| BaseColor::from_str(&c)).unwrap_or_default();
+    let default_accent = setting("color_theme").and_then(|c| AccentColor::from_str(&c)).unwrap_or_default();
     let (base_color, accent_color) = if force {
-        (BaseColor::default(), AccentColor::default())
+        (default_base, default_accent)
     } else {
-        (prompt_base_color()?, prompt_accent_color()?)
+        (prompt_base_color(default_base)?, prompt_accent_color(default_accent)?)
     };
 
-    // Back up ui_config.toml — restored automatically on Drop if we error out
-    let mut config_backup = FileBackup::new(Path::new(UI_CONFIG_TOML))
-        .map_err(|e| CliError::file_operation(&e.to_string()))?;
-
-    let ui_config = UiConfig {
-        base_color: base_color.label().to_lowercase(),
-        color_theme: accent_color.label().to_lowercase(),
-        ..UiConfig::default()
+    // Rendering mode decides how the UI crates are wired, and is recorded in ui_config.toml
+    let spinner = TaskSpinner::new("Detecting Leptos rendering mode...");
+    let leptos_setup = detect_leptos_setup(&workspace_info);
+    spinner.finish_success(&format!("Leptos rendering mode: {}.", leptos_setup.mode.label()));
+
+    let ui_config = init_ui_config(
+        existing_config,
+        base_color,
+        accent_color,
+        package_manager,
+        leptos_setup.mode,
+        &workspace_info,
+    );
+    // A re-init updates the existing file in place, comments included
+    let ui_config_toml = match &existing_contents {
+        Some(contents) => update_config_contents(contents, &ui_config)?,
+        None => toml::to_string_pretty(&ui_config)?,
     };
-    let ui_config_toml = toml::to_string_pretty(&ui_config)?;
+    let install_type = InstallType::tailwind(tailwind_version);
+
+    if dry_run {
+        let plan = InitPlan {
+            files: planned_file_changes(
+                &ui_config,
+                &ui_config_toml,
+                &tailwind_input_file,
+                tailwind_version,
+                base_color,
+                accent_color,
+                &workspace_info,
+            )?,
+            crates: plan_init_crates(&leptos_setup, Some(workspace_info.clone())).await?,
+            install_command: install_type.install_command(resolved_package_manager),
+        };
+        println!("{}", format_init_plan(&plan));
+        return Ok(InitOutcome { to_reinstall: vec![], base_path });
+    }
 
-    // ui_config.toml - always write (config file)
-    write_template_file(UI_CONFIG_TOML, &ui_config_toml).await?;
+    // Back up every file init may touch; all of them are rolled back if any step fails
+    let mut backups =
+        backup_init_files(&tailwind_input_file, tailwind_version, resolved_package_manager, &workspace_info)?;
 
-    // package.json - merge with existing to preserve user dependencies
-    merge_package_json(PACKAGE_JSON, MyTemplate::PACKAGE_JSON).await?;
+    let result: CliResult<()> = async {
+        // ui_config.toml - always write (config file)
+        write_template_file(UI_CONFIG_TOML, &ui_config_toml).await?;
 
-    // tailwind.css - ask before overwriting if exists (skipped when --yes or --force)
-    let css = MyTemplate::build_css(base_color, accent_color);
-    write_template_with_confirmation(&tailwind_input_file, &css, force).await?;
+        // package.json - merge with existing to preserve user dependencies
+        merge_package_json(PACKAGE_JSON, MyTemplate::PACKAGE_JSON).await?;
 
-    add_init_crates().await?;
+        // tailwind.css - only the ui-cli managed regions are rewritten, user CSS is preserved
+        write_tailwind_input_file(
+            &tailwind_input_file,
+            tailwind_version,
+            base_color,
+            accent_color,
+            &ui_config.themes,
+        )
+        .await?;
+        match tailwind_version {
+            TailwindVersion::V3 => write_tailwind_config_v3(&ui_config.base_path_components)?,
+            TailwindVersion::V4 => {
+                ensure_tailwind_sources(&ComponentDirs::from_config(&ui_config).source_dirs())?
+            }
+        }
+
+        add_init_crates(&leptos_setup, Some(workspace_info.clone())).await?;
 
-    install_dependencies(&[InstallType::Tailwind]).await?;
+        install_dependencies(&[install_type], resolved_package_manager).await
+    }
+    .await;
 
-    // All writes succeeded — disarm the backup
-    if let Some(ref mut backup) = config_backup {
-        backup.disarm();
+    if let Err(err) = result {
+        let summary = backups.restore();
+        let base = std::env::current_dir().unwrap_or_default();
+        eprintln!("{}", format_restore_summary(&summary, &base));
+        return Err(err);
     }
+    backups.disarm();
 
     // Determine which components to reinstall
     let to_reinstall = if installed.is_empty() {
@@ -154,28 +319,111 @@ pub async fn process_init(force: bool, reinstall: Option<bool>) -> CliResult<Ini
 /*                     ✨ FUNCTIONS ✨                        */
 /* ========================================================== */
 
-fn prompt_base_color() -> CliResult<BaseColor> {
+/// ui_config.toml after `ui init`: the colors, package manager, Leptos mode and target crate it
+/// picks, over the `existing` config so that a re-init keeps every other setting (themes,
+/// `registry`, `format_on_write`, `[aliases]`, `[paths]`, ...).
+fn init_ui_config(
+    existing: Option<UiConfig>,
+    base_color: BaseColor,
+    accent_color: AccentColor,
+    package_manager: Option<PackageManager>,
+    leptos_mode: LeptosMode,
+    workspace_info: &WorkspaceInfo,
+) -> UiConfig {
+    let existing = existing.unwrap_or_default();
+    UiConfig {
+        base_color: base_color.label().to_lowercase(),
+        color_theme: accent_color.label().to_lowercase(),
+        package_manager: package_manager.map(|pm| pm.command().to_string()).or(existing.package_manager),
+        leptos_mode: Some(leptos_mode),
+        base_path_components: workspace_info.components_base_path.clone(),
+        package: workspace_info.target_crate.clone().filter(|_| workspace_info.is_workspace),
+        ..existing
+    }
+}
+
+/// Crate to install into: `--package`, else the member stored in ui_config.toml, else the only
+/// Leptos crate. When a workspace has several, the user picks one (the first with `--yes`).
+fn select_workspace_member(
+    explicit: Option<&str>,
+    configured: Option<&str>,
+    force: bool,
+) -> CliResult<WorkspaceInfo> {
+    let members = leptos_members_from_path(&std::env::current_dir()?)?;
+    if let Some(name) = explicit {
+        return find_member(&members, name).cloned();
+    }
+    if let Some(member) = configured.and_then(|name| find_member(&members, name).ok()) {
+        return Ok(member.clone());
+    }
+    match members.as_slice() {
+        [first, rest @ ..] if rest.is_empty() || force => Ok(first.clone()),
+        _ => prompt_workspace_member(&members),
+    }
+}
+
+fn prompt_workspace_member(members: &[WorkspaceInfo]) -> CliResult<WorkspaceInfo> {
+    let labels: Vec<String> = members
+        .iter()
+        .map(|info| {
+            format!("{} ({})", info.target_crate.as_deref().unwrap_or_default(), info.components_base_path)
+        })
+        .collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Workspace member to install components into")
+        .default(0)
+        .items(&labels)
+        .interact()
+        .map_err(|e| CliError::validation(&e.to_string()))?;
+    members.get(selection).cloned().ok_or_else(|| CliError::validation("Invalid selection"))
+}
+
+pub fn prompt_base_color(default: BaseColor) -> CliResult<BaseColor> {
     let labels = BaseColor::all_labels();
+    let default_index = BaseColor::ALL.iter().position(|c| *c == default).unwrap_or_default();
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Base color")
-        .default(0)
+        .default(default_index)
         .items(&labels)
         .interact()
         .map_err(|e| CliError::validation(&e.to_string()))?;
     Ok(BaseColor::from_index(selection))
 }
 
-fn prompt_accent_color() -> CliResult<AccentColor> {
+pub fn prompt_accent_color(default: AccentColor) -> CliResult<AccentColor> {
     let labels = AccentColor::all_labels();
+    let default_index = AccentColor::ALL.iter().position(|c| *c == default).unwrap_or_default();
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Accent color")
-        .default(0)
+        .default(default_index)
         .items(&labels)
         .interact()
         .map_err(|e| CliError::validation(&e.to_string()))?;
     Ok(AccentColor::from_index(selection))
 }
 
+/// Back up every file `process_init` writes, directly or through cargo and the package manager.
+fn backup_init_files(
+    tailwind_input_file: &str,
+    tailwind_version: TailwindVersion,
+    package_manager: PackageManager,
+    workspace_info: &WorkspaceInfo,
+) -> CliResult<BackupSet> {
+    let mut paths: Vec<PathBuf> =
+        [UI_CONFIG_TOML, PACKAGE_JSON, tailwind_input_file].iter().map(PathBuf::from).collect();
+    if tailwind_version == TailwindVersion::V3 {
+        paths.push(PathBuf::from(TAILWIND_CONFIG_JS));
+    }
+    paths.extend(package_manager.lockfiles().into_iter().map(PathBuf::from));
+    paths.extend(init_manifest_paths(&Some(workspace_info.clone())));
+
+    let mut backups = BackupSet::new();
+    for path in &paths {
+        backups.track(path).map_err(|e| CliError::file_operation(&e.to_string()))?;
+    }
+    Ok(backups)
+}
+
 /// Write template file (always writes, no confirmation)
 async fn write_template_file(file_name: &str, template: &str) -> CliResult<()> {
     let file_path = Path::new(".").join(file_name);
@@ -207,38 +455,135 @@ async fn merge_package_json(file_name: &str, template: &str) -> CliResult<()> {
     Ok(())
 }
 
-/// Write template file with confirmation if file already exists.
-/// When `force` is true, overwrites without prompting.
-async fn write_template_with_confirmation(
+/// Write the Tailwind input file. An existing file is merged: missing `@import` lines are added and
+/// only the `/* ui-cli:<region>:start */ … /* ui-cli:<region>:end */` regions are rewritten.
+pub async fn write_tailwind_input_file(
     file_name: &str,
-    template: &str,
-    force: bool,
+    version: TailwindVersion,
+    base_color: BaseColor,
+    accent_color: AccentColor,
+    themes: &[NamedTheme],
 ) -> CliResult<()> {
     let file_path = Path::new(".").join(file_name);
+    let spinner = TaskSpinner::new(&format!("Writing {file_name}..."));
+
+    if !file_path.exists() {
+        write_file_content(
+            &file_path,
+            &MyTemplate::build_css_with_themes(version, base_color, accent_color, themes),
+        )?;
+        spinner.finish_success(&format!("{file_name} written."));
+        return Ok(());
+    }
 
-    if file_path.exists() && !force {
-        let should_overwrite = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("{file_name} already exists. Overwrite?"))
-            .default(false)
-            .interact()
-            .map_err(|err| CliError::validation(&format!("Failed to get user input: {err}")))?;
+    let existing = fs::read_to_string(&file_path)?;
+    let merged = MyTemplate::merge_css(&existing, version, base_color, accent_color, themes);
+    if merged == existing {
+        spinner.finish_success(&format!("{file_name} already up to date."));
+        return Ok(());
+    }
+
+    write_file_content(&file_path, &merged)?;
+    spinner.finish_success(&format!("{file_name} merged."));
+    Ok(())
+}
 
-        if !should_overwrite {
-            println!("⏭️  Skipping {file_name}");
-            return Ok(());
+/// Tailwind v3: write `tailwind.config.js` with the theme colors mapped to the CSS variables.
+/// An existing config is the user's; we only point out when it lacks the color mapping.
+fn write_tailwind_config_v3(components_base_path: &str) -> CliResult<()> {
+    if let Some(existing) = find_config_file(Path::new(".")) {
+        let content = fs::read_to_string(&existing)?;
+        if !content.contains("var(--background)") {
+            println!(
+                "⚠️  {} does not map the theme colors. Add `theme.extend.colors` entries like `background: \"var(--background)\"` (see `ui init` on a fresh project).",
+                existing.display()
+            );
         }
+        return Ok(());
     }
 
-    let spinner = TaskSpinner::new(&format!("Writing {file_name}..."));
-    write_file_content(&file_path, template)?;
-    spinner.finish_success(&format!("{file_name} written."));
+    let spinner = TaskSpinner::new(&format!("Writing {TAILWIND_CONFIG_JS}..."));
+    let content = MyTemplate::tailwind_config_v3(&tailwind_content_globs(components_base_path));
+    write_file_content(&Path::new(".").join(TAILWIND_CONFIG_JS), &content)?;
+    spinner.finish_success(&format!("{TAILWIND_CONFIG_JS} written."));
     Ok(())
 }
 
+/// Content of every file init writes, before and after, for `--dry-run`.
+/// Mirrors the write steps of `process_init` using the same merge functions.
+fn planned_file_changes(
+    ui_config: &UiConfig,
+    ui_config_toml: &str,
+    tailwind_input_file: &str,
+    tailwind_version: TailwindVersion,
+    base_color: BaseColor,
+    accent_color: AccentColor,
+    workspace_info: &WorkspaceInfo,
+) -> CliResult<Vec<FileChange>> {
+    let read = |path: &str| fs::read_to_string(Path::new(".").join(path)).ok();
+    let mut files = Vec::new();
+
+    files.push(FileChange {
+        path: UI_CONFIG_TOML.to_string(),
+        before: read(UI_CONFIG_TOML),
+        after: ui_config_toml.to_string(),
+    });
+
+    let package_json = read(PACKAGE_JSON);
+    let package_json_after = match &package_json {
+        Some(existing) => merge_json_objects(existing, MyTemplate::PACKAGE_JSON)?,
+        None => MyTemplate::PACKAGE_JSON.to_string(),
+    };
+    files.push(FileChange {
+        path: PACKAGE_JSON.to_string(),
+        before: package_json,
+        after: package_json_after,
+    });
+
+    let css = read(tailwind_input_file);
+    let mut css_after = MyTemplate::merge_css(
+        css.as_deref().unwrap_or_default(),
+        tailwind_version,
+        base_color,
+        accent_color,
+        &ui_config.themes,
+    );
+    if tailwind_version == TailwindVersion::V4 {
+        let sources = tailwind_sources(
+            &ComponentDirs::from_config(ui_config).source_dirs(),
+            Path::new(tailwind_input_file),
+            Some(workspace_info),
+        )?;
+        css_after = merge_sources(&css_after, &sources);
+    }
+    files.push(FileChange { path: tailwind_input_file.to_string(), before: css, after: css_after });
+
+    if tailwind_version == TailwindVersion::V3 && find_config_file(Path::new(".")).is_none() {
+        files.push(FileChange {
+            path: TAILWIND_CONFIG_JS.to_string(),
+            before: None,
+            after: MyTemplate::tailwind_config_v3(&tailwind_content_globs(&ui_config.base_path_components)),
+        });
+    }
+
+    Ok(files)
+}
+
 /* ========================================================== */
 /*                     ✨ HELPERS ✨                          */
 /* ========================================================== */
 
+/// `content` globs for the v3 config: the app sources plus the components directory when it lives
+/// outside `src/`.
+fn tailwind_content_globs(components_base_path: &str) -> Vec<String> {
+    let mut globs = vec!["./index.html".to_string(), "./src/**/*.rs".to_string()];
+    let base = components_base_path.trim_start_matches("./").trim_end_matches('/');
+    if !base.is_empty() && base != "src" && !base.starts_with("src/") {
+        globs.push(format!("./{base}/**/*.rs"));
+    }
+    globs
+}
+
 /// Write content to a file, creating parent directories if needed
 fn write_file_content(file_path: &Path, content: &str) -> io::Result<()> {
     // Create the directory if it doesn't exist
@@ -280,6 +625,7 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
+    use crate::command_config::migrate::load_ui_config;
 
     // --- command_init flags ---
 
@@ -322,6 +668,40 @@ mod tests {
         assert!(m.get_flag("reinstall"));
     }
 
+    #[test]
+    fn command_init_dry_run_flag_is_registered() {
+        let m = command_init().try_get_matches_from(["init", "--dry-run", "-y"]).unwrap();
+        let options = InitOptions::from_matches(&m);
+        assert!(options.dry_run);
+        assert!(options.force);
+        assert_eq!(options.reinstall, None);
+    }
+
+    #[test]
+    fn command_init_package_manager_flag_is_registered() {
+        let m = command_init().try_get_matches_from(["init", "--package-manager", "bun"]).unwrap();
+        assert_eq!(m.get_one::<String>("package_manager").unwrap(), "bun");
+        assert!(command_init().try_get_matches_from(["init", "--package-manager", "deno"]).is_err());
+    }
+
+    #[test]
+    fn command_init_mode_requires_project_name() {
+        let m = command_init().try_get_matches_from(["init", "my-app", "--mode", "ssr"]).unwrap();
+        let options = InitOptions::from_matches(&m);
+        assert_eq!(options.project_name.as_deref(), Some("my-app"));
+        assert_eq!(options.mode, Some(LeptosMode::Ssr));
+        assert!(command_init().try_get_matches_from(["init", "--mode", "csr"]).is_err());
+        assert!(command_init().try_get_matches_from(["init", "my-app", "--mode", "hydrate"]).is_err());
+    }
+
+    #[test]
+    fn command_init_package_flag_is_registered() {
+        let m = command_init().try_get_matches_from(["init", "--package", "admin"]).unwrap();
+        assert_eq!(InitOptions::from_matches(&m).package.as_deref(), Some("admin"));
+        let m = command_init().try_get_matches_from(["init", "-p", "app"]).unwrap();
+        assert_eq!(InitOptions::from_matches(&m).package.as_deref(), Some("app"));
+    }
+
     #[test]
     fn command_init_reinstall_is_false_by_default() {
         let m = command_init().try_get_matches_from(["init"]).unwrap();
@@ -402,6 +782,72 @@ mod tests {
         assert_eq!(parsed["devDependencies"]["tailwindcss"], "^4.0.0");
     }
 
+    #[test]
+    fn tailwind_content_globs_skip_components_under_src() {
+        assert_eq!(tailwind_content_globs("src/components"), vec!["./index.html", "./src/**/*.rs"]);
+    }
+
+    #[test]
+    fn tailwind_content_globs_add_components_outside_src() {
+        let globs = tailwind_content_globs("crates/ui/src/components/");
+        assert_eq!(globs.last().unwrap(), "./crates/ui/src/components/**/*.rs");
+    }
+
+    // --- re-init ---
+
+    const EXISTING_CONFIG: &str = r#"# project settings
+version = 1
+base_color = "neutral"
+color_theme = "default"
+base_path_components = "src/components"
+package_manager = "pnpm"
+registry = "https://ui.example.com/registry" # mirror
+mcp_client = "cursor"
+color = false
+concurrency = 2
+
+[[themes]]
+name = "ocean"
+base_color = "zinc"
+color_theme = "blue"
+
+[aliases]
+components = "crate::ui_kit"
+"#;
+
+    fn reinit(contents: &str) -> String {
+        let existing = load_ui_config(contents).unwrap().config;
+        let workspace_info =
+            WorkspaceInfo { components_base_path: "src/components".to_string(), ..Default::default() };
+        let config = init_ui_config(
+            Some(existing),
+            BaseColor::Stone,
+            AccentColor::default(),
+            None,
+            LeptosMode::Csr,
+            &workspace_info,
+        );
+        update_config_contents(contents, &config).unwrap()
+    }
+
+    #[test]
+    fn reinit_keeps_settings_it_does_not_pick() {
+        let updated = reinit(EXISTING_CONFIG);
+        let expected = EXISTING_CONFIG
+            .replace("base_color = \"neutral\"", "base_color = \"stone\"")
+            .replace("concurrency = 2\n", "concurrency = 2\nleptos_mode = \"csr\"\n");
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn reinit_keeps_format_on_write_off() {
+        let contents = EXISTING_CONFIG
+            .replace("color = false\n", "color = false\nformat_on_write = false # slow machine\n");
+        let updated = reinit(&contents);
+        assert!(updated.contains("\nformat_on_write = false # slow machine\n"), "{updated}");
+        assert_eq!(load_ui_config(&updated).unwrap().config.format_on_write, Some(false));
+    }
+
     #[test]
     fn test_write_file_content_creates_directories() {
         let temp = TempDir::new().unwrap();
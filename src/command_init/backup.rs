@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -32,6 +33,17 @@ impl FileBackup {
         let _ = fs::remove_file(&self.backup);
     }
 
+    /// Put the original file back now and disarm the guard.
+    pub fn restore(&mut self) -> io::Result<()> {
+        self.armed = false;
+        fs::copy(&self.backup, &self.original)?;
+        fs::remove_file(&self.backup)
+    }
+
+    pub fn original_path(&self) -> &Path {
+        &self.original
+    }
+
     /// Path of the backup file (`<original>.bak`).
     #[cfg(test)]
     pub fn backup_path(&self) -> &Path {
@@ -42,12 +54,126 @@ impl FileBackup {
 impl Drop for FileBackup {
     fn drop(&mut self) {
         if self.armed {
-            let _ = fs::copy(&self.backup, &self.original);
-            let _ = fs::remove_file(&self.backup);
+            let _ = self.restore();
+        }
+    }
+}
+
+/// What `BackupSet::restore` did to one tracked file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoredFile {
+    /// The file existed before and its original content was put back.
+    Restored(PathBuf),
+    /// The file did not exist before and was deleted.
+    Removed(PathBuf),
+    /// The file could not be put back; its backup (if any) is left next to it.
+    Failed(PathBuf, String),
+}
+
+/// Backups for every file a multi-step operation may touch, rolled back together.
+///
+/// Existing files are copied to `<path>.bak` like `FileBackup`; files that do not exist yet are
+/// remembered so a rollback can delete them. Call `restore()` when the operation fails to get a
+/// summary of what was rolled back, or `disarm()` once it succeeded. A set dropped while armed
+/// restores silently.
+pub struct BackupSet {
+    backups: Vec<FileBackup>,
+    created: Vec<PathBuf>,
+    tracked: HashSet<PathBuf>,
+    armed: bool,
+}
+
+impl Default for BackupSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackupSet {
+    pub fn new() -> Self {
+        Self { backups: Vec::new(), created: Vec::new(), tracked: HashSet::new(), armed: true }
+    }
+
+    /// Back up `path`, or record it as new when it does not exist yet.
+    /// Tracking the same file twice (even through a different path) is a no-op.
+    pub fn track(&mut self, path: &Path) -> io::Result<()> {
+        if !self.tracked.insert(normalize(path)) {
+            return Ok(());
+        }
+        match FileBackup::new(path)? {
+            Some(backup) => self.backups.push(backup),
+            None => self.created.push(path.to_path_buf()),
+        }
+        Ok(())
+    }
+
+    /// Keep every change: delete the backups and forget the new files.
+    pub fn disarm(&mut self) {
+        self.armed = false;
+        for backup in &mut self.backups {
+            backup.disarm();
+        }
+    }
+
+    /// Roll every tracked file back to its state when it was tracked.
+    pub fn restore(&mut self) -> Vec<RestoredFile> {
+        self.armed = false;
+        let mut summary = Vec::new();
+        for backup in &mut self.backups {
+            let path = backup.original_path().to_path_buf();
+            match backup.restore() {
+                Ok(()) => summary.push(RestoredFile::Restored(path)),
+                Err(e) => summary.push(RestoredFile::Failed(path, e.to_string())),
+            }
+        }
+        for path in self.created.iter().filter(|path| path.exists()) {
+            match fs::remove_file(path) {
+                Ok(()) => summary.push(RestoredFile::Removed(path.clone())),
+                Err(e) => summary.push(RestoredFile::Failed(path.clone(), e.to_string())),
+            }
+        }
+        summary
+    }
+}
+
+impl Drop for BackupSet {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = self.restore();
         }
     }
 }
 
+/* ========================================================== */
+/*                      🖨  FORMATTERS 🖨                      */
+/* ========================================================== */
+
+/// One line per rolled-back file, with paths shown relative to `base` when they are inside it.
+pub fn format_restore_summary(summary: &[RestoredFile], base: &Path) -> String {
+    if summary.is_empty() {
+        return "Nothing to roll back.".to_string();
+    }
+    let display = |path: &Path| path.strip_prefix(base).unwrap_or(path).display().to_string();
+    let mut lines = vec!["Rolled back:".to_string()];
+    for file in summary {
+        lines.push(match file {
+            RestoredFile::Restored(path) => format!("  restored  {}", display(path)),
+            RestoredFile::Removed(path) => format!("  removed   {}", display(path)),
+            RestoredFile::Failed(path, error) => format!("  FAILED    {} ({error})", display(path)),
+        });
+    }
+    lines.join("\n")
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Key used to recognise the same file behind relative and absolute paths.
+fn normalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).or_else(|_| std::path::absolute(path)).unwrap_or_else(|_| path.to_path_buf())
+}
+
 /* ========================================================== */
 /*                        🧪 TESTS 🧪                         */
 /* ========================================================== */
@@ -123,6 +249,92 @@ mod tests {
         assert!(!backup_path.exists());
     }
 
+    #[test]
+    fn backup_set_restores_existing_and_removes_created_files() {
+        let dir = TempDir::new().unwrap();
+        let existing = write(&dir, "Cargo.toml", "[package]");
+        let created = dir.path().join("tailwind.config.js");
+
+        let mut set = BackupSet::new();
+        set.track(&existing).unwrap();
+        set.track(&created).unwrap();
+        fs::write(&existing, "broken").unwrap();
+        fs::write(&created, "new").unwrap();
+
+        let summary = set.restore();
+        assert_eq!(
+            summary,
+            vec![RestoredFile::Restored(existing.clone()), RestoredFile::Removed(created.clone())]
+        );
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "[package]");
+        assert!(!created.exists());
+        assert!(!dir.path().join("Cargo.toml.bak").exists());
+    }
+
+    #[test]
+    fn backup_set_skips_created_files_that_were_never_written() {
+        let dir = TempDir::new().unwrap();
+        let mut set = BackupSet::new();
+        set.track(&dir.path().join("package-lock.json")).unwrap();
+        assert!(set.restore().is_empty());
+    }
+
+    #[test]
+    fn backup_set_tracks_each_file_once() {
+        let dir = TempDir::new().unwrap();
+        let existing = write(&dir, "Cargo.toml", "original");
+        let mut set = BackupSet::new();
+        set.track(&existing).unwrap();
+        fs::write(&existing, "modified").unwrap();
+        // A second track must not back up the already-modified content
+        set.track(&existing).unwrap();
+        assert_eq!(set.restore().len(), 1);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "original");
+    }
+
+    #[test]
+    fn disarmed_backup_set_keeps_changes() {
+        let dir = TempDir::new().unwrap();
+        let existing = write(&dir, "Cargo.toml", "original");
+        let created = dir.path().join("ui_config.toml");
+        let mut set = BackupSet::new();
+        set.track(&existing).unwrap();
+        set.track(&created).unwrap();
+        fs::write(&existing, "modified").unwrap();
+        fs::write(&created, "new").unwrap();
+        set.disarm();
+        drop(set);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "modified");
+        assert!(created.exists());
+        assert!(!dir.path().join("Cargo.toml.bak").exists());
+    }
+
+    #[test]
+    fn armed_backup_set_restores_on_drop() {
+        let dir = TempDir::new().unwrap();
+        let existing = write(&dir, "package.json", "{}");
+        let mut set = BackupSet::new();
+        set.track(&existing).unwrap();
+        fs::write(&existing, "{\"broken\": true}").unwrap();
+        drop(set);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "{}");
+    }
+
+    #[test]
+    fn restore_summary_lists_files_relative_to_base() {
+        let base = Path::new("/project");
+        let summary = vec![
+            RestoredFile::Restored(PathBuf::from("/project/Cargo.toml")),
+            RestoredFile::Removed(PathBuf::from("tailwind.config.js")),
+            RestoredFile::Failed(PathBuf::from("/elsewhere/Cargo.lock"), "denied".to_string()),
+        ];
+        assert_eq!(
+            format_restore_summary(&summary, base),
+            "Rolled back:\n  restored  Cargo.toml\n  removed   tailwind.config.js\n  FAILED    /elsewhere/Cargo.lock (denied)"
+        );
+        assert_eq!(format_restore_summary(&[], base), "Nothing to roll back.");
+    }
+
     #[test]
     fn backup_content_matches_original() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cargo_toml::Manifest;
+use serde::Deserialize;
+
+/// Workspace as reported by `cargo metadata --no-deps --offline`.
+///
+/// Cargo resolves member globs, `exclude`, renamed packages and target-specific
+/// dependencies itself, so this is preferred over reading manifests by hand.
+/// When cargo is unavailable or the manifests do not load, callers fall back to
+/// the manifest parser in `workspace_utils`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CargoMetadata {
+    pub packages: Vec<MetadataPackage>,
+    pub workspace_members: Vec<String>,
+    pub workspace_root: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct MetadataPackage {
+    pub name: String,
+    pub id: String,
+    pub manifest_path: PathBuf,
+    #[serde(default)]
+    pub dependencies: Vec<MetadataDependency>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct MetadataDependency {
+    /// Name of the package on the registry (the `package = "..."` of a renamed dependency).
+    pub name: String,
+    /// Key the dependency is declared under, when it differs from `name`.
+    #[serde(default)]
+    pub rename: Option<String>,
+    /// `None` for normal dependencies, `"dev"` or `"build"` otherwise.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Platform or `cfg(...)` of target-specific dependencies.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Directory of path dependencies.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+impl CargoMetadata {
+    /// Run `cargo metadata` for the manifest in `dir`. `None` when cargo fails.
+    pub fn load(dir: &Path) -> Option<Self> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--no-deps", "--offline", "--format-version", "1"])
+            .current_dir(dir)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::from_json(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Workspace members, in the order cargo lists them.
+    pub fn members(&self) -> impl Iterator<Item = &MetadataPackage> {
+        self.workspace_members.iter().filter_map(|id| self.packages.iter().find(|p| &p.id == id))
+    }
+
+    /// Member whose Cargo.toml lives in `dir`.
+    pub fn package_at(&self, dir: &Path) -> Option<&MetadataPackage> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        self.members().find(|package| package.dir() == dir)
+    }
+}
+
+impl MetadataPackage {
+    pub fn dir(&self) -> &Path {
+        self.manifest_path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Whether `package` is a normal dependency, for any target and under any name.
+    pub fn depends_on(&self, package: &str) -> bool {
+        self.dependencies.iter().any(|dep| dep.kind.is_none() && dep.name == package)
+    }
+
+    /// Every dependency key and package name, across kinds and targets.
+    pub fn dependency_names(&self) -> HashSet<String> {
+        self.dependencies
+            .iter()
+            .flat_map(|dep| [Some(dep.name.clone()), dep.rename.clone()])
+            .flatten()
+            .collect()
+    }
+}
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Parser fallback for `MetadataPackage::depends_on`: `[dependencies]` and every
+/// `[target.'...'.dependencies]`, matching renamed dependencies by their `package`.
+pub fn manifest_depends_on(manifest: &Manifest, package: &str) -> bool {
+    let target_deps = manifest.target.values().map(|target| &target.dependencies);
+    std::iter::once(&manifest.dependencies)
+        .chain(target_deps)
+        .flatten()
+        .any(|(key, dep)| dep.package().unwrap_or(key) == package)
+}
+
+/// Parser fallback for `MetadataPackage::dependency_names`.
+pub fn manifest_dependency_names(manifest: &Manifest) -> HashSet<String> {
+    let target_deps = manifest
+        .target
+        .values()
+        .flat_map(|target| [&target.dependencies, &target.dev_dependencies, &target.build_dependencies]);
+    [&manifest.dependencies, &manifest.dev_dependencies, &manifest.build_dependencies]
+        .into_iter()
+        .chain(target_deps)
+        .flatten()
+        .flat_map(|(key, dep)| [Some(key.clone()), dep.package().map(str::to_string)])
+        .flatten()
+        .collect()
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const METADATA_JSON: &str = r#"{
+  "packages": [
+    {
+      "name": "app",
+      "id": "path+file:///ws/app#0.1.0",
+      "manifest_path": "/ws/app/Cargo.toml",
+      "dependencies": [
+        { "name": "leptos", "rename": "lep", "kind": null, "target": null },
+        { "name": "web-sys", "kind": null, "target": "cfg(target_arch = \"wasm32\")" },
+        { "name": "shared", "kind": null, "path": "/ws/shared" },
+        { "name": "pretty_assertions", "kind": "dev" }
+      ]
+    },
+    {
+      "name": "shared",
+      "id": "path+file:///ws/shared#0.1.0",
+      "manifest_path": "/ws/shared/Cargo.toml",
+      "dependencies": [{ "name": "leptos", "kind": "dev" }]
+    }
+  ],
+  "workspace_members": ["path+file:///ws/app#0.1.0", "path+file:///ws/shared#0.1.0"],
+  "workspace_root": "/ws"
+}"#;
+
+    #[test]
+    fn parses_members_and_dependencies() {
+        let metadata = CargoMetadata::from_json(METADATA_JSON).unwrap();
+        let names: Vec<_> = metadata.members().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "shared"]);
+
+        let app = metadata.package_at(Path::new("/ws/app")).unwrap();
+        assert!(app.depends_on("leptos"), "renamed dependency");
+        assert!(app.depends_on("web-sys"), "target-specific dependency");
+        assert!(!app.depends_on("pretty_assertions"), "dev dependency");
+        assert_eq!(app.dependencies[2].path.as_deref(), Some(Path::new("/ws/shared")));
+        assert!(app.dependency_names().is_superset(&HashSet::from([
+            "leptos".to_string(),
+            "lep".to_string(),
+            "pretty_assertions".to_string()
+        ])));
+
+        let shared = metadata.package_at(Path::new("/ws/shared")).unwrap();
+        assert!(!shared.depends_on("leptos"));
+    }
+
+    #[test]
+    fn manifest_fallback_handles_targets_and_renames() {
+        let manifest = Manifest::from_slice(
+            br#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+lep = { package = "leptos", version = "0.8" }
+
+[target.'cfg(target_arch = "wasm32")'.dependencies]
+web-sys = "0.3"
+
+[target.'cfg(unix)'.dev-dependencies]
+tempfile = "3"
+"#,
+        )
+        .unwrap();
+        assert!(manifest_depends_on(&manifest, "leptos"));
+        assert!(manifest_depends_on(&manifest, "web-sys"));
+        assert!(!manifest_depends_on(&manifest, "lep"));
+        assert!(!manifest_depends_on(&manifest, "tempfile"));
+
+        let names = manifest_dependency_names(&manifest);
+        for name in ["lep", "leptos", "web-sys", "tempfile"] {
+            assert!(names.contains(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn load_runs_cargo_metadata() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nleptos = \"0.8\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/lib.rs"), "").unwrap();
+
+        // cargo is always present when these tests run
+        let metadata = CargoMetadata::load(temp.path()).unwrap();
+        let package = metadata.package_at(temp.path()).unwrap();
+        assert_eq!(package.name, "demo");
+        assert!(package.depends_on("leptos"));
+
+        assert!(CargoMetadata::load(&temp.path().join("src")).is_some_and(|m| m.packages.len() == 1));
+        fs::write(temp.path().join("Cargo.toml"), "not toml").unwrap();
+        assert!(CargoMetadata::load(temp.path()).is_none());
+    }
+}
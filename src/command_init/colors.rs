@@ -1,3 +1,5 @@
+use crate::shared::cli_error::{CliError, CliResult};
+
 /// Base color (gray scale) — controls background/foreground/border/muted/accent vars.
 /// OKLCH values mirror the /create page's theme_picker.rs.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -39,11 +41,20 @@ impl BaseColor {
         Self::ALL.iter().map(|c| c.label()).collect()
     }
 
-    #[allow(dead_code)]
     pub fn from_str(s: &str) -> Option<Self> {
         Self::ALL.iter().copied().find(|c| c.label().eq_ignore_ascii_case(s))
     }
 
+    /// Like `from_str`, with an error listing the known colors.
+    pub fn parse(s: &str) -> CliResult<Self> {
+        Self::from_str(s).ok_or_else(|| {
+            CliError::validation(&format!(
+                "Unknown base color '{s}'. Expected one of: {}",
+                Self::all_labels().join(", ")
+            ))
+        })
+    }
+
     pub fn from_index(idx: usize) -> Self {
         Self::ALL.get(idx).copied().unwrap_or_default()
     }
@@ -147,11 +158,20 @@ impl AccentColor {
         Self::ALL.iter().map(|c| c.label()).collect()
     }
 
-    #[allow(dead_code)]
     pub fn from_str(s: &str) -> Option<Self> {
         Self::ALL.iter().copied().find(|c| c.label().eq_ignore_ascii_case(s))
     }
 
+    /// Like `from_str`, with an error listing the known colors.
+    pub fn parse(s: &str) -> CliResult<Self> {
+        Self::from_str(s).ok_or_else(|| {
+            CliError::validation(&format!(
+                "Unknown accent color '{s}'. Expected one of: {}",
+                Self::all_labels().join(", ")
+            ))
+        })
+    }
+
     pub fn from_index(idx: usize) -> Self {
         Self::ALL.get(idx).copied().unwrap_or_default()
     }
@@ -210,27 +230,39 @@ impl AccentColor {
 /// Build the :root + .dark CSS variable blocks from a base color + accent overlay.
 /// The accent vars are written after base vars, overriding primary/secondary/chart/sidebar.
 pub fn generate_theme_vars(base: BaseColor, accent: AccentColor) -> String {
-    let mut out = String::from(":root {\n  --radius: 0.625rem;\n");
+    let mut out = vars_block(":root", base.light_vars(), accent.light_vars(), LIGHT_DESTRUCTIVE);
+    out.push('\n');
+    out.push_str(&vars_block(".dark", base.dark_vars(), accent.dark_vars(), DARK_DESTRUCTIVE));
+    out
+}
 
-    for (k, v) in base.light_vars() {
-        out.push_str(&format!("  {k}: {v};\n"));
-    }
-    for (k, v) in accent.light_vars() {
-        out.push_str(&format!("  {k}: {v};\n"));
-    }
-    out.push_str("  --destructive: oklch(0.577 0.245 27.325);\n");
-    out.push_str("}\n");
+/// Build the CSS variable blocks for a named theme.
+/// Light vars apply to `[data-theme="name"]` / `.theme-name`, dark vars when `.dark` is also set.
+pub fn generate_named_theme_vars(name: &str, base: BaseColor, accent: AccentColor) -> String {
+    let light_selector = format!("[data-theme=\"{name}\"],\n.theme-{name}");
+    let dark_selector = format!(".dark[data-theme=\"{name}\"],\n.dark .theme-{name},\n.dark.theme-{name}");
 
-    out.push_str("\n.dark {\n");
-    for (k, v) in base.dark_vars() {
-        out.push_str(&format!("  {k}: {v};\n"));
-    }
-    for (k, v) in accent.dark_vars() {
+    let mut out = vars_block(&light_selector, base.light_vars(), accent.light_vars(), LIGHT_DESTRUCTIVE);
+    out.push('\n');
+    out.push_str(&vars_block(&dark_selector, base.dark_vars(), accent.dark_vars(), DARK_DESTRUCTIVE));
+    out
+}
+
+const LIGHT_DESTRUCTIVE: &str = "oklch(0.577 0.245 27.325)";
+const DARK_DESTRUCTIVE: &str = "oklch(0.704 0.191 22.216)";
+
+fn vars_block(
+    selector: &str,
+    base_vars: &[(&str, &str)],
+    accent_vars: &[(&str, &str)],
+    destructive: &str,
+) -> String {
+    let mut out = format!("{selector} {{\n  --radius: 0.625rem;\n");
+    for (k, v) in base_vars.iter().chain(accent_vars) {
         out.push_str(&format!("  {k}: {v};\n"));
     }
-    out.push_str("  --destructive: oklch(0.704 0.191 22.216);\n");
+    out.push_str(&format!("  --destructive: {destructive};\n"));
     out.push_str("}\n");
-
     out
 }
 
@@ -1065,6 +1097,25 @@ mod tests {
         assert!(css.contains("--background: oklch(0.141 0.005 285.823)"), "dark bg: {css}");
     }
 
+    #[test]
+    fn generate_named_theme_vars_uses_theme_selectors() {
+        let css = generate_named_theme_vars("ocean", BaseColor::Zinc, AccentColor::Blue);
+        assert!(css.contains("[data-theme=\"ocean\"],\n.theme-ocean {"), "got: {css}");
+        assert!(css.contains(".dark[data-theme=\"ocean\"]"), "got: {css}");
+        assert!(css.contains(".dark .theme-ocean"), "got: {css}");
+        assert!(!css.contains(":root {"));
+        assert!(css.contains("--primary: oklch(0.488 0.243 264.376)"));
+    }
+
+    #[test]
+    fn generate_theme_vars_dark_block_follows_root_block() {
+        let css = generate_theme_vars(BaseColor::Neutral, AccentColor::Default);
+        let root = css.find(":root {").unwrap();
+        let dark = css.find(".dark {").unwrap();
+        assert!(root < dark);
+        assert!(css.contains("}\n\n.dark {"));
+    }
+
     #[test]
     fn base_color_all_have_matching_labels() {
         for color in BaseColor::ALL {
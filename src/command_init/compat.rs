@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use serde::Deserialize;
+
+use super::crate_versions::locked_versions;
+use super::workspace_utils::{WorkspaceInfo, load_cargo_manifest};
+use crate::shared::rust_ui_client::RustUIClient;
+
+/// Bundled copy of the registry's `compat.json`, used when the registry is unreachable.
+/// Built from the `leptos` requirement of each crates.io release: `leptos_ui` 0.1.1 to 0.1.16
+/// require Leptos 0.7, later ones and every `icons` release Leptos 0.8; neither supports 0.6.
+const BUNDLED_COMPAT_JSON: &str = r#"{
+  "leptos": {
+    "0.8": { "leptos_ui": "0.3", "icons": "0.19", "tw_merge": "0.1" },
+    "0.7": { "leptos_ui": "=0.1.16", "tw_merge": "0.1" },
+    "0.6": { "tw_merge": "0.1" }
+  }
+}"#;
+
+/// Which versions of the init crates work with each Leptos minor version.
+/// Published by the registry as `compat.json`:
+///
+/// ```json
+/// { "leptos": { "0.8": { "leptos_ui": "0.3", "icons": "0.19", "tw_merge": "0.1" } } }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct CompatTable {
+    #[serde(default)]
+    pub leptos: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl CompatTable {
+    /// The registry table, or the bundled one when it cannot be fetched.
+    pub async fn load() -> Self {
+        match RustUIClient::fetch_compat_json().await {
+            Ok(json) => Self::from_json(&json).unwrap_or_else(Self::bundled),
+            Err(_) => Self::bundled(),
+        }
+    }
+
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_COMPAT_JSON).unwrap_or_default()
+    }
+
+    fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Version requirement for `crate_name` compatible with `leptos_version` (e.g. `"0.8"`).
+    pub fn version_for(&self, leptos_version: &str, crate_name: &str) -> Option<&str> {
+        self.leptos.get(leptos_version)?.get(crate_name).map(String::as_str)
+    }
+
+    /// Whether the table lists `leptos_version` but no release of `crate_name` works with it.
+    pub fn is_unsupported(&self, leptos_version: &str, crate_name: &str) -> bool {
+        self.leptos.get(leptos_version).is_some_and(|crates| !crates.contains_key(crate_name))
+    }
+}
+
+/// Leptos `major.minor` of the project, e.g. `"0.8"`.
+/// The locked version wins over the requirement declared in the manifests.
+pub fn detect_leptos_version(info: &WorkspaceInfo) -> Option<String> {
+    let lock_dirs = [info.workspace_root.as_deref(), info.target_crate_path.as_deref()];
+    let locked = lock_dirs
+        .into_iter()
+        .flatten()
+        .find_map(|dir| fs::read_to_string(dir.join("Cargo.lock")).ok())
+        .and_then(|lock| locked_version(&lock, "leptos"));
+
+    locked.or_else(|| declared_versions(info).remove("leptos")).and_then(|version| compat_key(&version))
+}
+
+/// Version requirements declared for dependencies of the target crate, with
+/// `[workspace.dependencies]` filling in `dep.workspace = true` entries.
+pub fn declared_versions(info: &WorkspaceInfo) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+
+    if info.is_workspace
+        && let Some(root) = &info.workspace_root
+        && let Ok(Some(manifest)) = load_cargo_manifest(&root.join("Cargo.toml"))
+        && let Some(workspace) = manifest.workspace
+    {
+        for (name, dep) in &workspace.dependencies {
+            if let Ok(req) = dep.try_req() {
+                versions.insert(name.clone(), req.to_string());
+            }
+        }
+    }
+
+    if let Some(crate_path) = &info.target_crate_path
+        && let Ok(Some(manifest)) = load_cargo_manifest(&crate_path.join("Cargo.toml"))
+    {
+        for (name, dep) in &manifest.dependencies {
+            if let Ok(req) = dep.try_req()
+                && req != "*"
+            {
+                versions.insert(name.clone(), req.to_string());
+            }
+        }
+    }
+
+    versions
+}
+
+/// Whether `existing` (a version or requirement like `^0.3.1`) satisfies `expected` (e.g. `0.3` or
+/// `=0.1.16`); requirements are compared by compatibility range.
+pub fn is_compatible(existing: &str, expected: &str) -> bool {
+    if let (Ok(version), Ok(requirement)) =
+        (semver::Version::parse(existing.trim()), semver::VersionReq::parse(expected))
+    {
+        return requirement.matches(&version);
+    }
+    match (compat_key(existing), compat_key(expected)) {
+        (Some(existing), Some(expected)) => existing == expected,
+        _ => true,
+    }
+}
+
+/// Semver-compatible range of a version: `0.minor` before 1.0, `major` after.
+pub fn compat_key(version: &str) -> Option<String> {
+    let version = version.trim().trim_start_matches(|c: char| !c.is_ascii_digit());
+    let mut parts = version.split('.');
+    let major: u64 = parts.next()?.parse().ok()?;
+    if major > 0 {
+        return Some(major.to_string());
+    }
+    let minor: u64 = parts.next()?.trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?;
+    Some(format!("0.{minor}"))
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Newest version of `crate_name` in `lock`; a lock can hold several.
+fn locked_version(lock: &str, crate_name: &str) -> Option<String> {
+    locked_versions(lock, crate_name).into_iter().max_by_key(|version| semver::Version::parse(version).ok())
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// crates.io index entries of the init crates (`https://index.crates.io/<path>/<name>`), their
+    /// dependencies trimmed to `leptos`.
+    const CRATES_INDEX: [(&str, &str); 3] = [
+        ("leptos_ui", include_str!("../../tests/fixtures/crates_index/leptos_ui")),
+        ("icons", include_str!("../../tests/fixtures/crates_index/icons")),
+        ("tw_merge", include_str!("../../tests/fixtures/crates_index/tw_merge")),
+    ];
+
+    /// `(version, Leptos range required)` of each non-yanked release; `None` when it does not depend on Leptos.
+    fn releases(index: &str) -> Vec<(semver::Version, Option<String>)> {
+        index
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .filter(|release| release["yanked"] == false)
+            .map(|release| {
+                let leptos = release["deps"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|dep| dep["name"] == "leptos")
+                    .map(|dep| compat_key(dep["req"].as_str().unwrap()).unwrap());
+                (semver::Version::parse(release["vers"].as_str().unwrap()).unwrap(), leptos)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bundled_table_matches_the_leptos_requirement_of_each_release() {
+        let table = CompatTable::bundled();
+        assert_eq!(table.leptos.keys().collect::<Vec<_>>(), ["0.6", "0.7", "0.8"]);
+
+        for (krate, index) in CRATES_INDEX {
+            let releases = releases(index);
+            let uses_leptos = releases.iter().any(|(_, leptos)| leptos.is_some());
+            // Releases without a Leptos dependency predate the Leptos integration
+            let works_with = |leptos: &str, required: &Option<String>| {
+                if uses_leptos { required.as_deref() == Some(leptos) } else { true }
+            };
+
+            for leptos in table.leptos.keys() {
+                match table.version_for(leptos, krate) {
+                    Some(expected) => {
+                        let req = semver::VersionReq::parse(expected).unwrap();
+                        let matching: Vec<_> = releases.iter().filter(|(v, _)| req.matches(v)).collect();
+                        assert!(!matching.is_empty(), "{krate} {expected} has no release");
+                        for (version, required) in &matching {
+                            assert!(
+                                works_with(leptos, required),
+                                "{krate} {version} needs Leptos {required:?}"
+                            );
+                        }
+                        if leptos == "0.8" {
+                            let newest = releases.iter().map(|(v, _)| v).max().unwrap();
+                            assert!(req.matches(newest), "{krate} {expected} is behind {newest}");
+                        }
+                    }
+                    None => assert!(
+                        !releases.iter().any(|(_, required)| works_with(leptos, required)),
+                        "{krate} has a release for Leptos {leptos}"
+                    ),
+                }
+            }
+        }
+        assert_eq!(table.version_for("0.5", "leptos_ui"), None);
+        assert!(table.is_unsupported("0.7", "icons"));
+        assert!(!table.is_unsupported("0.5", "icons"));
+    }
+
+    #[test]
+    fn compat_key_handles_versions_and_requirements() {
+        assert_eq!(compat_key("0.8.2").as_deref(), Some("0.8"));
+        assert_eq!(compat_key("^0.7").as_deref(), Some("0.7"));
+        assert_eq!(compat_key("=1.2.3").as_deref(), Some("1"));
+        assert_eq!(compat_key("*"), None);
+    }
+
+    #[test]
+    fn is_compatible_compares_ranges() {
+        assert!(is_compatible("0.3.4", "0.3"));
+        assert!(!is_compatible("0.2.1", "0.3"));
+        assert!(is_compatible("*", "0.3"));
+        assert!(is_compatible("0.1.16", "=0.1.16"));
+        assert!(!is_compatible("0.1.51", "=0.1.16"));
+    }
+
+    #[test]
+    fn detect_leptos_version_prefers_lockfile() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nleptos = \"0.7\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join("Cargo.lock"),
+            "version = 4\n\n[[package]]\nname = \"leptos\"\nversion = \"0.8.2\"\n\n\
+             [[package]]\nname = \"leptos\"\nversion = \"0.7.1\"\n",
+        )
+        .unwrap();
+        let info = WorkspaceInfo { target_crate_path: Some(temp.path().to_path_buf()), ..Default::default() };
+        assert_eq!(detect_leptos_version(&info).as_deref(), Some("0.8"));
+
+        fs::remove_file(temp.path().join("Cargo.lock")).unwrap();
+        assert_eq!(detect_leptos_version(&info).as_deref(), Some("0.7"));
+    }
+
+    #[test]
+    fn declared_versions_include_workspace_dependencies() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\"]\n\n[workspace.dependencies]\nleptos = \"0.8\"\nleptos_ui = \"0.2\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(
+            root.join("app/Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nleptos.workspace = true\nleptos_ui.workspace = true\n",
+        )
+        .unwrap();
+
+        let info = WorkspaceInfo {
+            is_workspace: true,
+            workspace_root: Some(root.to_path_buf()),
+            target_crate: Some("app".to_string()),
+            target_crate_path: Some(root.join("app")),
+            components_base_path: "app/src/components".to_string(),
+        };
+        let versions = declared_versions(&info);
+        assert_eq!(versions.get("leptos_ui").map(String::as_str), Some("0.2"));
+        assert_eq!(detect_leptos_version(&info).as_deref(), Some("0.8"));
+    }
+}
@@ -1,13 +1,21 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use toml_edit::{DocumentMut, Item, Value};
 
+use crate::command_config::layers::load_settings_at;
+use crate::command_config::migrate::{CONFIG_VERSION, load_ui_config, load_warnings, print_warnings_once};
+use crate::command_init::compat::{CompatTable, declared_versions, detect_leptos_version, is_compatible};
+use crate::command_init::crate_versions::resolve_crate_version;
 use crate::command_init::crates::{Crate, INIT_CRATES};
-use crate::command_init::workspace_utils::{WorkspaceInfo, analyze_workspace, check_leptos_dependency, load_cargo_manifest};
+use crate::command_init::leptos_mode::{LeptosMode, LeptosSetup};
+use crate::command_init::workspace_utils::{
+    WorkspaceInfo, analyze_workspace, check_leptos_dependency, load_cargo_manifest,
+};
 use crate::shared::cli_error::{CliError, CliResult};
 use crate::shared::task_spinner::TaskSpinner;
 
@@ -18,12 +26,158 @@ use crate::shared::task_spinner::TaskSpinner;
 /// Note: `tailwind_input_file` is read from Cargo.toml metadata (not stored here)
 /// to avoid duplication with Leptos configuration.
 ///
-#[derive(Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, PartialOrd, JsonSchema)]
 pub struct UiConfig {
+    /// Schema version of this file (see `ui config migrate`). Files without it are version 0.
+    #[serde(default)]
+    pub version: u32,
+    /// Base color of the default theme (e.g. neutral, zinc, stone).
     pub base_color: String,
+    /// Accent color of the default theme (e.g. default, blue, rose).
     #[serde(default = "default_color_theme")]
     pub color_theme: String,
+    /// Directory components are installed into, relative to ui_config.toml.
     pub base_path_components: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<NamedTheme>,
+    /// npm, pnpm, yarn or bun. Detected from the project when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_manager: Option<String>,
+    /// Rendering mode of the target crate, detected by `ui init`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leptos_mode: Option<LeptosMode>,
+    /// Workspace member components are installed into, chosen by `ui init`
+    /// (or `--package`) when the workspace has several Leptos crates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Registry base URL, instead of https://www.rust-ui.com/registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Editor `ui mcp init` configures when `--client` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_client: Option<String>,
+    /// Colored output; `false` disables it like `NO_COLOR`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<bool>,
+    /// Registry requests run at the same time by `ui diff` and `ui update`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// Run `rustfmt` on components and `mod.rs` files `ui add` writes (as `--format`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format_on_write: Option<bool>,
+    /// Module paths `use crate::components::...` imports of registry components are rewritten to.
+    #[serde(default, skip_serializing_if = "Aliases::is_empty")]
+    pub aliases: Aliases,
+    /// Directories of the component categories installed outside `base_path_components`.
+    #[serde(default, skip_serializing_if = "ComponentPaths::is_empty")]
+    pub paths: ComponentPaths,
+}
+
+/// Additional customer-selectable theme, emitted as `[data-theme="name"]` / `.theme-name` CSS blocks
+/// next to the default `:root` / `.dark` variables.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, PartialOrd, JsonSchema)]
+pub struct NamedTheme {
+    pub name: String,
+    pub base_color: String,
+    #[serde(default = "default_color_theme")]
+    pub color_theme: String,
+}
+
+/// `[aliases]` — module paths of the installed components, e.g. `components = "crate::ui_kit"`
+/// or `ui = "my_ui::ui"`. Unset paths are inferred from where the directories sit in their crate.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, PartialOrd, JsonSchema)]
+pub struct Aliases {
+    /// Module of `base_path_components` (registry path: `crate::components`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<String>,
+    /// Module of the `ui` components (registry path: `crate::components::ui`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui: Option<String>,
+    /// Module of the `demos` components (registry path: `crate::components::demos`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demos: Option<String>,
+    /// Module of the `hooks` components (registry path: `crate::components::hooks`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<String>,
+    /// Module of the `extensions` components (registry path: `crate::components::extensions`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<String>,
+    /// Modules of other registry categories, e.g. `blocks = "crate::blocks"`.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn is_empty(&self) -> bool {
+        *self == Aliases::default()
+    }
+
+    /// Module of the components of `category`, when set.
+    pub fn category(&self, category: &str) -> Option<&String> {
+        match category {
+            "ui" => self.ui.as_ref(),
+            "demos" => self.demos.as_ref(),
+            "hooks" => self.hooks.as_ref(),
+            "extensions" => self.extensions.as_ref(),
+            other => self.other.get(other),
+        }
+    }
+
+    /// Categories with a module set.
+    pub fn categories(&self) -> Vec<String> {
+        category_names(&[&self.ui, &self.demos, &self.hooks, &self.extensions], &self.other)
+    }
+}
+
+/// `[paths]` — directory of each component category, relative to ui_config.toml, e.g.
+/// `demos = "examples/src/demos"` or `hooks = "src/hooks"`. Unset categories go to
+/// `<base_path_components>/<category>`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, PartialOrd, JsonSchema)]
+pub struct ComponentPaths {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demos: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<String>,
+    /// Directories of other registry categories, e.g. `blocks = "src/blocks"`.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, String>,
+}
+
+impl ComponentPaths {
+    pub fn is_empty(&self) -> bool {
+        *self == ComponentPaths::default()
+    }
+
+    /// Directory of `category`, when mapped.
+    pub fn category(&self, category: &str) -> Option<&String> {
+        match category {
+            "ui" => self.ui.as_ref(),
+            "demos" => self.demos.as_ref(),
+            "hooks" => self.hooks.as_ref(),
+            "extensions" => self.extensions.as_ref(),
+            other => self.other.get(other),
+        }
+    }
+
+    /// Categories mapped to a directory.
+    pub fn categories(&self) -> Vec<String> {
+        category_names(&[&self.ui, &self.demos, &self.hooks, &self.extensions], &self.other)
+    }
+}
+
+/// Names of the set built-in categories (in `ui`, `demos`, `hooks`, `extensions` order), then of `other`.
+fn category_names(builtin: &[&Option<String>; 4], other: &BTreeMap<String, String>) -> Vec<String> {
+    let builtin_names = ["ui", "demos", "hooks", "extensions"];
+    let set = builtin_names
+        .iter()
+        .zip(builtin)
+        .filter(|(_, value)| value.is_some())
+        .map(|(name, _)| name.to_string());
+    set.chain(other.keys().cloned()).collect()
 }
 
 fn default_color_theme() -> String {
@@ -32,12 +186,32 @@ fn default_color_theme() -> String {
 
 impl UiConfig {
     pub fn try_reading_ui_config(toml_path: &str) -> CliResult<UiConfig> {
+        if !Path::new(toml_path).exists() {
+            return Err(CliError::project_not_initialized());
+        }
+        let layered = load_settings_at(Path::new(toml_path))?;
+        print_warnings_once(&layered.warnings);
+        layered.ui_config()
+    }
+
+    /// ui_config.toml alone, without the user config or `UI_*` overrides.
+    /// Use this for a config that is written back to the file.
+    pub fn try_reading_project_config(toml_path: &str) -> CliResult<UiConfig> {
         if !Path::new(toml_path).exists() {
             return Err(CliError::project_not_initialized());
         }
         let contents = fs::read_to_string(toml_path)?;
-        let ui_config: UiConfig = toml::from_str(&contents)?;
-        Ok(ui_config)
+        let loaded = load_ui_config(&contents)?;
+        print_warnings_once(&load_warnings(toml_path, &loaded));
+        Ok(loaded.config)
+    }
+
+    /// Insert `theme`, replacing any existing theme with the same name.
+    pub fn upsert_theme(&mut self, theme: NamedTheme) {
+        match self.themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme,
+            None => self.themes.push(theme),
+        }
     }
 }
 
@@ -50,9 +224,21 @@ impl Default for UiConfig {
         };
 
         UiConfig {
+            version: CONFIG_VERSION,
             base_color: "neutral".to_string(),
             color_theme: default_color_theme(),
             base_path_components,
+            themes: Vec::new(),
+            package_manager: None,
+            leptos_mode: None,
+            package: None,
+            registry: None,
+            mcp_client: None,
+            color: None,
+            concurrency: None,
+            format_on_write: None,
+            aliases: Aliases::default(),
+            paths: ComponentPaths::default(),
         }
     }
 }
@@ -61,63 +247,204 @@ impl Default for UiConfig {
 /*                     ✨ FUNCTIONS ✨                        */
 /* ========================================================== */
 
-pub async fn add_init_crates() -> CliResult<()> {
-    let workspace_info = analyze_workspace().ok();
+/// How an init crate gets into the project.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrateTarget {
+    /// Already in `[workspace.dependencies]`: only referenced from the member.
+    WorkspaceRef,
+    /// Added to `[workspace.dependencies]`, then referenced from the member.
+    WorkspaceDependencies,
+    /// `cargo add` into the target crate.
+    CargoAdd,
+}
+
+#[derive(Debug, Clone)]
+pub struct CratePlan {
+    pub my_crate: Crate,
+    /// Pinned version requirement; `None` keeps the declared version or lets cargo pick the latest.
+    pub version: Option<String>,
+    pub optional: bool,
+    pub target: CrateTarget,
+}
+
+/// Everything `add_init_crates` is about to do, computed without touching any file.
+#[derive(Debug, Clone)]
+pub struct InitCratesPlan {
+    pub workspace_info: Option<WorkspaceInfo>,
+    pub crates: Vec<CratePlan>,
+    /// Crate features that enable optional UI crates (see `LeptosSetup::optional_features`).
+    pub optional_features: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Work out which crates to add and how, wired for the detected Leptos `setup`:
+/// `leptos` gets the mode feature, and when `leptos` is optional the UI crates are optional too
+/// and enabled from the same crate features.
+///
+/// Versions come from the compatibility table for the project's Leptos version; crates the project
+/// already declares keep their version and are only checked against the table, and crates without
+/// a release for that Leptos version are skipped with a warning.
+pub async fn plan_init_crates(
+    setup: &LeptosSetup,
+    workspace_info: Option<WorkspaceInfo>,
+) -> CliResult<InitCratesPlan> {
     let workspace_crates = get_workspace_dependencies(&workspace_info);
+    let has_workspace_deps = has_workspace_dependencies_section(&workspace_info);
+
+    let compat = CompatTable::load().await;
+    let leptos_version = workspace_info.as_ref().and_then(detect_leptos_version);
+    let declared = workspace_info.as_ref().map(declared_versions).unwrap_or_default();
+    let mut warnings = compatibility_warnings(&compat, leptos_version.as_deref(), &declared);
 
+    let mut crates = Vec::new();
     for my_crate in INIT_CRATES {
-        if my_crate.name == "leptos" && check_leptos_dependency()? {
+        let is_leptos = my_crate.name == "leptos";
+        if is_leptos && check_leptos_dependency()? {
+            continue;
+        }
+        let my_crate = if is_leptos {
+            Crate { features: Some(setup.mode.leptos_features()), ..my_crate }
+        } else {
+            my_crate
+        };
+        if let Some(leptos) = leptos_version.as_deref()
+            && !is_leptos
+            && !declared.contains_key(my_crate.name)
+            && compat.is_unsupported(leptos, my_crate.name)
+        {
+            warnings.push(format!("{} has no release for Leptos {leptos}; it is not added.", my_crate.name));
             continue;
         }
+        let version = if declared.contains_key(my_crate.name) {
+            None
+        } else {
+            pinned_version(&my_crate, &compat, leptos_version.as_deref()).map(str::to_string)
+        };
 
-        let spinner = TaskSpinner::new(&format!("Adding {} crate...", my_crate.name));
+        crates.push(CratePlan {
+            target: crate_target(&my_crate, &workspace_info, &workspace_crates, has_workspace_deps),
+            optional: !is_leptos && !setup.optional_features.is_empty(),
+            my_crate,
+            version,
+        });
+    }
 
-        if add_crate_to_workspace(&my_crate, &workspace_info, &workspace_crates)? {
-            spinner.finish_success(&format!("{} (workspace) added.", my_crate.name));
-            continue;
+    Ok(InitCratesPlan {
+        workspace_info,
+        crates,
+        optional_features: setup.optional_features.clone(),
+        warnings,
+    })
+}
+
+/// Add the crates every rust-ui project needs to the `workspace_info` target (see `plan_init_crates`).
+pub async fn add_init_crates(setup: &LeptosSetup, workspace_info: Option<WorkspaceInfo>) -> CliResult<()> {
+    let plan = plan_init_crates(setup, workspace_info).await?;
+    for warning in &plan.warnings {
+        println!("⚠️  {warning}");
+    }
+
+    for crate_plan in &plan.crates {
+        let name = crate_plan.my_crate.name;
+        let spinner = TaskSpinner::new(&format!("Adding {name} crate..."));
+        apply_crate_plan(crate_plan, &plan.workspace_info)?;
+        if crate_plan.optional {
+            wire_optional_dependency(
+                &member_cargo_toml(&plan.workspace_info),
+                name,
+                &plan.optional_features,
+            )?;
         }
 
-        add_crate_with_cargo(&my_crate, &workspace_info)?;
-        spinner.finish_success(&format!("{} added.", my_crate.name));
+        let location = if crate_plan.target == CrateTarget::CargoAdd { "" } else { " (workspace)" };
+        spinner.finish_success(&format!("{name}{location} added."));
     }
     Ok(())
 }
 
-fn add_crate_to_workspace(
-    my_crate: &Crate,
-    workspace_info: &Option<WorkspaceInfo>,
-    workspace_crates: &HashSet<String>,
-) -> CliResult<bool> {
-    let Some(info) = workspace_info.as_ref().filter(|i| i.is_workspace) else {
-        return Ok(false);
-    };
-    let Some(workspace_root) = &info.workspace_root else {
-        return Ok(false);
-    };
-    let Some(member_path) = &info.target_crate_path else {
-        return Ok(false);
-    };
+/// Every manifest `add_init_crates` may edit: the target crate's Cargo.toml, the workspace root
+/// Cargo.toml and the Cargo.lock that `cargo add` updates.
+pub fn init_manifest_paths(workspace_info: &Option<WorkspaceInfo>) -> Vec<PathBuf> {
+    let member = member_cargo_toml(workspace_info);
+    let lock_dir = workspace_info
+        .as_ref()
+        .and_then(|info| {
+            if info.is_workspace { info.workspace_root.clone() } else { info.target_crate_path.clone() }
+        })
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let mut paths = vec![member];
+    if let Some(root) =
+        workspace_info.as_ref().filter(|info| info.is_workspace).and_then(|info| info.workspace_root.as_ref())
+    {
+        paths.push(root.join("Cargo.toml"));
+    }
+    paths.push(lock_dir.join("Cargo.lock"));
+    paths
+}
 
-    let root_cargo_toml = workspace_root.join("Cargo.toml");
-    let member_cargo_toml = member_path.join("Cargo.toml");
+fn apply_crate_plan(crate_plan: &CratePlan, workspace_info: &Option<WorkspaceInfo>) -> CliResult<()> {
+    let CratePlan { my_crate, version, optional, target } = crate_plan;
+    let member_cargo_toml = member_cargo_toml(workspace_info);
 
-    if workspace_crates.contains(my_crate.name) {
-        add_workspace_ref_to_member(&member_cargo_toml, my_crate.name)?;
-        return Ok(true);
+    match target {
+        CrateTarget::CargoAdd => {
+            add_crate_with_cargo(my_crate, version.as_deref(), workspace_info, *optional)
+        }
+        CrateTarget::WorkspaceRef => {
+            add_workspace_ref_to_member(&member_cargo_toml, my_crate.name, *optional)
+        }
+        CrateTarget::WorkspaceDependencies => {
+            let Some(root) = workspace_info.as_ref().and_then(|info| info.workspace_root.as_ref()) else {
+                return Err(CliError::cargo_operation("Workspace root not found"));
+            };
+            let version = resolve_crate_version(my_crate.name, root, version.as_deref())?;
+            add_to_workspace_dependencies(
+                &root.join("Cargo.toml"),
+                my_crate.name,
+                &version,
+                my_crate.features,
+            )?;
+            add_workspace_ref_to_member(&member_cargo_toml, my_crate.name, *optional)
+        }
     }
+}
 
-    if !has_workspace_dependencies_section(workspace_info) {
-        return Ok(false);
+/// Workspace members reuse `[workspace.dependencies]` when the workspace has one; anything else
+/// goes through `cargo add`.
+fn crate_target(
+    my_crate: &Crate,
+    workspace_info: &Option<WorkspaceInfo>,
+    workspace_crates: &HashSet<String>,
+    has_workspace_deps: bool,
+) -> CrateTarget {
+    let in_member = workspace_info
+        .as_ref()
+        .filter(|i| i.is_workspace)
+        .is_some_and(|i| i.workspace_root.is_some() && i.target_crate_path.is_some());
+
+    if !in_member {
+        CrateTarget::CargoAdd
+    } else if workspace_crates.contains(my_crate.name) {
+        CrateTarget::WorkspaceRef
+    } else if has_workspace_deps {
+        CrateTarget::WorkspaceDependencies
+    } else {
+        CrateTarget::CargoAdd
     }
-
-    let version = fetch_latest_version(my_crate.name)?;
-    add_to_workspace_dependencies(&root_cargo_toml, my_crate.name, &version, my_crate.features)?;
-    add_workspace_ref_to_member(&member_cargo_toml, my_crate.name)?;
-    Ok(true)
 }
 
-fn add_crate_with_cargo(my_crate: &Crate, workspace_info: &Option<WorkspaceInfo>) -> CliResult<()> {
-    let mut args = vec!["add".to_owned(), my_crate.name.to_owned()];
+fn add_crate_with_cargo(
+    my_crate: &Crate,
+    version: Option<&str>,
+    workspace_info: &Option<WorkspaceInfo>,
+    optional: bool,
+) -> CliResult<()> {
+    let spec = match version {
+        Some(version) => format!("{}@{version}", my_crate.name),
+        None => my_crate.name.to_owned(),
+    };
+    let mut args = vec!["add".to_owned(), spec];
 
     if let Some(info) = workspace_info.as_ref().filter(|i| i.is_workspace)
         && let Some(crate_name) = &info.target_crate
@@ -129,6 +456,10 @@ fn add_crate_with_cargo(my_crate: &Crate, workspace_info: &Option<WorkspaceInfo>
         args.extend(["--features".to_owned(), features.join(",")]);
     }
 
+    if optional {
+        args.push("--optional".to_owned());
+    }
+
     let output = Command::new("cargo").args(&args).output().map_err(|e| {
         CliError::cargo_operation(&format!("Failed to execute cargo add {}: {e}", my_crate.name))
     })?;
@@ -182,7 +513,93 @@ fn get_workspace_dependencies(workspace_info: &Option<WorkspaceInfo>) -> HashSet
         .unwrap_or_default()
 }
 
-fn add_workspace_ref_to_member(cargo_toml_path: &Path, dep: &str) -> CliResult<()> {
+/// Version to add `my_crate` at: the compatibility table entry for the project's Leptos version,
+/// else the crate's own pin. `None` lets cargo pick the latest.
+fn pinned_version<'a>(
+    my_crate: &'a Crate,
+    compat: &'a CompatTable,
+    leptos_version: Option<&str>,
+) -> Option<&'a str> {
+    leptos_version.and_then(|leptos| compat.version_for(leptos, my_crate.name)).or(my_crate.version)
+}
+
+/// Warnings about init crates the project already declares at a version that does not match its Leptos version.
+fn compatibility_warnings(
+    compat: &CompatTable,
+    leptos_version: Option<&str>,
+    declared: &HashMap<String, String>,
+) -> Vec<String> {
+    let Some(leptos) = leptos_version else {
+        return Vec::new();
+    };
+    if !compat.leptos.contains_key(leptos) {
+        return vec![format!(
+            "Leptos {leptos} is not in the compatibility table; crates are added at their latest version."
+        )];
+    }
+    incompatible_versions(compat, leptos, declared)
+}
+
+fn incompatible_versions(
+    compat: &CompatTable,
+    leptos: &str,
+    declared: &HashMap<String, String>,
+) -> Vec<String> {
+    INIT_CRATES
+        .iter()
+        .filter_map(|my_crate| {
+            let existing = declared.get(my_crate.name)?;
+            let expected = compat.version_for(leptos, my_crate.name)?;
+            (!is_compatible(existing, expected)).then(|| {
+                format!(
+                    "{} {existing} is not compatible with Leptos {leptos} (expected {expected}). Update it in Cargo.toml.",
+                    my_crate.name
+                )
+            })
+        })
+        .collect()
+}
+
+/// Cargo.toml of the crate init installs into.
+fn member_cargo_toml(workspace_info: &Option<WorkspaceInfo>) -> PathBuf {
+    workspace_info
+        .as_ref()
+        .and_then(|info| info.target_crate_path.as_ref())
+        .map(|path| path.join("Cargo.toml"))
+        .unwrap_or_else(|| PathBuf::from("Cargo.toml"))
+}
+
+/// Enable the optional dependency `dep` from each of `features` with `dep:<name>`.
+fn wire_optional_dependency(cargo_toml_path: &Path, dep: &str, features: &[String]) -> CliResult<()> {
+    let contents = fs::read_to_string(cargo_toml_path)?;
+    let mut doc: DocumentMut = contents
+        .parse()
+        .map_err(|e| CliError::cargo_operation(&format!("Failed to parse Cargo.toml: {e}")))?;
+
+    let features_table = doc
+        .entry("features")
+        .or_insert(Item::Table(toml_edit::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| CliError::cargo_operation("[features] is not a table"))?;
+
+    let entry = format!("dep:{dep}");
+    for feature in features {
+        let Some(enables) = features_table.get_mut(feature).and_then(Item::as_array_mut) else {
+            continue;
+        };
+        if !enables.iter().any(|v| v.as_str() == Some(&entry)) {
+            enables.push(entry.as_str());
+        }
+    }
+
+    let updated = doc.to_string();
+    if updated != contents {
+        fs::write(cargo_toml_path, updated)?;
+    }
+    Ok(())
+}
+
+fn add_workspace_ref_to_member(cargo_toml_path: &Path, dep: &str, optional: bool) -> CliResult<()> {
     let contents = fs::read_to_string(cargo_toml_path)?;
     let mut doc: DocumentMut = contents
         .parse()
@@ -200,6 +617,9 @@ fn add_workspace_ref_to_member(cargo_toml_path: &Path, dep: &str) -> CliResult<(
     let mut dep_table = toml_edit::Table::new();
     dep_table.set_dotted(true);
     dep_table.insert("workspace", Item::Value(Value::Boolean(toml_edit::Formatted::new(true))));
+    if optional {
+        dep_table.insert("optional", Item::Value(Value::Boolean(toml_edit::Formatted::new(true))));
+    }
     deps_table.insert(dep, Item::Table(dep_table));
 
     fs::write(cargo_toml_path, doc.to_string())?;
@@ -248,37 +668,6 @@ fn add_to_workspace_dependencies(
     Ok(())
 }
 
-fn fetch_latest_version(crate_name: &str) -> CliResult<String> {
-    let output = Command::new("cargo")
-        .args(["search", crate_name, "--limit", "1"])
-        .output()
-        .map_err(|_| CliError::cargo_operation("Failed to execute cargo search"))?;
-
-    if !output.status.success() {
-        return Ok("*".to_string());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    for line in stdout.lines() {
-        if line.starts_with(crate_name)
-            && let Some(version_part) = line.split('=').nth(1)
-        {
-            let version = version_part
-                .trim()
-                .trim_matches('"')
-                .split_whitespace()
-                .next()
-                .unwrap_or("")
-                .trim_matches('"');
-            if !version.is_empty() {
-                return Ok(version.to_string());
-            }
-        }
-    }
-
-    Ok("*".to_string())
-}
-
 /* ========================================================== */
 /*                        🧪 TESTS 🧪                         */
 /* ========================================================== */
@@ -324,6 +713,29 @@ serde = "1.0"
         assert_eq!(deps.len(), 3);
     }
 
+    #[test]
+    fn test_init_manifest_paths_cover_member_root_and_lockfile() {
+        let info = WorkspaceInfo {
+            is_workspace: true,
+            workspace_root: Some(PathBuf::from("/ws")),
+            target_crate: Some("app".to_string()),
+            target_crate_path: Some(PathBuf::from("/ws/app")),
+            components_base_path: "app/src/components".to_string(),
+        };
+        assert_eq!(
+            init_manifest_paths(&Some(info)),
+            vec![
+                PathBuf::from("/ws/app/Cargo.toml"),
+                PathBuf::from("/ws/Cargo.toml"),
+                PathBuf::from("/ws/Cargo.lock")
+            ]
+        );
+        assert_eq!(
+            init_manifest_paths(&None),
+            vec![PathBuf::from("Cargo.toml"), PathBuf::from("./Cargo.lock")]
+        );
+    }
+
     #[test]
     fn test_get_workspace_dependencies_empty_when_no_workspace() {
         let deps = get_workspace_dependencies(&None);
@@ -387,7 +799,7 @@ leptos.workspace = true
         )
         .unwrap();
 
-        add_workspace_ref_to_member(&cargo_toml, "tw_merge").unwrap();
+        add_workspace_ref_to_member(&cargo_toml, "tw_merge", false).unwrap();
 
         let contents = fs::read_to_string(&cargo_toml).unwrap();
         assert!(contents.contains("tw_merge.workspace = true"), "Should use dotted format, got: {contents}");
@@ -408,13 +820,85 @@ tw_merge.workspace = true
         fs::write(&cargo_toml, original).unwrap();
 
         // Should not error or modify when dep already exists
-        add_workspace_ref_to_member(&cargo_toml, "tw_merge").unwrap();
+        add_workspace_ref_to_member(&cargo_toml, "tw_merge", false).unwrap();
 
         let contents = fs::read_to_string(&cargo_toml).unwrap();
         // Count occurrences - should still be just one
         assert_eq!(contents.matches("tw_merge").count(), 1, "Should not duplicate: {contents}");
     }
 
+    #[test]
+    fn test_pinned_version_uses_compat_table() {
+        let compat = CompatTable::bundled();
+        let leptos_ui = Crate::new("leptos_ui", None, None);
+        assert_eq!(pinned_version(&leptos_ui, &compat, Some("0.8")), compat.version_for("0.8", "leptos_ui"));
+        assert_eq!(pinned_version(&leptos_ui, &compat, Some("0.1")), None);
+        assert_eq!(pinned_version(&leptos_ui, &compat, None), None);
+
+        let pinned = Crate::new("leptos_ui", Some("0.3"), None);
+        assert_eq!(pinned_version(&pinned, &compat, None), Some("0.3"));
+    }
+
+    #[test]
+    fn test_incompatible_versions_reports_mismatches_only() {
+        let compat = CompatTable::bundled();
+        let expected = compat.version_for("0.8", "leptos_ui").unwrap().to_string();
+        let mut declared = HashMap::new();
+        declared.insert("leptos_ui".to_string(), "0.0.1".to_string());
+        declared.insert("tw_merge".to_string(), compat.version_for("0.8", "tw_merge").unwrap().to_string());
+
+        let messages = incompatible_versions(&compat, "0.8", &declared);
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        assert!(messages[0].starts_with("leptos_ui 0.0.1 is not compatible with Leptos 0.8"));
+        assert!(messages[0].contains(&format!("(expected {expected})")));
+    }
+
+    #[test]
+    fn test_add_workspace_ref_to_member_marks_optional() {
+        let temp = TempDir::new().unwrap();
+        let cargo_toml = temp.path().join("Cargo.toml");
+        fs::write(&cargo_toml, "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\n").unwrap();
+
+        add_workspace_ref_to_member(&cargo_toml, "icons", true).unwrap();
+
+        let contents = fs::read_to_string(&cargo_toml).unwrap();
+        assert!(contents.contains("icons.workspace = true"), "got: {contents}");
+        assert!(contents.contains("icons.optional = true"), "got: {contents}");
+    }
+
+    #[test]
+    fn test_wire_optional_dependency_appends_dep_entry_once() {
+        let temp = TempDir::new().unwrap();
+        let cargo_toml = temp.path().join("Cargo.toml");
+        fs::write(
+            &cargo_toml,
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[features]
+hydrate = ["dep:leptos", "leptos/hydrate"]
+ssr = ["dep:leptos", "leptos/ssr"]
+"#,
+        )
+        .unwrap();
+
+        let features = vec!["hydrate".to_string(), "ssr".to_string(), "missing".to_string()];
+        wire_optional_dependency(&cargo_toml, "leptos_ui", &features).unwrap();
+        wire_optional_dependency(&cargo_toml, "leptos_ui", &features).unwrap();
+
+        let contents = fs::read_to_string(&cargo_toml).unwrap();
+        assert!(
+            contents.contains(r#"hydrate = ["dep:leptos", "leptos/hydrate", "dep:leptos_ui"]"#),
+            "got: {contents}"
+        );
+        assert!(
+            contents.contains(r#"ssr = ["dep:leptos", "leptos/ssr", "dep:leptos_ui"]"#),
+            "got: {contents}"
+        );
+        assert!(!contents.contains("missing"), "got: {contents}");
+    }
+
     #[test]
     fn test_workspace_crate_detection_for_init() {
         let temp = TempDir::new().unwrap();
@@ -699,4 +1183,84 @@ base_path_components = "src/ui"
         assert_eq!(result.color_theme, "blue");
         assert_eq!(result.base_path_components, "src/ui");
     }
+
+    #[test]
+    fn try_reading_ui_config_parses_named_themes() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("ui_config.toml");
+        fs::write(
+            &path,
+            r#"base_color = "neutral"
+base_path_components = "src/components"
+
+[[themes]]
+name = "ocean"
+base_color = "zinc"
+color_theme = "blue"
+
+[[themes]]
+name = "forest"
+base_color = "olive"
+"#,
+        )
+        .unwrap();
+        let result = UiConfig::try_reading_ui_config(path.to_str().unwrap()).unwrap();
+        assert_eq!(result.themes.len(), 2);
+        assert_eq!(result.themes[0].name, "ocean");
+        assert_eq!(result.themes[0].color_theme, "blue");
+        assert_eq!(result.themes[1].color_theme, "default");
+    }
+
+    #[test]
+    fn ui_config_without_themes_omits_themes_key() {
+        let config = UiConfig {
+            version: CONFIG_VERSION,
+            base_color: "neutral".to_string(),
+            color_theme: "default".to_string(),
+            base_path_components: "src/components".to_string(),
+            themes: Vec::new(),
+            package_manager: None,
+            leptos_mode: None,
+            package: None,
+            registry: None,
+            mcp_client: None,
+            color: None,
+            concurrency: None,
+            format_on_write: None,
+            aliases: Aliases::default(),
+            paths: ComponentPaths::default(),
+        };
+        let toml = toml::to_string_pretty(&config).unwrap();
+        assert!(!toml.contains("themes"), "got: {toml}");
+    }
+
+    #[test]
+    fn upsert_theme_replaces_theme_with_same_name() {
+        let mut config = UiConfig {
+            version: CONFIG_VERSION,
+            base_color: "neutral".to_string(),
+            color_theme: "default".to_string(),
+            base_path_components: "src/components".to_string(),
+            themes: Vec::new(),
+            package_manager: None,
+            leptos_mode: None,
+            package: None,
+            registry: None,
+            mcp_client: None,
+            color: None,
+            concurrency: None,
+            format_on_write: None,
+            aliases: Aliases::default(),
+            paths: ComponentPaths::default(),
+        };
+        let theme = |base: &str| NamedTheme {
+            name: "ocean".to_string(),
+            base_color: base.to_string(),
+            color_theme: "blue".to_string(),
+        };
+        config.upsert_theme(theme("zinc"));
+        config.upsert_theme(theme("stone"));
+        assert_eq!(config.themes.len(), 1);
+        assert_eq!(config.themes[0].base_color, "stone");
+    }
 }
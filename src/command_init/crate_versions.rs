@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::compat::is_compatible;
+use crate::shared::cli_error::{CliError, CliResult};
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Version to write for `crate_name` in the crate at `crate_dir`, without the network:
+/// 1. the newest version locked in the nearest `Cargo.lock`,
+/// 2. the newest release in the local cargo registry index cache,
+/// 3. `registry_version`, the requirement the rust-ui registry gives for it.
+///
+/// Locked and cached versions only count when compatible with `registry_version`. Never `*`:
+/// when none of them is known, the crate cannot be added.
+pub fn resolve_crate_version(
+    crate_name: &str,
+    crate_dir: &Path,
+    registry_version: Option<&str>,
+) -> CliResult<String> {
+    resolve_crate_version_in(crate_name, crate_dir, cargo_home().as_deref(), registry_version)
+}
+
+/// Every version of `crate_name` in the `Cargo.lock` contents `lock`.
+pub fn locked_versions(lock: &str, crate_name: &str) -> Vec<String> {
+    let Ok(lock) = toml::from_str::<toml::Value>(lock) else {
+        return Vec::new();
+    };
+    let packages = lock.get("package").and_then(|packages| packages.as_array());
+    packages
+        .into_iter()
+        .flatten()
+        .filter(|package| package.get("name").and_then(|n| n.as_str()) == Some(crate_name))
+        .filter_map(|package| package.get("version")?.as_str().map(str::to_string))
+        .collect()
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn resolve_crate_version_in(
+    crate_name: &str,
+    crate_dir: &Path,
+    cargo_home: Option<&Path>,
+    registry_version: Option<&str>,
+) -> CliResult<String> {
+    let fits = |version: &String| registry_version.is_none_or(|expected| is_compatible(version, expected));
+
+    let crate_dir = crate_dir.canonicalize().unwrap_or_else(|_| crate_dir.to_path_buf());
+    let lock = crate_dir.ancestors().find_map(|dir| fs::read_to_string(dir.join("Cargo.lock")).ok());
+    let locked = lock.map(|lock| locked_versions(&lock, crate_name)).unwrap_or_default();
+    if let Some(version) =
+        locked.into_iter().filter(fits).max_by_key(|version| semver::Version::parse(version).ok())
+    {
+        return Ok(version);
+    }
+
+    let cached = cargo_home.map(|home| cached_versions(home, crate_name)).unwrap_or_default();
+    if let Some(version) = cached.into_iter().filter(fits).max_by_key(|version| release_key(version)) {
+        return Ok(version);
+    }
+
+    match registry_version {
+        Some(version) if version.trim() != "*" => Ok(version.to_string()),
+        _ => Err(CliError::cargo_operation(&format!(
+            "Could not determine a version for '{crate_name}' offline (not in Cargo.lock nor in the local registry index). Run `cargo fetch` or add it with `cargo add {crate_name}`, then retry."
+        ))),
+    }
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))
+}
+
+/// Non-yanked releases of `crate_name` in the index caches under `<cargo_home>/registry/index`
+/// (sparse and git registries share the `.cache` layout).
+fn cached_versions(cargo_home: &Path, crate_name: &str) -> Vec<String> {
+    let Ok(registries) = fs::read_dir(cargo_home.join("registry/index")) else {
+        return Vec::new();
+    };
+    let relative_path = index_path(crate_name);
+    registries
+        .flatten()
+        .filter_map(|registry| fs::read(registry.path().join(".cache").join(&relative_path)).ok())
+        .flat_map(|cache| parse_index_cache(&cache, crate_name))
+        .collect()
+}
+
+/// Path of a crate in a registry index: `1/a`, `2/ab`, `3/a/abc`, `se/rd/serde`.
+fn index_path(crate_name: &str) -> PathBuf {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => Path::new("1").join(&name),
+        2 => Path::new("2").join(&name),
+        3 => Path::new("3").join(&name[..1]).join(&name),
+        _ => Path::new(&name[..2]).join(&name[2..4]).join(&name),
+    }
+}
+
+/// Releases in a cargo index cache file: a binary header, then NUL-separated pairs of a version
+/// and its JSON index line.
+fn parse_index_cache(cache: &[u8], crate_name: &str) -> Vec<String> {
+    #[derive(serde::Deserialize)]
+    struct IndexLine {
+        name: String,
+        vers: String,
+        #[serde(default)]
+        yanked: bool,
+    }
+
+    cache
+        .split(|byte| *byte == 0)
+        .filter(|entry| entry.first() == Some(&b'{'))
+        .filter_map(|entry| serde_json::from_slice::<IndexLine>(entry).ok())
+        .filter(|line| !line.yanked && line.name.eq_ignore_ascii_case(crate_name))
+        .filter(|line| release_key(&line.vers).is_some())
+        .map(|line| line.vers)
+        .collect()
+}
+
+/// `(major, minor, patch)` of a release version; `None` for pre-releases and anything else.
+fn release_key(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split('+').next()?;
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let key = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(key)
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const LOCK: &str = r#"
+version = 4
+
+[[package]]
+name = "strum"
+version = "0.26.3"
+
+[[package]]
+name = "strum"
+version = "0.27.2"
+
+[[package]]
+name = "strum"
+version = "0.26.1"
+"#;
+
+    fn write_cache(cargo_home: &Path, crate_name: &str, versions: &[(&str, bool)]) {
+        let path = cargo_home
+            .join("registry/index/index.crates.io-1949cf8c6b5b557f/.cache")
+            .join(index_path(crate_name));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut cache = vec![3, 2, 0, 0, 0];
+        cache.extend_from_slice(b"Unknown\0");
+        for (version, yanked) in versions {
+            let line =
+                format!(r#"{{"name": "{crate_name}", "vers": "{version}", "deps": [], "yanked": {yanked}}}"#);
+            cache.extend_from_slice(format!("{version}\0{line}\0").as_bytes());
+        }
+        fs::write(path, cache).unwrap();
+    }
+
+    #[test]
+    fn index_paths_follow_the_cargo_layout() {
+        assert_eq!(index_path("a"), PathBuf::from("1/a"));
+        assert_eq!(index_path("cc"), PathBuf::from("2/cc"));
+        assert_eq!(index_path("syn"), PathBuf::from("3/s/syn"));
+        assert_eq!(index_path("Serde_JSON"), PathBuf::from("se/rd/serde_json"));
+    }
+
+    #[test]
+    fn newest_locked_version_comes_first() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.lock"), LOCK).unwrap();
+        let member = dir.path().join("app");
+        fs::create_dir_all(&member).unwrap();
+        write_cache(dir.path(), "strum", &[("0.27.9", false)]);
+
+        let home = Some(dir.path());
+        assert_eq!(resolve_crate_version_in("strum", &member, home, None).unwrap(), "0.27.2");
+        assert_eq!(resolve_crate_version_in("strum", &member, home, Some("0.26")).unwrap(), "0.26.3");
+    }
+
+    #[test]
+    fn newest_cached_release_comes_next() {
+        let dir = TempDir::new().unwrap();
+        write_cache(
+            dir.path(),
+            "tw_merge",
+            &[
+                ("0.1.4", false),
+                ("0.2.0", false),
+                ("0.2.1-beta.1", false),
+                ("0.2.2", true),
+                ("0.1.10", false),
+            ],
+        );
+
+        let home = Some(dir.path());
+        assert_eq!(resolve_crate_version_in("tw_merge", dir.path(), home, None).unwrap(), "0.2.0");
+        assert_eq!(resolve_crate_version_in("tw_merge", dir.path(), home, Some("0.1")).unwrap(), "0.1.10");
+    }
+
+    #[test]
+    fn registry_version_is_the_last_resort_and_never_a_wildcard() {
+        let dir = TempDir::new().unwrap();
+        let home = Some(dir.path());
+        assert_eq!(resolve_crate_version_in("icons", dir.path(), home, Some("0.3")).unwrap(), "0.3");
+        assert!(resolve_crate_version_in("icons", dir.path(), home, None).is_err());
+        assert!(resolve_crate_version_in("icons", dir.path(), home, Some("*")).is_err());
+    }
+}
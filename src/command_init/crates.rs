@@ -1,12 +1,13 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Crate {
     pub name: &'static str,
-    #[allow(unused)] // TODO. Find a way to set the version to "0.8" instead of "0.8.2".
+    /// Fixed version requirement (e.g. "0.8"), used when the compatibility table has no entry.
     pub version: Option<&'static str>,
     pub features: Option<&'static [&'static str]>,
 }
 
 impl Crate {
-    const fn new(
+    pub const fn new(
         name: &'static str,
         version: Option<&'static str>,
         features: Option<&'static [&'static str]>,
@@ -16,8 +17,8 @@ impl Crate {
 }
 
 pub const INIT_CRATES: [Crate; 4] = [
-    // TODO. Handle leptos csr or ssr based on user input.
-    Crate::new("leptos", None, Some(&["csr"])),
+    // Features follow the detected LeptosMode (see `add_init_crates`).
+    Crate::new("leptos", None, None),
     Crate::new("tw_merge", None, Some(&["variant"])),
     Crate::new("icons", None, Some(&["leptos"])),
     Crate::new("leptos_ui", None, None),
@@ -0,0 +1,234 @@
+// Managed regions of the Tailwind input file.
+// The CLI only ever rewrites the content between `/* ui-cli:<name>:start */` and
+// `/* ui-cli:<name>:end */`; everything outside those markers belongs to the user.
+
+/// Where a region goes when it is not in the file yet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegionPlacement {
+    /// Right after the last `@import` / `@tailwind` line (or at the top when there is none).
+    AfterImports,
+    /// At the end of the file.
+    End,
+}
+
+pub fn region_start(name: &str) -> String {
+    format!("/* ui-cli:{name}:start */")
+}
+
+pub fn region_end(name: &str) -> String {
+    format!("/* ui-cli:{name}:end */")
+}
+
+/// Wrap `content` in the start/end markers of region `name`.
+pub fn wrap_region(name: &str, content: &str) -> String {
+    let content = content.trim_matches('\n');
+    format!("{}\n{content}\n{}\n", region_start(name), region_end(name))
+}
+
+/// Byte range of region `name` in `css`, markers and trailing newline included.
+pub fn find_region(css: &str, name: &str) -> Option<(usize, usize)> {
+    let start_marker = region_start(name);
+    let end_marker = region_end(name);
+
+    let start = css.find(&start_marker)?;
+    let end_offset = css.get(start..)?.find(&end_marker)?;
+    let mut end = start + end_offset + end_marker.len();
+    if css.get(end..).is_some_and(|rest| rest.starts_with('\n')) {
+        end += 1;
+    }
+    Some((start, end))
+}
+
+/// Replace the content of region `name`, or insert the region at `placement` when missing.
+pub fn upsert_region(css: &str, name: &str, content: &str, placement: RegionPlacement) -> String {
+    let region = wrap_region(name, content);
+
+    if let Some((start, end)) = find_region(css, name) {
+        return format!(
+            "{}{region}{}",
+            css.get(..start).unwrap_or_default(),
+            css.get(end..).unwrap_or_default()
+        );
+    }
+
+    match placement {
+        RegionPlacement::AfterImports => {
+            match end_of_imports(css).and_then(|idx| css.split_at_checked(idx)) {
+                Some((imports, rest)) => format!("{imports}\n{region}{}", separate(rest)),
+                None => format!("{region}{}", separate(css)),
+            }
+        }
+        RegionPlacement::End if css.trim().is_empty() => region,
+        RegionPlacement::End if css.ends_with('\n') => format!("{css}\n{region}"),
+        RegionPlacement::End => format!("{css}\n\n{region}"),
+    }
+}
+
+/// Make sure each `@import "<module>";` is present, adding missing ones after the existing imports.
+pub fn merge_imports(css: &str, modules: &[&str]) -> String {
+    let missing: Vec<String> = modules
+        .iter()
+        .filter(|module| !has_import(css, module))
+        .map(|module| format!("@import \"{module}\";\n"))
+        .collect();
+
+    if missing.is_empty() {
+        return css.to_string();
+    }
+
+    insert_header_lines(css, &missing.concat())
+}
+
+/// Make sure each `@tailwind <layer>;` directive (Tailwind v3) is present, adding missing ones
+/// after the existing header lines.
+pub fn merge_tailwind_directives(css: &str, layers: &[&str]) -> String {
+    let missing: Vec<String> = layers
+        .iter()
+        .filter(|layer| !has_tailwind_directive(css, layer))
+        .map(|layer| format!("@tailwind {layer};\n"))
+        .collect();
+
+    if missing.is_empty() {
+        return css.to_string();
+    }
+
+    insert_header_lines(css, &missing.concat())
+}
+
+/// Whether `css` imports `module`, with either quote style and any trailing layer/source options.
+pub fn has_import(css: &str, module: &str) -> bool {
+    css.lines()
+        .map(str::trim_start)
+        .filter(|line| line.starts_with("@import"))
+        .any(|line| line.contains(&format!("\"{module}\"")) || line.contains(&format!("'{module}'")))
+}
+
+/// Whether `css` has a top-level `@tailwind <layer>;` directive.
+pub fn has_tailwind_directive(css: &str, layer: &str) -> bool {
+    css.lines()
+        .filter_map(|line| line.trim().strip_prefix("@tailwind"))
+        .any(|rest| rest.trim().trim_end_matches(';').trim() == layer)
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Insert `lines` after the existing header lines, or at the top of the file.
+fn insert_header_lines(css: &str, lines: &str) -> String {
+    match end_of_imports(css).and_then(|idx| css.split_at_checked(idx)) {
+        Some((imports, rest)) => format!("{imports}{lines}{rest}"),
+        None if css.trim().is_empty() => lines.to_string(),
+        None => format!("{lines}\n{css}"),
+    }
+}
+
+/// Byte offset just past the newline of the last top-level `@import` / `@tailwind` line.
+fn end_of_imports(css: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut end = None;
+    for line in css.split_inclusive('\n') {
+        offset += line.len();
+        if line.starts_with("@import") || line.starts_with("@tailwind") {
+            end = Some(offset);
+        }
+    }
+    end
+}
+
+/// Blank line between an inserted region and whatever follows it.
+fn separate(rest: &str) -> String {
+    if rest.is_empty() || rest.starts_with('\n') { rest.to_string() } else { format!("\n{rest}") }
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_region_adds_markers() {
+        assert_eq!(
+            wrap_region("theme", ":root {}\n"),
+            "/* ui-cli:theme:start */\n:root {}\n/* ui-cli:theme:end */\n"
+        );
+    }
+
+    #[test]
+    fn upsert_region_replaces_only_region_content() {
+        let css =
+            "body { color: red; }\n\n/* ui-cli:theme:start */\nold\n/* ui-cli:theme:end */\n\n.mine {}\n";
+        let updated = upsert_region(css, "theme", "new", RegionPlacement::End);
+        assert_eq!(
+            updated,
+            "body { color: red; }\n\n/* ui-cli:theme:start */\nnew\n/* ui-cli:theme:end */\n\n.mine {}\n"
+        );
+    }
+
+    #[test]
+    fn upsert_region_inserts_after_imports() {
+        let css = "@import \"tailwindcss\";\n.mine {}\n";
+        let updated = upsert_region(css, "theme", "vars", RegionPlacement::AfterImports);
+        assert_eq!(
+            updated,
+            "@import \"tailwindcss\";\n\n/* ui-cli:theme:start */\nvars\n/* ui-cli:theme:end */\n\n.mine {}\n"
+        );
+    }
+
+    #[test]
+    fn upsert_region_inserts_at_top_without_imports() {
+        let updated = upsert_region(".mine {}\n", "theme", "vars", RegionPlacement::AfterImports);
+        assert!(updated.starts_with("/* ui-cli:theme:start */\nvars\n"));
+        assert!(updated.ends_with("\n\n.mine {}\n"));
+    }
+
+    #[test]
+    fn upsert_region_appends_at_end() {
+        let updated = upsert_region(".mine {}\n", "base", "@layer base {}", RegionPlacement::End);
+        assert_eq!(updated, ".mine {}\n\n/* ui-cli:base:start */\n@layer base {}\n/* ui-cli:base:end */\n");
+    }
+
+    #[test]
+    fn upsert_region_is_idempotent() {
+        let once = upsert_region(".mine {}\n", "base", "x", RegionPlacement::End);
+        let twice = upsert_region(&once, "base", "x", RegionPlacement::End);
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn merge_imports_adds_missing_after_existing() {
+        let css = "@import \"tailwindcss\";\n\n.mine {}\n";
+        let merged = merge_imports(css, &["tailwindcss", "tw-animate-css"]);
+        assert_eq!(merged, "@import \"tailwindcss\";\n@import \"tw-animate-css\";\n\n.mine {}\n");
+    }
+
+    #[test]
+    fn merge_imports_prepends_when_no_imports() {
+        let merged = merge_imports(".mine {}\n", &["tailwindcss"]);
+        assert_eq!(merged, "@import \"tailwindcss\";\n\n.mine {}\n");
+    }
+
+    #[test]
+    fn merge_imports_recognises_single_quotes_and_options() {
+        let css = "@import 'tailwindcss' source(none);\n";
+        assert_eq!(merge_imports(css, &["tailwindcss"]), css);
+    }
+
+    #[test]
+    fn merge_tailwind_directives_adds_missing_layers() {
+        let css = "@tailwind base;\n\n.mine {}\n";
+        let merged = merge_tailwind_directives(css, &["base", "components", "utilities"]);
+        assert_eq!(merged, "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n\n.mine {}\n");
+        assert_eq!(merge_tailwind_directives(&merged, &["base", "components", "utilities"]), merged);
+    }
+
+    #[test]
+    fn upsert_region_inserts_after_tailwind_directives() {
+        let css = "@tailwind base;\n@tailwind utilities;\n.mine {}\n";
+        let updated = upsert_region(css, "theme", "vars", RegionPlacement::AfterImports);
+        assert!(updated.starts_with("@tailwind base;\n@tailwind utilities;\n\n/* ui-cli:theme:start */\n"));
+    }
+}
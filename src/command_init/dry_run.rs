@@ -0,0 +1,170 @@
+use similar::TextDiff;
+
+use super::config::{CrateTarget, InitCratesPlan};
+
+const CONTEXT_LINES: usize = 3;
+
+/// A file `ui init` would write, with its current and planned content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    /// `None` when the file does not exist yet.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl FileChange {
+    fn action(&self) -> &'static str {
+        match &self.before {
+            None => "create",
+            Some(before) if *before == self.after => "unchanged",
+            Some(_) => "overwrite",
+        }
+    }
+}
+
+/// Everything `ui init` would do, computed without touching the project.
+#[derive(Debug, Clone)]
+pub struct InitPlan {
+    pub files: Vec<FileChange>,
+    pub crates: InitCratesPlan,
+    pub install_command: String,
+}
+
+/* ========================================================== */
+/*                      🖨  FORMATTERS 🖨                      */
+/* ========================================================== */
+
+pub fn format_init_plan(plan: &InitPlan) -> String {
+    let mut lines = vec!["[dry-run] Files:".to_string()];
+    for file in &plan.files {
+        lines.push(format!("  {:<10} {}", file.action(), file.path));
+    }
+    for file in plan.files.iter().filter(|f| f.action() != "unchanged") {
+        lines.push(String::new());
+        lines.push(format_file_diff(file));
+    }
+
+    lines.push(String::new());
+    if plan.crates.crates.is_empty() {
+        lines.push("[dry-run] No crates to add.".to_string());
+    } else {
+        lines.push("[dry-run] Crates:".to_string());
+        for crate_plan in &plan.crates.crates {
+            let mut spec = crate_plan.my_crate.name.to_string();
+            if let Some(version) = &crate_plan.version {
+                spec.push_str(&format!("@{version}"));
+            }
+            if let Some(features) = crate_plan.my_crate.features.filter(|f| !f.is_empty()) {
+                spec.push_str(&format!(" [{}]", features.join(", ")));
+            }
+            let via = match crate_plan.target {
+                CrateTarget::CargoAdd => "cargo add",
+                CrateTarget::WorkspaceRef => "existing [workspace.dependencies] entry",
+                CrateTarget::WorkspaceDependencies => "new [workspace.dependencies] entry",
+            };
+            let optional = if crate_plan.optional {
+                format!(", optional via {}", plan.crates.optional_features.join(", "))
+            } else {
+                String::new()
+            };
+            lines.push(format!("  {spec}  ({via}{optional})"));
+        }
+    }
+    for warning in &plan.crates.warnings {
+        lines.push(format!("[dry-run] Warning: {warning}"));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("[dry-run] Would run: {}", plan.install_command));
+    lines.join("\n")
+}
+
+/// Unified diff of a planned file change; new files diff against an empty file.
+pub fn format_file_diff(file: &FileChange) -> String {
+    let before = file.before.as_deref().unwrap_or_default();
+    let old_header = if file.before.is_some() { file.path.clone() } else { "/dev/null".to_string() };
+    TextDiff::from_lines(before, &file.after)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(&old_header, &file.path)
+        .to_string()
+        .trim_end()
+        .to_string()
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_init::config::CratePlan;
+    use crate::command_init::crates::Crate;
+
+    fn plan(files: Vec<FileChange>, crates: Vec<CratePlan>) -> InitPlan {
+        InitPlan {
+            files,
+            crates: InitCratesPlan {
+                workspace_info: None,
+                crates,
+                optional_features: vec!["hydrate".to_string(), "ssr".to_string()],
+                warnings: Vec::new(),
+            },
+            install_command: "pnpm add tailwindcss".to_string(),
+        }
+    }
+
+    fn file(path: &str, before: Option<&str>, after: &str) -> FileChange {
+        FileChange { path: path.to_string(), before: before.map(str::to_string), after: after.to_string() }
+    }
+
+    #[test]
+    fn file_actions() {
+        assert_eq!(file("a", None, "x").action(), "create");
+        assert_eq!(file("a", Some("x"), "x").action(), "unchanged");
+        assert_eq!(file("a", Some("x"), "y").action(), "overwrite");
+    }
+
+    #[test]
+    fn plan_lists_files_and_diffs_changed_ones() {
+        let out = format_init_plan(&plan(
+            vec![
+                file("ui_config.toml", Some("base_color = \"zinc\"\n"), "base_color = \"stone\"\n"),
+                file("package.json", Some("{}\n"), "{}\n"),
+                file("style/tailwind.css", None, "@import \"tailwindcss\";\n"),
+            ],
+            Vec::new(),
+        ));
+        assert!(out.contains("  overwrite  ui_config.toml"));
+        assert!(out.contains("  unchanged  package.json"));
+        assert!(out.contains("  create     style/tailwind.css"));
+        assert!(out.contains("-base_color = \"zinc\"\n+base_color = \"stone\""));
+        assert!(out.contains("--- /dev/null\n+++ style/tailwind.css"));
+        assert!(!out.contains("+++ package.json"));
+        assert!(out.contains("[dry-run] No crates to add."));
+        assert!(out.ends_with("[dry-run] Would run: pnpm add tailwindcss"));
+    }
+
+    #[test]
+    fn plan_describes_crate_targets() {
+        let crates = vec![
+            CratePlan {
+                my_crate: Crate::new("tw_merge", None, Some(&["variant"])),
+                version: Some("0.1".to_string()),
+                optional: false,
+                target: CrateTarget::WorkspaceDependencies,
+            },
+            CratePlan {
+                my_crate: Crate::new("leptos_ui", None, None),
+                version: None,
+                optional: true,
+                target: CrateTarget::CargoAdd,
+            },
+        ];
+        let out = format_init_plan(&plan(Vec::new(), crates));
+        assert!(out.contains("  tw_merge@0.1 [variant]  (new [workspace.dependencies] entry)"));
+        assert!(out.contains("  leptos_ui  (cargo add, optional via hydrate, ssr)"));
+    }
+}
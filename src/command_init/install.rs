@@ -1,53 +1,151 @@
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 use strum::AsRefStr;
 
+use super::tailwind_version::TailwindVersion;
 use crate::shared::cli_error::{CliError, CliResult};
 use crate::shared::task_spinner::TaskSpinner;
 
+const PACKAGE_JSON: &str = "package.json";
+
+/// Lockfiles in detection order; the first one found decides the package manager.
+const LOCKFILES: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("bun.lockb", PackageManager::Bun),
+    ("bun.lock", PackageManager::Bun),
+    ("yarn.lock", PackageManager::Yarn),
+    ("package-lock.json", PackageManager::Npm),
+];
+
 #[derive(Debug, Clone, AsRefStr)]
 pub enum InstallType {
     Tailwind,
+    #[strum(serialize = "Tailwind v3")]
+    TailwindV3,
 }
 
 impl InstallType {
+    /// Tailwind install type matching the project's major version.
+    pub fn tailwind(version: TailwindVersion) -> Self {
+        match version {
+            TailwindVersion::V3 => Self::TailwindV3,
+            TailwindVersion::V4 => Self::Tailwind,
+        }
+    }
+
     fn dependencies(&self) -> &'static [&'static str] {
         match self {
             Self::Tailwind => &["@tailwindcss/cli", "tailwindcss", "tw-animate-css"],
+            Self::TailwindV3 => &["tailwindcss@3", "tailwindcss-animate"],
         }
     }
 
     fn name(&self) -> &str {
         self.as_ref()
     }
+
+    /// Command line `install_dependencies` runs for this install type, e.g. `pnpm add tailwindcss`.
+    pub fn install_command(&self, package_manager: PackageManager) -> String {
+        format!(
+            "{} {} {}",
+            package_manager.command(),
+            package_manager.add_subcommand(),
+            self.dependencies().join(" ")
+        )
+    }
 }
 
-#[derive(Debug, Clone, AsRefStr)]
+#[derive(Debug, Clone, Copy, PartialEq, AsRefStr)]
 #[strum(serialize_all = "lowercase")]
-enum PackageManager {
+pub enum PackageManager {
     Pnpm,
     Npm,
+    Yarn,
+    Bun,
 }
 
 impl PackageManager {
-    fn command(&self) -> &str {
+    pub const ALL: &'static [PackageManager] =
+        &[PackageManager::Npm, PackageManager::Pnpm, PackageManager::Yarn, PackageManager::Bun];
+
+    pub fn command(&self) -> &str {
         self.as_ref()
     }
 
+    pub fn all_labels() -> Vec<&'static str> {
+        Self::ALL.iter().map(|pm| pm.as_ref()).collect()
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|pm| pm.command().eq_ignore_ascii_case(s))
+    }
+
+    /// Like `from_str`, with an error listing the supported package managers.
+    pub fn parse(s: &str) -> CliResult<Self> {
+        Self::from_str(s).ok_or_else(|| {
+            CliError::validation(&format!(
+                "Unknown package manager '{s}'. Expected one of: {}",
+                Self::all_labels().join(", ")
+            ))
+        })
+    }
+
+    /// Subcommand that adds packages to package.json (`npm install`, `pnpm add`, ...).
+    fn add_subcommand(&self) -> &'static str {
+        match self {
+            Self::Npm => "install",
+            Self::Pnpm | Self::Yarn | Self::Bun => "add",
+        }
+    }
+
+    /// Lockfiles this package manager may write.
+    pub fn lockfiles(&self) -> Vec<&'static str> {
+        LOCKFILES.iter().filter(|(_, pm)| pm == self).map(|(lockfile, _)| *lockfile).collect()
+    }
+
     fn is_pnpm_available() -> bool {
         Command::new("pnpm").arg("--version").output().map(|output| output.status.success()).unwrap_or(false)
     }
 
-    fn detect() -> PackageManager {
-        if Self::is_pnpm_available() { PackageManager::Pnpm } else { PackageManager::Npm }
+    /// Pick the package manager for the project in the current directory.
+    ///
+    /// Priority: `explicit` (`--package-manager`), then `configured` (`package_manager` in
+    /// ui_config.toml), then the project files, then pnpm when available, else npm.
+    pub fn resolve(explicit: Option<PackageManager>, configured: Option<&str>) -> CliResult<PackageManager> {
+        if let Some(package_manager) = explicit {
+            return Ok(package_manager);
+        }
+        if let Some(label) = configured {
+            return Self::parse(label);
+        }
+        if let Some(package_manager) = Self::detect_from_path(Path::new(".")) {
+            return Ok(package_manager);
+        }
+        Ok(if Self::is_pnpm_available() { PackageManager::Pnpm } else { PackageManager::Npm })
     }
-}
 
-pub async fn install_dependencies(install_types: &[InstallType]) -> CliResult<()> {
-    let package_manager = PackageManager::detect();
+    /// Package manager declared by the project: the `packageManager` field of package.json
+    /// (e.g. `"pnpm@9.1.0"`) or, failing that, the lockfile present in `dir`.
+    pub fn detect_from_path(dir: &Path) -> Option<PackageManager> {
+        let declared = fs::read_to_string(dir.join(PACKAGE_JSON))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|json| json.get("packageManager")?.as_str().map(str::to_string))
+            .and_then(|field| Self::from_str(field.split('@').next().unwrap_or_default()));
 
+        declared
+            .or_else(|| LOCKFILES.iter().find(|(lockfile, _)| dir.join(lockfile).exists()).map(|(_, pm)| *pm))
+    }
+}
+
+pub async fn install_dependencies(
+    install_types: &[InstallType],
+    package_manager: PackageManager,
+) -> CliResult<()> {
     for install_type in install_types {
-        install_with_package_manager(install_type.clone(), package_manager.clone())?;
+        install_packages(install_type.name(), install_type.dependencies(), package_manager)?;
     }
 
     Ok(())
@@ -57,25 +155,29 @@ pub async fn install_dependencies(install_types: &[InstallType]) -> CliResult<()
 /*                     ✨ FUNCTIONS ✨                        */
 /* ========================================================== */
 
-fn install_with_package_manager(install_type: InstallType, package_manager: PackageManager) -> CliResult<()> {
-    let dependencies = install_type.dependencies();
-    let deps_list = dependencies.join(" ");
+/// Add `packages` to the project with `package_manager`.
+/// Every JS dependency install goes through here so the package-manager choice is honoured.
+pub fn install_packages(label: &str, packages: &[&str], package_manager: PackageManager) -> CliResult<()> {
+    if packages.is_empty() {
+        return Ok(());
+    }
+
+    let deps_list = packages.join(" ");
     let pm_name = package_manager.command();
-    let type_name = install_type.name();
-    let message = format!("Installing {type_name} dependencies with {pm_name}: {deps_list}");
+    let message = format!("Installing {label} dependencies with {pm_name}: {deps_list}");
     let spinner = TaskSpinner::new(&message);
 
     let mut cmd = Command::new(package_manager.command());
-    cmd.arg("install");
+    cmd.arg(package_manager.add_subcommand());
 
-    for dep in dependencies {
-        cmd.arg(dep);
+    for package in packages {
+        cmd.arg(package);
     }
 
     let output = cmd.output().map_err(|_| CliError::npm_install_failed())?;
 
     if output.status.success() {
-        let success_message = format!("All {} dependencies installed successfully", install_type.name());
+        let success_message = format!("All {label} dependencies installed successfully");
         spinner.finish_success(&success_message);
     } else {
         return Err(CliError::npm_install_failed());
@@ -83,3 +185,77 @@ fn install_with_package_manager(install_type: InstallType, package_manager: Pack
 
     Ok(())
 }
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn from_str_is_case_insensitive() {
+        assert_eq!(PackageManager::from_str("Yarn"), Some(PackageManager::Yarn));
+        assert_eq!(PackageManager::from_str("bun"), Some(PackageManager::Bun));
+        assert_eq!(PackageManager::from_str("deno"), None);
+        assert!(PackageManager::parse("deno").is_err());
+    }
+
+    #[test]
+    fn add_subcommand_matches_package_manager() {
+        assert_eq!(PackageManager::Npm.add_subcommand(), "install");
+        assert_eq!(PackageManager::Yarn.add_subcommand(), "add");
+        assert_eq!(PackageManager::Bun.add_subcommand(), "add");
+    }
+
+    #[test]
+    fn install_command_uses_add_subcommand() {
+        assert_eq!(
+            InstallType::TailwindV3.install_command(PackageManager::Yarn),
+            "yarn add tailwindcss@3 tailwindcss-animate"
+        );
+        assert!(InstallType::Tailwind.install_command(PackageManager::Npm).starts_with("npm install "));
+    }
+
+    #[test]
+    fn detect_from_lockfiles() {
+        for (lockfile, expected) in LOCKFILES {
+            let temp = TempDir::new().unwrap();
+            fs::write(temp.path().join(lockfile), "").unwrap();
+            assert_eq!(PackageManager::detect_from_path(temp.path()), Some(*expected), "{lockfile}");
+        }
+    }
+
+    #[test]
+    fn lockfiles_per_package_manager() {
+        assert_eq!(PackageManager::Bun.lockfiles(), vec!["bun.lockb", "bun.lock"]);
+        assert_eq!(PackageManager::Npm.lockfiles(), vec!["package-lock.json"]);
+    }
+
+    #[test]
+    fn package_manager_field_wins_over_lockfile() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("package.json"), r#"{"packageManager": "yarn@4.1.0"}"#).unwrap();
+        fs::write(temp.path().join("package-lock.json"), "{}").unwrap();
+        assert_eq!(PackageManager::detect_from_path(temp.path()), Some(PackageManager::Yarn));
+    }
+
+    #[test]
+    fn detect_returns_none_without_project_files() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(PackageManager::detect_from_path(temp.path()), None);
+    }
+
+    #[test]
+    fn resolve_prefers_explicit_then_config() {
+        assert_eq!(
+            PackageManager::resolve(Some(PackageManager::Bun), Some("yarn")).unwrap(),
+            PackageManager::Bun
+        );
+        assert_eq!(PackageManager::resolve(None, Some("yarn")).unwrap(), PackageManager::Yarn);
+        assert!(PackageManager::resolve(None, Some("deno")).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+
+use cargo_toml::Manifest;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::workspace_utils::{WorkspaceInfo, load_cargo_manifest};
+
+/// Feature names Leptos uses for its rendering modes.
+const MODE_FEATURES: &[&str] = &["csr", "ssr", "hydrate"];
+
+/// How the target crate renders: client-side only, or cargo-leptos SSR with hydration.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LeptosMode {
+    #[default]
+    Csr,
+    /// Server crate (usually with a `hydrate` feature next to `ssr`).
+    Ssr,
+    /// Client crate of an SSR app, split from the server crate.
+    Hydrate,
+}
+
+impl LeptosMode {
+    pub const ALL: &'static [LeptosMode] = &[LeptosMode::Csr, LeptosMode::Ssr, LeptosMode::Hydrate];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|mode| mode.label().eq_ignore_ascii_case(s))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Csr => "csr",
+            Self::Ssr => "ssr",
+            Self::Hydrate => "hydrate",
+        }
+    }
+
+    /// Features to enable on `leptos` itself when init has to add it.
+    pub fn leptos_features(&self) -> &'static [&'static str] {
+        match self {
+            Self::Csr => &["csr"],
+            Self::Ssr => &["ssr"],
+            Self::Hydrate => &["hydrate"],
+        }
+    }
+}
+
+/// Rendering mode of the target crate, and how new UI crates must be wired into it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LeptosSetup {
+    pub mode: LeptosMode,
+    /// When `leptos` is an optional dependency, the crate features that enable it
+    /// (e.g. `ssr` and `hydrate`). UI crates are then added as optional too and
+    /// enabled from each of these features with `dep:<name>`.
+    pub optional_features: Vec<String>,
+}
+
+/// Detect the Leptos setup of the crate init installs into.
+pub fn detect_leptos_setup(info: &WorkspaceInfo) -> LeptosSetup {
+    let Some(crate_path) = &info.target_crate_path else {
+        return LeptosSetup::default();
+    };
+    let Ok(Some(manifest)) = load_cargo_manifest(&crate_path.join("Cargo.toml")) else {
+        return LeptosSetup::default();
+    };
+    let workspace_manifest = info
+        .workspace_root
+        .as_deref()
+        .filter(|_| info.is_workspace)
+        .and_then(|root| load_cargo_manifest(&root.join("Cargo.toml")).ok().flatten());
+
+    detect_from_manifests(&manifest, workspace_manifest.as_ref())
+}
+
+/// Detection order: the crate's own mode features, then the features of its `leptos` dependency,
+/// then cargo-leptos metadata (which implies SSR). Falls back to CSR.
+pub fn detect_from_manifests(manifest: &Manifest, workspace_manifest: Option<&Manifest>) -> LeptosSetup {
+    let leptos_features = leptos_dependency_features(manifest, workspace_manifest);
+
+    let mode = mode_from_names(manifest.features.keys().map(String::as_str))
+        .or_else(|| mode_from_names(leptos_features.iter().map(String::as_str)))
+        .or_else(|| has_leptos_metadata(manifest, workspace_manifest).then_some(LeptosMode::Ssr))
+        .unwrap_or_default();
+
+    LeptosSetup { mode, optional_features: features_enabling_optional_leptos(manifest) }
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// `ssr` wins over `hydrate` (a server crate usually declares both), which wins over `csr`.
+fn mode_from_names<'a>(names: impl Iterator<Item = &'a str>) -> Option<LeptosMode> {
+    let names: HashSet<&str> = names.filter(|name| MODE_FEATURES.contains(name)).collect();
+    if names.contains("ssr") {
+        Some(LeptosMode::Ssr)
+    } else if names.contains("hydrate") {
+        Some(LeptosMode::Hydrate)
+    } else if names.contains("csr") {
+        Some(LeptosMode::Csr)
+    } else {
+        None
+    }
+}
+
+/// Features requested on the `leptos` dependency, including those declared in
+/// `[workspace.dependencies]` for `leptos.workspace = true`.
+fn leptos_dependency_features(manifest: &Manifest, workspace_manifest: Option<&Manifest>) -> Vec<String> {
+    let mut features: Vec<String> =
+        manifest.dependencies.get("leptos").map(|dep| dep.req_features().to_vec()).unwrap_or_default();
+
+    if let Some(dep) =
+        workspace_manifest.and_then(|ws| ws.workspace.as_ref()).and_then(|ws| ws.dependencies.get("leptos"))
+    {
+        features.extend(dep.req_features().iter().cloned());
+    }
+    features
+}
+
+fn has_leptos_metadata(manifest: &Manifest, workspace_manifest: Option<&Manifest>) -> bool {
+    let package_metadata = manifest
+        .package
+        .as_ref()
+        .and_then(|p| p.metadata.as_ref())
+        .is_some_and(|m| m.get("leptos").is_some());
+    let workspace_metadata = [Some(manifest), workspace_manifest]
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.workspace.as_ref()?.metadata.as_ref())
+        .any(|m| m.get("leptos").is_some());
+    package_metadata || workspace_metadata
+}
+
+/// Crate features that turn on an optional `leptos` dependency.
+fn features_enabling_optional_leptos(manifest: &Manifest) -> Vec<String> {
+    if !manifest.dependencies.get("leptos").is_some_and(|dep| dep.optional()) {
+        return Vec::new();
+    }
+    manifest
+        .features
+        .iter()
+        .filter(|(_, enables)| {
+            enables.iter().any(|f| f == "leptos" || f == "dep:leptos" || f.starts_with("leptos/"))
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse without workspace resolution, like member manifests in a temp dir.
+    fn parse(toml: &str) -> Manifest {
+        Manifest::from_slice(toml.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn csr_from_leptos_features() {
+        let manifest = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos = { version = "0.8", features = ["csr"] }
+"#,
+        );
+        let setup = detect_from_manifests(&manifest, None);
+        assert_eq!(setup.mode, LeptosMode::Csr);
+        assert!(setup.optional_features.is_empty());
+    }
+
+    #[test]
+    fn ssr_from_cargo_leptos_features() {
+        let manifest = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos = { version = "0.8" }
+axum = { version = "0.8", optional = true }
+
+[features]
+hydrate = ["leptos/hydrate"]
+ssr = ["dep:axum", "leptos/ssr"]
+"#,
+        );
+        let setup = detect_from_manifests(&manifest, None);
+        assert_eq!(setup.mode, LeptosMode::Ssr);
+        // leptos is not optional, so UI crates are plain dependencies
+        assert!(setup.optional_features.is_empty());
+    }
+
+    #[test]
+    fn hydrate_only_client_crate() {
+        let manifest = parse(
+            r#"[package]
+name = "frontend"
+version = "0.1.0"
+
+[dependencies]
+leptos = { version = "0.8", features = ["hydrate"] }
+"#,
+        );
+        assert_eq!(detect_from_manifests(&manifest, None).mode, LeptosMode::Hydrate);
+    }
+
+    #[test]
+    fn ssr_from_package_metadata_only() {
+        let manifest = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos = "0.8"
+
+[package.metadata.leptos]
+output-name = "app"
+"#,
+        );
+        assert_eq!(detect_from_manifests(&manifest, None).mode, LeptosMode::Ssr);
+    }
+
+    #[test]
+    fn mode_from_workspace_dependency_features() {
+        let member = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos.workspace = true
+"#,
+        );
+        let root = parse(
+            r#"[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+leptos = { version = "0.8", features = ["csr"] }
+"#,
+        );
+        assert_eq!(detect_from_manifests(&member, Some(&root)).mode, LeptosMode::Csr);
+    }
+
+    #[test]
+    fn optional_leptos_lists_enabling_features() {
+        let manifest = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos = { version = "0.8", optional = true }
+
+[features]
+default = []
+hydrate = ["dep:leptos", "leptos/hydrate"]
+ssr = ["leptos/ssr"]
+"#,
+        );
+        let setup = detect_from_manifests(&manifest, None);
+        assert_eq!(setup.mode, LeptosMode::Ssr);
+        assert_eq!(setup.optional_features, vec!["hydrate".to_string(), "ssr".to_string()]);
+    }
+
+    #[test]
+    fn defaults_to_csr() {
+        let manifest = parse(
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+leptos = "0.8"
+"#,
+        );
+        assert_eq!(detect_from_manifests(&manifest, None), LeptosSetup::default());
+    }
+}
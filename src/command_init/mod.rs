@@ -1,9 +1,18 @@
 pub mod _init;
 
 pub mod backup;
+pub mod cargo_metadata;
 pub mod colors;
+pub mod compat;
 pub mod config;
+pub mod crate_versions;
 pub mod crates;
+pub mod css_regions;
+pub mod dry_run;
 pub mod install;
+pub mod leptos_mode;
+pub mod scaffold;
+pub mod tailwind_sources;
+pub mod tailwind_version;
 pub mod template;
 pub mod workspace_utils;
@@ -0,0 +1,345 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use heck::ToSnakeCase;
+
+use super::leptos_mode::LeptosMode;
+use crate::shared::cli_error::{CliError, CliResult};
+use crate::shared::task_spinner::TaskSpinner;
+
+/// Rendering modes `ui init <project_name> --mode` can scaffold.
+pub const SCAFFOLD_MODES: &[&str] = &["csr", "ssr"];
+
+/// Placeholders filled in every template.
+const NAME: &str = "__NAME__";
+const CRATE_NAME: &str = "__CRATE_NAME__";
+
+const GITIGNORE: &str = "/target
+node_modules/
+";
+
+const TAILWIND_CSS: &str = "@import \"tailwindcss\";
+";
+
+/* ------------------------------ CSR (trunk) ------------------------------ */
+
+const CSR_CARGO_TOML: &str = r#"[package]
+name = "__NAME__"
+version = "0.1.0"
+edition = "2024"
+
+[dependencies]
+console_error_panic_hook = "0.1"
+leptos = { version = "0.8", features = ["csr"] }
+
+[package.metadata.leptos]
+tailwind-input-file = "style/tailwind.css"
+"#;
+
+const CSR_INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+    <title>__NAME__</title>
+    <link data-trunk rel="tailwind-css" href="style/tailwind.css" />
+  </head>
+  <body></body>
+</html>
+"#;
+
+const CSR_MAIN_RS: &str = r#"mod app;
+
+fn main() {
+    console_error_panic_hook::set_once();
+    leptos::mount::mount_to_body(app::App);
+}
+"#;
+
+const CSR_APP_RS: &str = r#"use leptos::prelude::*;
+
+#[component]
+pub fn App() -> impl IntoView {
+    view! {
+        <main class="flex flex-col gap-4 justify-center items-center min-h-screen">
+            <h1 class="text-3xl font-bold">"__NAME__"</h1>
+            <p>"Add components with `ui add button`."</p>
+        </main>
+    }
+}
+"#;
+
+/* ---------------------------- SSR (cargo-leptos) ---------------------------- */
+
+const SSR_CARGO_TOML: &str = r#"[package]
+name = "__NAME__"
+version = "0.1.0"
+edition = "2024"
+
+[lib]
+crate-type = ["cdylib", "rlib"]
+
+[dependencies]
+axum = { version = "0.8", optional = true }
+console_error_panic_hook = { version = "0.1", optional = true }
+leptos = "0.8"
+leptos_axum = { version = "0.8", optional = true }
+leptos_meta = "0.8"
+leptos_router = "0.8"
+tokio = { version = "1", features = ["rt-multi-thread"], optional = true }
+wasm-bindgen = { version = "0.2", optional = true }
+
+[features]
+hydrate = ["leptos/hydrate", "dep:console_error_panic_hook", "dep:wasm-bindgen"]
+ssr = ["dep:axum", "dep:tokio", "dep:leptos_axum", "leptos/ssr", "leptos_meta/ssr", "leptos_router/ssr"]
+
+[package.metadata.leptos]
+output-name = "__CRATE_NAME__"
+site-root = "target/site"
+site-pkg-dir = "pkg"
+tailwind-input-file = "style/tailwind.css"
+site-addr = "127.0.0.1:3000"
+reload-port = 3001
+bin-features = ["ssr"]
+bin-default-features = false
+lib-features = ["hydrate"]
+lib-default-features = false
+"#;
+
+const SSR_LIB_RS: &str = r#"pub mod app;
+
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn hydrate() {
+    console_error_panic_hook::set_once();
+    leptos::mount::hydrate_body(app::App);
+}
+"#;
+
+const SSR_MAIN_RS: &str = r#"#[cfg(feature = "ssr")]
+#[tokio::main]
+async fn main() {
+    use __CRATE_NAME__::app::{App, shell};
+    use axum::Router;
+    use leptos::prelude::*;
+    use leptos_axum::{LeptosRoutes, generate_route_list};
+
+    let conf = get_configuration(None).expect("invalid [package.metadata.leptos]");
+    let addr = conf.leptos_options.site_addr;
+    let leptos_options = conf.leptos_options;
+    let routes = generate_route_list(App);
+
+    let app = Router::new()
+        .leptos_routes(&leptos_options, routes, {
+            let leptos_options = leptos_options.clone();
+            move || shell(leptos_options.clone())
+        })
+        .fallback(leptos_axum::file_and_error_handler(shell))
+        .with_state(leptos_options);
+
+    let listener = tokio::net::TcpListener::bind(&addr).await.expect("failed to bind site-addr");
+    println!("listening on http://{addr}");
+    axum::serve(listener, app.into_make_service()).await.expect("server error");
+}
+
+#[cfg(not(feature = "ssr"))]
+pub fn main() {
+    // No client-side main; the client entry point is `hydrate` in lib.rs.
+}
+"#;
+
+const SSR_APP_RS: &str = r#"use leptos::prelude::*;
+use leptos_meta::{MetaTags, Stylesheet, Title, provide_meta_context};
+use leptos_router::components::{Route, Router, Routes};
+use leptos_router::path;
+
+pub fn shell(options: LeptosOptions) -> impl IntoView {
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8" />
+                <meta name="viewport" content="width=device-width, initial-scale=1" />
+                <AutoReload options=options.clone() />
+                <HydrationScripts options />
+                <MetaTags />
+            </head>
+            <body>
+                <App />
+            </body>
+        </html>
+    }
+}
+
+#[component]
+pub fn App() -> impl IntoView {
+    provide_meta_context();
+
+    view! {
+        <Stylesheet id="leptos" href="/pkg/__CRATE_NAME__.css" />
+        <Title text="__NAME__" />
+        <Router>
+            <Routes fallback=|| "Page not found.">
+                <Route path=path!("") view=HomePage />
+            </Routes>
+        </Router>
+    }
+}
+
+#[component]
+fn HomePage() -> impl IntoView {
+    view! {
+        <main class="flex flex-col gap-4 justify-center items-center min-h-screen">
+            <h1 class="text-3xl font-bold">"__NAME__"</h1>
+            <p>"Add components with `ui add button`."</p>
+        </main>
+    }
+}
+"#;
+
+/// A file of the scaffolded project, relative to the project directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaffoldFile {
+    pub path: &'static str,
+    pub content: String,
+}
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Files of a minimal Leptos project named `name`: a trunk app for `Csr`,
+/// a cargo-leptos + axum app for `Ssr` (and `Hydrate`, which needs the same server).
+pub fn scaffold_files(name: &str, mode: LeptosMode) -> Vec<ScaffoldFile> {
+    let templates: &[(&'static str, &str)] = match mode {
+        LeptosMode::Csr => &[
+            ("Cargo.toml", CSR_CARGO_TOML),
+            ("index.html", CSR_INDEX_HTML),
+            ("src/main.rs", CSR_MAIN_RS),
+            ("src/app.rs", CSR_APP_RS),
+        ],
+        LeptosMode::Ssr | LeptosMode::Hydrate => &[
+            ("Cargo.toml", SSR_CARGO_TOML),
+            ("src/lib.rs", SSR_LIB_RS),
+            ("src/main.rs", SSR_MAIN_RS),
+            ("src/app.rs", SSR_APP_RS),
+        ],
+    };
+    let crate_name = name.to_snake_case();
+
+    templates
+        .iter()
+        .chain([(".gitignore", GITIGNORE), ("style/tailwind.css", TAILWIND_CSS)].iter())
+        .map(|(path, template)| ScaffoldFile {
+            path,
+            content: template.replace(CRATE_NAME, &crate_name).replace(NAME, name),
+        })
+        .collect()
+}
+
+/// Write a new project named `name` into `parent/<name>` and return its directory.
+/// Refuses to touch an existing non-empty directory.
+pub fn scaffold_project(parent: &Path, name: &str, mode: LeptosMode) -> CliResult<PathBuf> {
+    validate_project_name(name)?;
+    let project_dir = parent.join(name);
+    if project_dir.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(CliError::validation(&format!(
+            "Directory '{}' already exists and is not empty",
+            project_dir.display()
+        )));
+    }
+
+    let spinner = TaskSpinner::new(&format!("Scaffolding {} project {name}...", mode.label()));
+    for file in scaffold_files(name, mode) {
+        let path = project_dir.join(file.path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, file.content)?;
+    }
+    spinner.finish_success(&format!("Project {name} created."));
+    Ok(project_dir)
+}
+
+/// Cargo package names: ASCII letters, digits, `-` and `_`, not starting with a digit.
+pub fn validate_project_name(name: &str) -> CliResult<()> {
+    let valid_chars = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let starts_with_letter = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if valid_chars && starts_with_letter {
+        Ok(())
+    } else {
+        Err(CliError::validation(&format!(
+            "Invalid project name '{name}': use letters, digits, '-' and '_', starting with a letter"
+        )))
+    }
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::command_init::leptos_mode::detect_from_manifests;
+    use crate::command_init::workspace_utils::{
+        analyze_workspace_from_path, get_tailwind_input_file_from_path,
+    };
+
+    fn file<'a>(files: &'a [ScaffoldFile], path: &str) -> &'a str {
+        &files.iter().find(|f| f.path == path).unwrap().content
+    }
+
+    #[test]
+    fn templates_are_filled_in() {
+        for mode in [LeptosMode::Csr, LeptosMode::Ssr] {
+            for f in scaffold_files("my-app", mode) {
+                assert!(!f.content.contains("__"), "{} has an unfilled placeholder", f.path);
+            }
+        }
+        let ssr = scaffold_files("my-app", LeptosMode::Ssr);
+        assert!(file(&ssr, "src/main.rs").contains("use my_app::app::{App, shell};"));
+        assert!(file(&ssr, "Cargo.toml").contains("output-name = \"my_app\""));
+    }
+
+    #[test]
+    fn scaffolded_manifests_are_detected_in_their_mode() {
+        for mode in [LeptosMode::Csr, LeptosMode::Ssr] {
+            let files = scaffold_files("my-app", mode);
+            let manifest = cargo_toml::Manifest::from_slice(file(&files, "Cargo.toml").as_bytes()).unwrap();
+            let setup = detect_from_manifests(&manifest, None);
+            assert_eq!(setup.mode, mode);
+            assert!(setup.optional_features.is_empty());
+        }
+    }
+
+    #[test]
+    fn scaffold_project_writes_an_initializable_project() {
+        let temp = TempDir::new().unwrap();
+        let dir = scaffold_project(temp.path(), "demo", LeptosMode::Csr).unwrap();
+        assert!(dir.join("index.html").exists());
+        assert!(dir.join("src/app.rs").exists());
+        assert!(analyze_workspace_from_path(&dir).is_ok());
+        assert_eq!(get_tailwind_input_file_from_path(&dir).unwrap(), "style/tailwind.css");
+    }
+
+    #[test]
+    fn scaffold_project_refuses_non_empty_directory() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("demo")).unwrap();
+        fs::write(temp.path().join("demo/keep.txt"), "").unwrap();
+        assert!(scaffold_project(temp.path(), "demo", LeptosMode::Ssr).is_err());
+    }
+
+    #[test]
+    fn project_name_validation() {
+        assert!(validate_project_name("my-app").is_ok());
+        assert!(validate_project_name("my_app2").is_ok());
+        assert!(validate_project_name("2app").is_err());
+        assert!(validate_project_name("my app").is_err());
+        assert!(validate_project_name("../app").is_err());
+        assert!(validate_project_name("").is_err());
+    }
+}
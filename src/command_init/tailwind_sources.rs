@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::css_regions::{RegionPlacement, find_region, upsert_region};
+use super::tailwind_version::TailwindVersion;
+use super::workspace_utils::{WorkspaceInfo, analyze_workspace, get_tailwind_input_file};
+use crate::shared::cli_error::CliResult;
+use crate::shared::task_spinner::TaskSpinner;
+
+const SOURCES_REGION: &str = "sources";
+
+/* ========================================================== */
+/*                     ✨ FUNCTIONS ✨                        */
+/* ========================================================== */
+
+/// Make sure the Tailwind input file has `@source` entries covering `component_dirs`
+/// (and the target workspace member's `src/`), so classes used by installed components are not purged.
+/// Does nothing when the project has no Tailwind input file yet, or on Tailwind v3 where sources are
+/// the `content` globs of `tailwind.config.js`.
+pub fn ensure_tailwind_sources(component_dirs: &[PathBuf]) -> CliResult<()> {
+    if TailwindVersion::detect() == TailwindVersion::V3 {
+        return Ok(());
+    }
+    let Ok(tailwind_input_file) = get_tailwind_input_file() else {
+        return Ok(());
+    };
+    let css_path = Path::new(&tailwind_input_file);
+    if !css_path.exists() {
+        return Ok(());
+    }
+
+    let existing = fs::read_to_string(css_path)?;
+    let sources = tailwind_sources(component_dirs, css_path, analyze_workspace().ok().as_ref())?;
+    let updated = merge_sources(&existing, &sources);
+    if updated != existing {
+        let spinner = TaskSpinner::new(&format!("Registering @source entries in {tailwind_input_file}..."));
+        fs::write(css_path, updated)?;
+        spinner.finish_success(&format!("@source entries registered in {tailwind_input_file}."));
+    }
+    Ok(())
+}
+
+/// `@source` paths, relative to `css_path`, covering `component_dirs` and the target
+/// workspace member's `src/` (from `workspace_info`).
+pub fn tailwind_sources(
+    component_dirs: &[PathBuf],
+    css_path: &Path,
+    workspace_info: Option<&WorkspaceInfo>,
+) -> CliResult<Vec<String>> {
+    let cwd = std::env::current_dir()?;
+    let mut dirs = component_dirs.to_vec();
+    if let Some(info) = workspace_info {
+        dirs.extend(workspace_source_dirs(info));
+    }
+
+    Ok(dirs.iter().map(|dir| source_path(&cwd, css_path, dir)).collect())
+}
+
+/// Add `sources` to the managed `sources` region. Sources already declared elsewhere in the file
+/// are skipped, and sources registered by earlier runs are kept.
+pub fn merge_sources(css: &str, sources: &[String]) -> String {
+    let (managed, user_declared) = declared_sources(css);
+
+    let mut wanted: Vec<String> = managed;
+    for source in sources {
+        if !wanted.contains(source) {
+            wanted.push(source.clone());
+        }
+    }
+    wanted.retain(|source| !user_declared.contains(source));
+    wanted.sort();
+
+    if wanted.is_empty() {
+        return css.to_string();
+    }
+
+    let content: Vec<String> = wanted.iter().map(|source| format!("@source \"{source}\";")).collect();
+    upsert_region(css, SOURCES_REGION, &content.join("\n"), RegionPlacement::AfterImports)
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+/// Member source directory for workspaces, relative to the current directory.
+fn workspace_source_dirs(info: &WorkspaceInfo) -> Vec<PathBuf> {
+    if !info.is_workspace {
+        return Vec::new();
+    }
+    let Some(member_path) = &info.target_crate_path else {
+        return Vec::new();
+    };
+    vec![member_path.join("src")]
+}
+
+/// `@source` paths declared inside the managed region vs. elsewhere in the file.
+fn declared_sources(css: &str) -> (Vec<String>, Vec<String>) {
+    let (region_start, region_end) = find_region(css, SOURCES_REGION).unwrap_or((0, 0));
+    let mut managed = Vec::new();
+    let mut user_declared = Vec::new();
+
+    let mut offset = 0;
+    for line in css.split_inclusive('\n') {
+        let in_region = offset >= region_start && offset < region_end;
+        offset += line.len();
+
+        let Some(path) = parse_source_line(line) else {
+            continue;
+        };
+        if in_region { managed.push(path) } else { user_declared.push(path) }
+    }
+
+    (managed, user_declared)
+}
+
+fn parse_source_line(line: &str) -> Option<String> {
+    let rest = line.trim().strip_prefix("@source")?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = rest.get(1..)?;
+    let end = inner.find(quote)?;
+    inner.get(..end).map(str::to_string)
+}
+
+/// Path of `dir` as seen from the directory containing `css_file`, both relative to `cwd`.
+fn source_path(cwd: &Path, css_file: &Path, dir: &Path) -> String {
+    let css_dir = normalize(&cwd.join(css_file.parent().unwrap_or(Path::new(""))));
+    let target = normalize(&cwd.join(dir));
+
+    let common = css_dir.components().zip(target.components()).take_while(|(a, b)| a == b).count();
+    let ups = css_dir.components().count() - common;
+
+    let mut relative = PathBuf::new();
+    for _ in 0..ups {
+        relative.push("..");
+    }
+    for component in target.components().skip(common) {
+        relative.push(component);
+    }
+
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    if relative.is_empty() { ".".to_string() } else { relative }
+}
+
+/// Lexically resolve `.` and `..` components.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn source_path_is_relative_to_css_dir() {
+        let cwd = Path::new("/project");
+        assert_eq!(
+            source_path(cwd, Path::new("style/tailwind.css"), Path::new("src/components")),
+            "../src/components"
+        );
+        assert_eq!(
+            source_path(cwd, Path::new("tailwind.css"), Path::new("src/components")),
+            "src/components"
+        );
+        assert_eq!(
+            source_path(cwd, Path::new("./app/style/input.css"), Path::new("app/src/components")),
+            "../src/components"
+        );
+    }
+
+    #[test]
+    fn source_path_handles_absolute_member_paths() {
+        let cwd = Path::new("/ws");
+        assert_eq!(
+            source_path(cwd, Path::new("style/tailwind.css"), Path::new("/ws/crates/app/src")),
+            "../crates/app/src"
+        );
+    }
+
+    #[test]
+    fn merge_sources_adds_region_after_imports() {
+        let css = "@import \"tailwindcss\";\n\n.mine {}\n";
+        let merged = merge_sources(css, &s(&["../src/components"]));
+        assert_eq!(
+            merged,
+            "@import \"tailwindcss\";\n\n/* ui-cli:sources:start */\n@source \"../src/components\";\n/* ui-cli:sources:end */\n\n.mine {}\n"
+        );
+    }
+
+    #[test]
+    fn merge_sources_keeps_previously_managed_sources() {
+        let css = merge_sources("@import \"tailwindcss\";\n", &s(&["../src/components"]));
+        let merged = merge_sources(&css, &s(&["../crates/ui/src"]));
+        assert!(merged.contains("@source \"../src/components\";"));
+        assert!(merged.contains("@source \"../crates/ui/src\";"));
+        assert_eq!(merged.matches("ui-cli:sources:start").count(), 1);
+    }
+
+    #[test]
+    fn merge_sources_skips_user_declared_sources() {
+        let css = "@import \"tailwindcss\";\n@source '../src/components';\n";
+        assert_eq!(merge_sources(css, &s(&["../src/components"])), css);
+    }
+
+    #[test]
+    fn merge_sources_is_idempotent() {
+        let once = merge_sources("@import \"tailwindcss\";\n", &s(&["../src"]));
+        assert_eq!(merge_sources(&once, &s(&["../src"])), once);
+    }
+
+    #[test]
+    fn parse_source_line_reads_both_quote_styles() {
+        assert_eq!(parse_source_line("@source \"../src\";"), Some("../src".to_string()));
+        assert_eq!(parse_source_line("  @source '../src/**/*.rs';"), Some("../src/**/*.rs".to_string()));
+        assert_eq!(parse_source_line("@import \"tailwindcss\";"), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PACKAGE_JSON: &str = "package.json";
+
+/// Tailwind v3 projects are configured through one of these files.
+const V3_CONFIG_FILES: &[&str] =
+    &["tailwind.config.js", "tailwind.config.cjs", "tailwind.config.mjs", "tailwind.config.ts"];
+
+/// Tailwind major version the project is built with.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TailwindVersion {
+    V3,
+    #[default]
+    V4,
+}
+
+impl TailwindVersion {
+    /// Detect the Tailwind major version of the project in the current directory.
+    pub fn detect() -> Self {
+        std::env::current_dir().map(|cwd| Self::detect_from_path(&cwd)).unwrap_or_default()
+    }
+
+    /// Detect from a specific directory (useful for testing).
+    ///
+    /// The `tailwindcss` version in `package.json` wins; otherwise a `tailwind.config.*` file means v3.
+    /// New projects default to v4.
+    pub fn detect_from_path(dir: &Path) -> Self {
+        if let Some(major) = fs::read_to_string(dir.join(PACKAGE_JSON))
+            .ok()
+            .and_then(|content| tailwind_major_from_package_json(&content))
+        {
+            return if major <= 3 { Self::V3 } else { Self::V4 };
+        }
+
+        if find_config_file(dir).is_some() {
+            return Self::V3;
+        }
+
+        Self::V4
+    }
+}
+
+/// The project's `tailwind.config.*` file, if any.
+pub fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    V3_CONFIG_FILES.iter().map(|file| dir.join(file)).find(|path| path.exists())
+}
+
+/// Major version of `tailwindcss` declared in `dependencies` / `devDependencies`, if any.
+fn tailwind_major_from_package_json(content: &str) -> Option<u32> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let spec = ["devDependencies", "dependencies"]
+        .iter()
+        .find_map(|section| json.get(section)?.get("tailwindcss")?.as_str())?;
+    parse_major(spec)
+}
+
+/// First number in a semver requirement like `^3.4.1`, `~4.0.0`, `>=3` or `3.x`.
+fn parse_major(spec: &str) -> Option<u32> {
+    let digits: String = spec
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn parse_major_handles_common_specs() {
+        assert_eq!(parse_major("^3.4.1"), Some(3));
+        assert_eq!(parse_major("~4.0.0"), Some(4));
+        assert_eq!(parse_major(">=3"), Some(3));
+        assert_eq!(parse_major("4.x"), Some(4));
+        assert_eq!(parse_major("latest"), None);
+    }
+
+    #[test]
+    fn detects_v3_from_dev_dependencies() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("package.json"), r#"{"devDependencies": {"tailwindcss": "^3.4.1"}}"#)
+            .unwrap();
+        assert_eq!(TailwindVersion::detect_from_path(temp.path()), TailwindVersion::V3);
+    }
+
+    #[test]
+    fn detects_v4_from_dependencies() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("package.json"), r#"{"dependencies": {"tailwindcss": "^4.1.0"}}"#)
+            .unwrap();
+        fs::write(temp.path().join("tailwind.config.js"), "export default {}").unwrap();
+        // package.json wins over a leftover config file
+        assert_eq!(TailwindVersion::detect_from_path(temp.path()), TailwindVersion::V4);
+    }
+
+    #[test]
+    fn detects_v3_from_config_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("package.json"), r#"{"type": "module"}"#).unwrap();
+        fs::write(temp.path().join("tailwind.config.cjs"), "module.exports = {}").unwrap();
+        assert_eq!(TailwindVersion::detect_from_path(temp.path()), TailwindVersion::V3);
+    }
+
+    #[test]
+    fn defaults_to_v4() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(TailwindVersion::detect_from_path(temp.path()), TailwindVersion::V4);
+    }
+}
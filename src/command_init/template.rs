@@ -1,10 +1,13 @@
-use super::colors::{AccentColor, BaseColor, generate_theme_vars};
+use super::colors::{AccentColor, BaseColor, generate_named_theme_vars, generate_theme_vars};
+use super::config::NamedTheme;
+use super::css_regions::{
+    RegionPlacement, find_region, merge_imports, merge_tailwind_directives, upsert_region, wrap_region,
+};
+use super::tailwind_version::TailwindVersion;
 
 pub struct MyTemplate;
 
 impl MyTemplate {
-    const CSS_HEADER: &str = "@import \"tailwindcss\";\n@import \"tw-animate-css\";\n\n";
-
     const CSS_FOOTER: &str = r#"
 @theme inline {
   --color-background: var(--background);
@@ -49,13 +52,182 @@ impl MyTemplate {
     margin: auto;
   }
 }
+"#;
+
+    /// Tailwind v3 has no `@theme inline`: colors are mapped in `tailwind.config.js` instead.
+    const CSS_FOOTER_V3: &str = r#"
+@layer base {
+  * {
+    @apply border-border;
+  }
+  body {
+    @apply bg-background text-foreground;
+  }
+
+  button:not(:disabled),
+  [role="button"]:not(:disabled) {
+    cursor: pointer;
+  }
+
+  dialog {
+    margin: auto;
+  }
+}
 "#;
 
     /// Build a complete tailwind.css from the chosen base + accent colors.
+    #[cfg(test)]
     pub fn build_css(base: BaseColor, accent: AccentColor) -> String {
-        format!("{}{}{}", Self::CSS_HEADER, generate_theme_vars(base, accent), Self::CSS_FOOTER)
+        Self::build_css_with_themes(TailwindVersion::V4, base, accent, &[])
+    }
+
+    /// Build a complete tailwind.css, emitting one block per named theme after `:root` / `.dark`.
+    /// Generated CSS lives in managed regions so later runs can update it without touching user CSS.
+    pub fn build_css_with_themes(
+        version: TailwindVersion,
+        base: BaseColor,
+        accent: AccentColor,
+        themes: &[NamedTheme],
+    ) -> String {
+        Self::merge_css("", version, base, accent, themes)
+    }
+
+    /// Update an existing tailwind.css in place: add missing `@import` lines (`@tailwind` directives
+    /// for v3) and rewrite only the managed theme/base regions. Everything outside the region markers
+    /// is preserved.
+    pub fn merge_css(
+        existing: &str,
+        version: TailwindVersion,
+        base: BaseColor,
+        accent: AccentColor,
+        themes: &[NamedTheme],
+    ) -> String {
+        let css = Self::wrap_legacy_blocks(existing);
+        let (css, footer) = match version {
+            TailwindVersion::V3 => {
+                (merge_tailwind_directives(&css, Self::CSS_DIRECTIVES_V3), Self::CSS_FOOTER_V3)
+            }
+            TailwindVersion::V4 => (merge_imports(&css, Self::CSS_IMPORTS), Self::CSS_FOOTER),
+        };
+        let css = upsert_region(
+            &css,
+            Self::THEME_REGION,
+            &Self::theme_vars(base, accent, themes),
+            RegionPlacement::AfterImports,
+        );
+        upsert_region(&css, Self::BASE_REGION, footer, RegionPlacement::End)
+    }
+
+    const CSS_IMPORTS: &[&str] = &["tailwindcss", "tw-animate-css"];
+    const CSS_DIRECTIVES_V3: &[&str] = &["base", "components", "utilities"];
+    const THEME_REGION: &str = "theme";
+    const BASE_REGION: &str = "base";
+
+    /// `:root` / `.dark` variables followed by one block per named theme.
+    fn theme_vars(base: BaseColor, accent: AccentColor, themes: &[NamedTheme]) -> String {
+        let mut vars = generate_theme_vars(base, accent);
+        for theme in themes {
+            let theme_base = BaseColor::from_str(&theme.base_color).unwrap_or_default();
+            let theme_accent = AccentColor::from_str(&theme.color_theme).unwrap_or_default();
+            vars.push('\n');
+            vars.push_str(&generate_named_theme_vars(&theme.name, theme_base, theme_accent));
+        }
+        vars
+    }
+
+    /// Files written before managed regions existed contain the generated blocks unmarked.
+    /// Wrap them in region markers so the merge replaces them instead of duplicating them.
+    fn wrap_legacy_blocks(css: &str) -> String {
+        let mut css = css.to_string();
+
+        if find_region(&css, Self::THEME_REGION).is_none()
+            && let Some(start) = css.find(Self::LEGACY_ROOT_START)
+            && let Some(dark_offset) = css[start..].find(Self::LEGACY_DARK_START)
+            && let Some(close_offset) = css[start + dark_offset..].find("\n}\n")
+        {
+            let end = start + dark_offset + close_offset + "\n}\n".len();
+            let legacy = wrap_region(Self::THEME_REGION, &css[start..end]);
+            css.replace_range(start..end, &legacy);
+        }
+
+        let legacy_footer = Self::CSS_FOOTER.trim_start();
+        if find_region(&css, Self::BASE_REGION).is_none()
+            && let Some(start) = css.find(legacy_footer)
+        {
+            let end = start + legacy_footer.len();
+            css.replace_range(start..end, &wrap_region(Self::BASE_REGION, legacy_footer));
+        }
+
+        css
     }
 
+    const LEGACY_ROOT_START: &str = ":root {\n  --radius: 0.625rem;\n";
+    const LEGACY_DARK_START: &str = "\n.dark {\n";
+
+    /// `tailwind.config.js` for Tailwind v3 projects, mapping the theme colors to the CSS variables.
+    /// `content` lists the globs Tailwind scans for class names.
+    pub fn tailwind_config_v3(content: &[String]) -> String {
+        let content: Vec<String> = content.iter().map(|glob| format!("    \"{glob}\",")).collect();
+        Self::TAILWIND_CONFIG_V3.replace("__CONTENT__", &content.join("\n"))
+    }
+
+    const TAILWIND_CONFIG_V3: &str = r#"import animate from "tailwindcss-animate";
+
+/** @type {import('tailwindcss').Config} */
+export default {
+  darkMode: "class",
+  content: [
+__CONTENT__
+  ],
+  theme: {
+    extend: {
+      colors: {
+        border: "var(--border)",
+        input: "var(--input)",
+        ring: "var(--ring)",
+        background: "var(--background)",
+        foreground: "var(--foreground)",
+        primary: {
+          DEFAULT: "var(--primary)",
+          foreground: "var(--primary-foreground)",
+        },
+        secondary: {
+          DEFAULT: "var(--secondary)",
+          foreground: "var(--secondary-foreground)",
+        },
+        destructive: {
+          DEFAULT: "var(--destructive)",
+          foreground: "var(--destructive-foreground)",
+        },
+        muted: {
+          DEFAULT: "var(--muted)",
+          foreground: "var(--muted-foreground)",
+        },
+        accent: {
+          DEFAULT: "var(--accent)",
+          foreground: "var(--accent-foreground)",
+        },
+        popover: {
+          DEFAULT: "var(--popover)",
+          foreground: "var(--popover-foreground)",
+        },
+        card: {
+          DEFAULT: "var(--card)",
+          foreground: "var(--card-foreground)",
+        },
+      },
+      borderRadius: {
+        sm: "calc(var(--radius) - 4px)",
+        md: "calc(var(--radius) - 2px)",
+        lg: "var(--radius)",
+        xl: "calc(var(--radius) + 4px)",
+      },
+    },
+  },
+  plugins: [animate],
+};
+"#;
+
     pub const PACKAGE_JSON: &str = r#"{
 	"type": "module"
 }
@@ -98,6 +270,151 @@ mod tests {
         assert!(css.contains("--radius: 0.625rem"));
     }
 
+    fn ocean(base: &str) -> NamedTheme {
+        NamedTheme {
+            name: "ocean".to_string(),
+            base_color: base.to_string(),
+            color_theme: "blue".to_string(),
+        }
+    }
+
+    #[test]
+    fn build_css_with_themes_emits_named_blocks_after_dark() {
+        let css = MyTemplate::build_css_with_themes(
+            TailwindVersion::V4,
+            BaseColor::default(),
+            AccentColor::default(),
+            &[ocean("zinc")],
+        );
+        let dark = css.find(".dark {").unwrap();
+        let named = css.find("[data-theme=\"ocean\"]").unwrap();
+        let inline = css.find("@theme inline").unwrap();
+        assert!(dark < named && named < inline);
+    }
+
+    #[test]
+    fn build_css_wraps_generated_css_in_regions() {
+        let css = MyTemplate::build_css(BaseColor::default(), AccentColor::default());
+        assert!(css.starts_with(
+            "@import \"tailwindcss\";\n@import \"tw-animate-css\";\n\n/* ui-cli:theme:start */\n:root {"
+        ));
+        assert!(css.contains("/* ui-cli:theme:end */\n\n/* ui-cli:base:start */\n@theme inline {"));
+        assert!(css.ends_with("/* ui-cli:base:end */\n"));
+    }
+
+    #[test]
+    fn merge_css_is_idempotent() {
+        let css = MyTemplate::build_css_with_themes(
+            TailwindVersion::V4,
+            BaseColor::Zinc,
+            AccentColor::Blue,
+            &[ocean("stone")],
+        );
+        let merged = MyTemplate::merge_css(
+            &css,
+            TailwindVersion::V4,
+            BaseColor::Zinc,
+            AccentColor::Blue,
+            &[ocean("stone")],
+        );
+        assert_eq!(merged, css);
+    }
+
+    #[test]
+    fn merge_css_preserves_user_css_when_theme_changes() {
+        let mut css = MyTemplate::build_css(BaseColor::default(), AccentColor::default());
+        css.push_str("\n.my-button {\n  color: hotpink;\n}\n");
+        css = css.replace(
+            "@import \"tw-animate-css\";\n",
+            "@import \"tw-animate-css\";\n@import \"./fonts.css\";\n",
+        );
+
+        let merged = MyTemplate::merge_css(
+            &css,
+            TailwindVersion::V4,
+            BaseColor::Zinc,
+            AccentColor::Blue,
+            &[ocean("zinc")],
+        );
+
+        assert!(merged.contains(".my-button {\n  color: hotpink;\n}"));
+        assert!(merged.contains("@import \"./fonts.css\";"));
+        assert!(merged.contains("--primary: oklch(0.488 0.243 264.376)"));
+        assert!(merged.contains("[data-theme=\"ocean\"]"));
+        assert_eq!(merged.matches("@theme inline").count(), 1);
+    }
+
+    #[test]
+    fn merge_css_adds_missing_imports_and_regions_to_user_file() {
+        let merged = MyTemplate::merge_css(
+            ".card { padding: 1rem; }\n",
+            TailwindVersion::V4,
+            BaseColor::default(),
+            AccentColor::default(),
+            &[],
+        );
+        assert!(merged.starts_with("@import \"tailwindcss\";\n@import \"tw-animate-css\";\n"));
+        assert!(merged.contains(".card { padding: 1rem; }"));
+        assert!(merged.contains("/* ui-cli:theme:start */"));
+        assert!(merged.contains("/* ui-cli:base:start */"));
+    }
+
+    #[test]
+    fn merge_css_wraps_legacy_generated_blocks_instead_of_duplicating() {
+        let legacy = format!(
+            "@import \"tailwindcss\";\n@import \"tw-animate-css\";\n\n{}{}\n.mine {{}}\n",
+            generate_theme_vars(BaseColor::default(), AccentColor::default()),
+            MyTemplate::CSS_FOOTER
+        );
+
+        let merged =
+            MyTemplate::merge_css(&legacy, TailwindVersion::V4, BaseColor::Zinc, AccentColor::default(), &[]);
+
+        assert_eq!(merged.matches(":root {").count(), 1);
+        assert_eq!(merged.matches("@theme inline").count(), 1);
+        assert!(merged.contains("--background: oklch(0.141 0.005 285.823)"));
+        assert!(merged.ends_with(".mine {}\n"));
+    }
+
+    #[test]
+    fn build_css_v3_uses_tailwind_directives() {
+        let css = MyTemplate::build_css_with_themes(
+            TailwindVersion::V3,
+            BaseColor::default(),
+            AccentColor::default(),
+            &[],
+        );
+        assert!(css.starts_with(
+            "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n\n/* ui-cli:theme:start */\n:root {"
+        ));
+        assert!(!css.contains("@import"));
+        assert!(!css.contains("@theme inline"));
+        assert!(css.contains("@apply border-border;"));
+    }
+
+    #[test]
+    fn merge_css_v3_is_idempotent_and_keeps_user_css() {
+        let existing = "@tailwind base;\n@tailwind components;\n@tailwind utilities;\n\n.mine {}\n";
+        let merged =
+            MyTemplate::merge_css(existing, TailwindVersion::V3, BaseColor::Zinc, AccentColor::Blue, &[]);
+        assert!(merged.contains(".mine {}"));
+        assert_eq!(merged.matches("@tailwind base;").count(), 1);
+        assert_eq!(
+            MyTemplate::merge_css(&merged, TailwindVersion::V3, BaseColor::Zinc, AccentColor::Blue, &[]),
+            merged
+        );
+    }
+
+    #[test]
+    fn tailwind_config_v3_maps_colors_to_css_variables() {
+        let config = MyTemplate::tailwind_config_v3(&["./src/**/*.rs".to_string()]);
+        assert!(config.contains("    \"./src/**/*.rs\",\n"));
+        assert!(config.contains("background: \"var(--background)\""));
+        assert!(config.contains("DEFAULT: \"var(--primary)\""));
+        assert!(config.contains("darkMode: \"class\""));
+        assert!(!config.contains("__CONTENT__"));
+    }
+
     #[test]
     fn build_css_zinc_blue_has_zinc_background() {
         let css = MyTemplate::build_css(BaseColor::Zinc, AccentColor::Blue);
@@ -1,9 +1,14 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use cargo_toml::{Dependency, Manifest};
+use cargo_toml::Manifest;
 
+use super::cargo_metadata::{CargoMetadata, MetadataPackage, manifest_dependency_names, manifest_depends_on};
+use super::config::UiConfig;
 use crate::shared::cli_error::{CliError, CliResult};
 
+const UI_CONFIG_TOML: &str = "ui_config.toml";
+
 /// Information about the workspace and target crate
 #[derive(Debug, Clone, PartialEq)]
 pub struct WorkspaceInfo {
@@ -33,13 +38,31 @@ impl Default for WorkspaceInfo {
 
 /// Analyzes the current directory to detect workspace structure and find the appropriate
 /// crate for installing components.
+///
+/// At a workspace root, the member stored as `package` in ui_config.toml is targeted
+/// (see `analyze_workspace_for_package`).
 pub fn analyze_workspace() -> CliResult<WorkspaceInfo> {
+    let configured = UiConfig::try_reading_ui_config(UI_CONFIG_TOML).ok().and_then(|config| config.package);
+    analyze_workspace_for_package(configured.as_deref())
+}
+
+/// Like `analyze_workspace`, targeting the workspace member named `package`
+/// instead of the first one that depends on Leptos.
+pub fn analyze_workspace_for_package(package: Option<&str>) -> CliResult<WorkspaceInfo> {
     let current_dir = std::env::current_dir()?;
-    analyze_workspace_from_path(&current_dir)
+    analyze_workspace_from_path_for_package(&current_dir, package)
 }
 
 /// Analyzes workspace from a specific path (useful for testing)
 pub fn analyze_workspace_from_path(start_path: &Path) -> CliResult<WorkspaceInfo> {
+    analyze_workspace_from_path_for_package(start_path, None)
+}
+
+/// Analyzes workspace from a specific path, targeting the member named `package` when set.
+pub fn analyze_workspace_from_path_for_package(
+    start_path: &Path,
+    package: Option<&str>,
+) -> CliResult<WorkspaceInfo> {
     // First, check if we're in a workspace member directory
     let local_cargo_toml = start_path.join("Cargo.toml");
 
@@ -52,42 +75,103 @@ pub fn analyze_workspace_from_path(start_path: &Path) -> CliResult<WorkspaceInfo
 
     // Check if this is a workspace root
     if local_manifest.workspace.is_some() {
-        return analyze_from_workspace_root(start_path, &local_manifest);
+        let members = analyze_from_workspace_root(start_path, &local_manifest)?;
+        return match package {
+            Some(name) => find_member(&members, name).cloned(),
+            None => members.into_iter().next().ok_or_else(no_leptos_member_error),
+        };
     }
 
     // Check if we're in a workspace member by looking for workspace root
-    if let Some(workspace_root) = find_workspace_root(start_path)? {
-        return analyze_from_workspace_member(start_path, &workspace_root);
-    }
+    let info = if let Some(workspace_root) = find_workspace_root(start_path)? {
+        analyze_from_workspace_member(start_path, &workspace_root)?
+    } else {
+        // Not in a workspace - simple single-crate project
+        let has_leptos = check_leptos_in_manifest(&local_manifest);
+
+        if !has_leptos {
+            return Err(CliError::config("Leptos dependency not found in Cargo.toml"));
+        }
+
+        WorkspaceInfo {
+            is_workspace: false,
+            workspace_root: None,
+            target_crate: local_manifest.package.as_ref().map(|p| p.name.clone()),
+            target_crate_path: Some(start_path.to_path_buf()),
+            components_base_path: "src/components".to_string(),
+        }
+    };
 
-    // Not in a workspace - simple single-crate project
-    let has_leptos = check_leptos_in_manifest(&local_manifest);
+    if let Some(name) = package
+        && info.target_crate.as_deref() != Some(name)
+    {
+        return Err(CliError::validation(&format!(
+            "Package '{name}' is not the crate in the current directory ({}). Run from the workspace root to target another member.",
+            info.target_crate.as_deref().unwrap_or_default()
+        )));
+    }
+    Ok(info)
+}
 
-    if !has_leptos {
-        return Err(CliError::config("Leptos dependency not found in Cargo.toml"));
+/// Every crate components can be installed into from `start_path`: all Leptos members when
+/// run from a workspace root, otherwise the current crate.
+pub fn leptos_members_from_path(start_path: &Path) -> CliResult<Vec<WorkspaceInfo>> {
+    let cargo_toml = start_path.join("Cargo.toml");
+    if let Some(manifest) = load_cargo_manifest(&cargo_toml)?
+        && manifest.workspace.is_some()
+    {
+        let members = analyze_from_workspace_root(start_path, &manifest)?;
+        if members.is_empty() {
+            return Err(no_leptos_member_error());
+        }
+        return Ok(members);
     }
+    Ok(vec![analyze_workspace_from_path(start_path)?])
+}
 
-    Ok(WorkspaceInfo {
-        is_workspace: false,
-        workspace_root: None,
-        target_crate: local_manifest.package.as_ref().map(|p| p.name.clone()),
-        target_crate_path: Some(start_path.to_path_buf()),
-        components_base_path: "src/components".to_string(),
+/// The member named `name` (package name) among `members`.
+pub fn find_member<'a>(members: &'a [WorkspaceInfo], name: &str) -> CliResult<&'a WorkspaceInfo> {
+    members.iter().find(|info| info.target_crate.as_deref() == Some(name)).ok_or_else(|| {
+        let names: Vec<&str> = members.iter().filter_map(|info| info.target_crate.as_deref()).collect();
+        CliError::validation(&format!(
+            "No workspace member named '{name}'. Available members: {}",
+            names.join(", ")
+        ))
     })
 }
 
-/// Analyze when running from workspace root
-fn analyze_from_workspace_root(workspace_root: &Path, manifest: &Manifest) -> CliResult<WorkspaceInfo> {
+/// Analyze when running from workspace root: every member that depends on Leptos
+/// (directly, renamed, target-specific or through `leptos.workspace = true`), in `members` order.
+///
+/// Membership comes from `cargo metadata` when it loads, so `exclude` and a root package are
+/// honoured; otherwise the manifests are parsed directly.
+fn analyze_from_workspace_root(workspace_root: &Path, manifest: &Manifest) -> CliResult<Vec<WorkspaceInfo>> {
     let workspace =
         manifest.workspace.as_ref().ok_or_else(|| CliError::config("Expected workspace manifest"))?;
 
-    // Find workspace member with Leptos
-    let members = expand_workspace_members(workspace_root, &workspace.members)?;
+    let members = expand_workspace_members(workspace_root, &workspace.members, &workspace.exclude)?;
+
+    if let Some(metadata) = CargoMetadata::load(workspace_root) {
+        let position = |dir: &Path| {
+            members
+                .iter()
+                .position(|member| member.canonicalize().is_ok_and(|m| m == dir))
+                .unwrap_or(usize::MAX)
+        };
+        let mut leptos_packages: Vec<&MetadataPackage> =
+            metadata.members().filter(|package| package.depends_on("leptos")).collect();
+        leptos_packages.sort_by_key(|package| position(package.dir()));
+        return Ok(leptos_packages
+            .into_iter()
+            .map(|package| member_info(workspace_root, package.dir(), package.name.clone()))
+            .collect());
+    }
 
+    let mut leptos_members = Vec::new();
     for member_path in &members {
         let member_cargo_toml = member_path.join("Cargo.toml");
         if let Some(member_manifest) = load_cargo_manifest(&member_cargo_toml)?
-            && member_manifest.dependencies.contains_key("leptos")
+            && check_leptos_in_manifest(&member_manifest)
         {
             let crate_name = member_manifest
                 .package
@@ -96,50 +180,58 @@ fn analyze_from_workspace_root(workspace_root: &Path, manifest: &Manifest) -> Cl
                 .or_else(|| member_path.file_name().map(|n| n.to_string_lossy().to_string()))
                 .unwrap_or_default();
 
-            let relative_path = member_path.strip_prefix(workspace_root).unwrap_or(member_path);
-
-            return Ok(WorkspaceInfo {
-                is_workspace: true,
-                workspace_root: Some(workspace_root.to_path_buf()),
-                target_crate: Some(crate_name),
-                target_crate_path: Some(member_path.clone()),
-                components_base_path: format!("{}/src/components", relative_path.display()),
-            });
+            leptos_members.push(member_info(workspace_root, member_path, crate_name));
         }
     }
 
-    // Check workspace.dependencies for leptos
-    if workspace.dependencies.contains_key("leptos") {
-        // Leptos is in workspace deps, but we need to find which member uses it
-        for member_path in &members {
-            let member_cargo_toml = member_path.join("Cargo.toml");
-            if let Some(member_manifest) = load_cargo_manifest(&member_cargo_toml)?
-                && let Some(dep) = member_manifest.dependencies.get("leptos")
-                && matches!(dep, Dependency::Inherited(_))
-            {
-                let crate_name = member_manifest
-                    .package
-                    .as_ref()
-                    .map(|p| p.name.clone())
-                    .or_else(|| member_path.file_name().map(|n| n.to_string_lossy().to_string()))
-                    .unwrap_or_default();
-
-                let relative_path = member_path.strip_prefix(workspace_root).unwrap_or(member_path);
-
-                return Ok(WorkspaceInfo {
-                    is_workspace: true,
-                    workspace_root: Some(workspace_root.to_path_buf()),
-                    target_crate: Some(crate_name),
-                    target_crate_path: Some(member_path.clone()),
-                    components_base_path: format!("{}/src/components", relative_path.display()),
-                });
-            }
-        }
+    Ok(leptos_members)
+}
+
+/// Target info for the member in `member_path`; components go under its `src/`.
+fn member_info(workspace_root: &Path, member_path: &Path, crate_name: String) -> WorkspaceInfo {
+    let root = workspace_root.canonicalize().unwrap_or_else(|_| workspace_root.to_path_buf());
+    let relative_path = member_path
+        .strip_prefix(&root)
+        .or_else(|_| member_path.strip_prefix(workspace_root))
+        .unwrap_or(member_path);
+    let components_base_path = if relative_path.as_os_str().is_empty() {
+        "src/components".to_string()
+    } else {
+        format!("{}/src/components", relative_path.display())
+    };
+
+    WorkspaceInfo {
+        is_workspace: true,
+        workspace_root: Some(workspace_root.to_path_buf()),
+        target_crate: Some(crate_name),
+        target_crate_path: Some(workspace_root.join(relative_path)),
+        components_base_path,
+    }
+}
+
+/// Names of the dependencies declared by the crate in `crate_dir` (keys and renamed packages,
+/// every kind and target), from `cargo metadata` or else its manifest.
+pub fn existing_dependency_names(crate_dir: &Path) -> CliResult<HashSet<String>> {
+    if let Some(metadata) = CargoMetadata::load(crate_dir)
+        && let Some(package) = metadata.package_at(crate_dir)
+    {
+        return Ok(package.dependency_names());
     }
 
-    Err(CliError::config(
+    // Read the file directly to avoid workspace resolution issues
+    let cargo_toml_path = crate_dir.join("Cargo.toml");
+    if !cargo_toml_path.exists() {
+        return Ok(HashSet::new());
+    }
+    let contents = std::fs::read_to_string(&cargo_toml_path)?;
+    let manifest = Manifest::from_slice(contents.as_bytes())?;
+    Ok(manifest_dependency_names(&manifest))
+}
+
+fn no_leptos_member_error() -> CliError {
+    CliError::config(
         "No workspace member with Leptos dependency found. Please run from a crate directory with Leptos installed.",
-    ))
+    )
 }
 
 /// Analyze when running from a workspace member directory
@@ -198,8 +290,12 @@ fn find_workspace_root(start_path: &Path) -> CliResult<Option<PathBuf>> {
     Ok(None)
 }
 
-/// Expand workspace member patterns (handles globs like "crates/*")
-fn expand_workspace_members(workspace_root: &Path, members: &[String]) -> CliResult<Vec<PathBuf>> {
+/// Expand workspace member patterns (handles globs like "crates/*"), minus `exclude`d directories
+fn expand_workspace_members(
+    workspace_root: &Path,
+    members: &[String],
+    exclude: &[String],
+) -> CliResult<Vec<PathBuf>> {
     let mut result = Vec::new();
 
     for member in members {
@@ -223,18 +319,23 @@ fn expand_workspace_members(workspace_root: &Path, members: &[String]) -> CliRes
         }
     }
 
+    // Like cargo, an excluded path removes every member below it
+    let excluded: Vec<PathBuf> = exclude.iter().map(|path| workspace_root.join(path)).collect();
+    result.retain(|member| !excluded.iter().any(|path| member.starts_with(path)));
+
     Ok(result)
 }
 
 /// Check if manifest has leptos dependency
 fn check_leptos_in_manifest(manifest: &Manifest) -> bool {
-    manifest.dependencies.contains_key("leptos")
+    manifest_depends_on(manifest, "leptos")
 }
 
 /// Checks if Leptos is installed as a dependency in Cargo.toml
 pub fn check_leptos_dependency() -> CliResult<bool> {
-    // Use the workspace analysis which handles workspaces properly
-    match analyze_workspace() {
+    // Use the workspace analysis which handles workspaces properly. The configured member is
+    // ignored here: any Leptos member is enough, and init may be about to pick another one.
+    match analyze_workspace_from_path(&std::env::current_dir()?) {
         Ok(_) => Ok(true), // If analysis succeeds, leptos was found
         Err(e) => {
             // Check if it's specifically a "leptos not found" error
@@ -584,6 +685,167 @@ axum = "0.7"
         assert!(result.unwrap_err().to_string().contains("Leptos"));
     }
 
+    /// Workspace with three Leptos members (app, admin, shared-ui) and one server crate.
+    fn write_monorepo(root: &Path) {
+        write_cargo_toml(
+            root,
+            r#"
+[workspace]
+members = ["app", "admin", "server", "shared-ui"]
+
+[workspace.dependencies]
+leptos = "0.8"
+"#,
+        );
+        for (dir, deps) in [
+            ("app", "leptos = \"0.8\""),
+            ("admin", "leptos.workspace = true"),
+            ("server", "axum = \"0.8\""),
+            ("shared-ui", "leptos.workspace = true"),
+        ] {
+            let member = root.join(dir);
+            fs::create_dir_all(&member).unwrap();
+            write_cargo_toml(
+                &member,
+                &format!("[package]\nname = \"{dir}\"\nversion = \"0.1.0\"\n\n[dependencies]\n{deps}\n"),
+            );
+            create_src_dir(&member);
+        }
+    }
+
+    #[test]
+    fn test_leptos_members_lists_every_leptos_crate() {
+        let temp = TempDir::new().unwrap();
+        write_monorepo(temp.path());
+
+        let members = leptos_members_from_path(temp.path()).unwrap();
+        let names: Vec<_> = members.iter().filter_map(|m| m.target_crate.as_deref()).collect();
+        assert_eq!(names, vec!["app", "admin", "shared-ui"]);
+        assert_eq!(find_member(&members, "admin").unwrap().components_base_path, "admin/src/components");
+
+        let err = find_member(&members, "server").unwrap_err().to_string();
+        assert!(err.contains("app, admin, shared-ui"), "got: {err}");
+    }
+
+    #[test]
+    fn test_analyze_for_package_targets_named_member() {
+        let temp = TempDir::new().unwrap();
+        write_monorepo(temp.path());
+
+        let info = analyze_workspace_from_path_for_package(temp.path(), Some("shared-ui")).unwrap();
+        assert_eq!(info.target_crate.as_deref(), Some("shared-ui"));
+        assert_eq!(info.target_crate_path, Some(temp.path().join("shared-ui")));
+        assert_eq!(info.components_base_path, "shared-ui/src/components");
+
+        // Without a package the first Leptos member is used, as before
+        let info = analyze_workspace_from_path(temp.path()).unwrap();
+        assert_eq!(info.target_crate.as_deref(), Some("app"));
+
+        assert!(analyze_workspace_from_path_for_package(temp.path(), Some("server")).is_err());
+    }
+
+    #[test]
+    fn test_analyze_for_package_in_member_directory_must_match() {
+        let temp = TempDir::new().unwrap();
+        write_monorepo(temp.path());
+        let admin = temp.path().join("admin");
+
+        assert!(analyze_workspace_from_path_for_package(&admin, Some("admin")).is_ok());
+        assert!(analyze_workspace_from_path_for_package(&admin, Some("app")).is_err());
+        assert_eq!(leptos_members_from_path(&admin).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_exclude_renamed_and_target_specific_leptos() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write_cargo_toml(
+            root,
+            r#"
+[workspace]
+members = ["crates/*"]
+exclude = ["crates/legacy"]
+"#,
+        );
+        for (dir, deps) in [
+            ("crates/legacy", "[dependencies]\nleptos = \"0.6\""),
+            ("crates/web", "[target.'cfg(target_arch = \"wasm32\")'.dependencies]\nleptos = \"0.8\""),
+            ("crates/site", "[dependencies]\nlep = { package = \"leptos\", version = \"0.8\" }"),
+        ] {
+            let member = root.join(dir);
+            fs::create_dir_all(&member).unwrap();
+            let name = dir.trim_start_matches("crates/");
+            write_cargo_toml(
+                &member,
+                &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n{deps}\n"),
+            );
+            create_src_dir(&member);
+        }
+
+        let members = leptos_members_from_path(root).unwrap();
+        let mut names: Vec<_> = members.iter().filter_map(|m| m.target_crate.as_deref()).collect();
+        names.sort();
+        assert_eq!(names, vec!["site", "web"]);
+
+        // The manifest-parser fallback honours `exclude` too
+        let exclude = ["crates/legacy".to_string()];
+        let expanded = expand_workspace_members(root, &["crates/*".to_string()], &exclude).unwrap();
+        assert_eq!(expanded.len(), 2);
+        assert!(!expanded.iter().any(|p| p.ends_with("legacy")));
+    }
+
+    #[test]
+    fn test_workspace_root_package_with_leptos() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write_cargo_toml(
+            root,
+            r#"
+[package]
+name = "root-app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+leptos = "0.8"
+
+[workspace]
+members = []
+"#,
+        );
+        create_src_dir(root);
+
+        let info = analyze_workspace_from_path(root).unwrap();
+        assert_eq!(info.target_crate.as_deref(), Some("root-app"));
+        assert_eq!(info.components_base_path, "src/components");
+    }
+
+    #[test]
+    fn test_existing_dependency_names_include_renamed_and_target_deps() {
+        let temp = TempDir::new().unwrap();
+        write_cargo_toml(
+            temp.path(),
+            r#"
+[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+lep = { package = "leptos", version = "0.8" }
+
+[target.'cfg(target_arch = "wasm32")'.dependencies]
+web-sys = "0.3"
+"#,
+        );
+        create_src_dir(temp.path());
+
+        let names = existing_dependency_names(temp.path()).unwrap();
+        for name in ["lep", "leptos", "web-sys"] {
+            assert!(names.contains(name), "{name} in {names:?}");
+        }
+    }
+
     #[test]
     fn test_no_cargo_toml() {
         let temp = TempDir::new().unwrap();
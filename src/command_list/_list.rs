@@ -5,7 +5,6 @@ use serde::Serialize;
 
 use crate::command_add::tree_parser::TreeParser;
 use crate::shared::cli_error::CliResult;
-use crate::shared::rust_ui_client::RustUIClient;
 
 /* ========================================================== */
 /*                         🦀 MAIN 🦀                         */
@@ -20,8 +19,7 @@ pub fn command_list() -> Command {
 pub async fn process_list(matches: &ArgMatches) -> CliResult<()> {
     let json = matches.get_flag("json");
 
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
+    let tree_parser = TreeParser::fetch().await?;
     let by_category = tree_parser.get_components_by_category();
 
     let output = if json { format_list_json(&by_category)? } else { format_list(&by_category) };
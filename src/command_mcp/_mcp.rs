@@ -11,6 +11,7 @@ use serde::Deserialize;
 
 use super::config::{McpClient, write_mcp_config};
 use super::tools;
+use crate::command_config::layers::load_settings;
 use crate::shared::cli_error::{CliError, CliResult};
 
 /* ========================================================== */
@@ -50,8 +51,9 @@ pub async fn process_mcp_server() -> CliResult<()> {
 }
 
 pub fn process_mcp_init(matches: &ArgMatches) -> CliResult<()> {
-    let client_name = match matches.get_one::<String>("client") {
-        Some(s) => s.clone(),
+    let configured_client = load_settings().ok().and_then(|s| s.get_str("mcp_client").map(str::to_string));
+    let client_name = match matches.get_one::<String>("client").cloned().or(configured_client) {
+        Some(s) => s,
         None => {
             let names = McpClient::all_names();
             let labels = ["Claude Code", "Cursor", "VS Code", "OpenCode"];
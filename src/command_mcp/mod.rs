@@ -1,3 +1,3 @@
 pub mod _mcp;
-mod config;
+pub mod config;
 mod tools;
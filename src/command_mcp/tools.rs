@@ -6,8 +6,7 @@ use crate::shared::cli_error::CliResult;
 use crate::shared::rust_ui_client::RustUIClient;
 
 pub async fn list_components(category: Option<String>) -> CliResult<String> {
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
+    let tree_parser = TreeParser::fetch().await?;
     let by_category = tree_parser.get_components_by_category();
 
     let filtered = match &category {
@@ -19,10 +18,8 @@ pub async fn list_components(category: Option<String>) -> CliResult<String> {
 }
 
 pub async fn search_components(query: &str) -> CliResult<String> {
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
-    let by_category = tree_parser.get_components_by_category();
-    let filtered = filter_by_query(&by_category, query);
+    let tree_parser = TreeParser::fetch().await?;
+    let filtered = tree_parser.search(query);
     Ok(format_search_result(&filtered, query))
 }
 
@@ -40,7 +37,7 @@ After adding components:
 - [ ] mod.rs — component is pub mod'd correctly
 - [ ] Imports — correct use paths (leptos::*, leptos_ui::*)
 - [ ] Features — leptos feature flags match your project (csr/ssr/hydrate)
-- [ ] Tailwind — input.css includes the component's source glob
+- [ ] Tailwind — input.css has an `@source` covering the components directory (added by `ui init` / `ui add`)
 - [ ] Browser — hot reload and check for hydration errors in console"#
         .to_string()
 }
@@ -1,9 +1,8 @@
 use clap::{Arg, ArgMatches, Command};
 
 use crate::command_add::tree_parser::TreeParser;
-use crate::command_list::_list::{filter_by_query, format_list, format_list_json};
+use crate::command_list::_list::{format_list, format_list_json};
 use crate::shared::cli_error::CliResult;
-use crate::shared::rust_ui_client::RustUIClient;
 
 /* ========================================================== */
 /*                         🦀 MAIN 🦀                         */
@@ -11,7 +10,7 @@ use crate::shared::rust_ui_client::RustUIClient;
 
 pub fn command_search() -> Command {
     Command::new("search")
-        .about("Search available components by name")
+        .about("Search available components by name, tag or description")
         .arg(Arg::new("query").help("Search query (case-insensitive)").required(true))
         .arg(Arg::new("json").long("json").help("Output as JSON").action(clap::ArgAction::SetTrue))
 }
@@ -20,10 +19,8 @@ pub async fn process_search(matches: &ArgMatches) -> CliResult<()> {
     let query = matches.get_one::<String>("query").map(|s| s.as_str()).unwrap_or("");
     let json = matches.get_flag("json");
 
-    let tree_content = RustUIClient::fetch_tree_md().await?;
-    let tree_parser = TreeParser::parse_tree_md(&tree_content)?;
-    let by_category = tree_parser.get_components_by_category();
-    let filtered = filter_by_query(&by_category, query);
+    let tree_parser = TreeParser::fetch().await?;
+    let filtered = tree_parser.search(query);
 
     let output = if json { format_list_json(&filtered)? } else { format_search_result(&filtered, query) };
     println!("{output}");
@@ -60,6 +57,7 @@ mod tests {
     use std::collections::BTreeMap;
 
     use super::*;
+    use crate::command_list::_list::filter_by_query;
 
     fn make_map(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
         entries
@@ -0,0 +1,351 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use clap::{Arg, ArgMatches, Command};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, value};
+
+use super::switcher::{DEFAULT_THEME, SWITCHER_COMPONENT_NAME, render_theme_switcher};
+use crate::command_add::component_dirs::ComponentDirs;
+use crate::command_add::components::Components;
+use crate::command_add::registry::{write_component_file, write_component_name_in_mod_rs_if_not_exists};
+use crate::command_add::rustfmt::format_or_keep;
+use crate::command_config::migrate::load_ui_config;
+use crate::command_init::_init::{prompt_accent_color, prompt_base_color};
+use crate::command_init::colors::{AccentColor, BaseColor};
+use crate::command_init::config::{NamedTheme, UiConfig};
+use crate::command_init::tailwind_version::TailwindVersion;
+use crate::command_init::template::MyTemplate;
+use crate::command_init::workspace_utils::get_tailwind_input_file;
+use crate::shared::cli_error::{CliError, CliResult};
+use crate::shared::task_spinner::TaskSpinner;
+
+const UI_CONFIG_TOML: &str = "ui_config.toml";
+
+/* ========================================================== */
+/*                      🔧 CLAP COMMANDS 🔧                   */
+/* ========================================================== */
+
+pub fn command_theme() -> Command {
+    Command::new("theme")
+        .about("Manage named themes for runtime theme switching")
+        .subcommand(
+            Command::new("add")
+                .about("Add (or replace) a named theme in ui_config.toml and the Tailwind input file")
+                .arg(
+                    Arg::new("name")
+                        .help("Theme name, used as data-theme=\"name\" / .theme-name")
+                        .required(true),
+                )
+                .arg(Arg::new("base").long("base").value_name("COLOR").help("Base color (e.g. zinc, stone)"))
+                .arg(
+                    Arg::new("accent")
+                        .long("accent")
+                        .value_name("COLOR")
+                        .help("Accent color (e.g. blue, rose)"),
+                )
+                .arg(
+                    Arg::new("switcher")
+                        .long("switcher")
+                        .help("Also generate the ThemeSwitcher Leptos component")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("yes")
+                        .short('y')
+                        .long("yes")
+                        .help("Skip prompts and use the project colors for missing options")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(Command::new("list").about("List the named themes declared in ui_config.toml"))
+        .subcommand(
+            Command::new("switcher")
+                .about("Generate the ThemeSwitcher Leptos component for the declared themes"),
+        )
+}
+
+/* ========================================================== */
+/*                      🦀 PROCESS FNS 🦀                     */
+/* ========================================================== */
+
+pub fn process_theme(matches: &ArgMatches) -> CliResult<()> {
+    match matches.subcommand() {
+        Some(("add", add_matches)) => process_theme_add(add_matches),
+        Some(("switcher", _)) => {
+            let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
+            write_theme_switcher(&config)
+        }
+        _ => {
+            let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
+            println!("{}", format_theme_list(&config));
+            Ok(())
+        }
+    }
+}
+
+fn process_theme_add(matches: &ArgMatches) -> CliResult<()> {
+    let name = matches.get_one::<String>("name").cloned().unwrap_or_default();
+    validate_theme_name(&name)?;
+    let skip_prompts = matches.get_flag("yes");
+
+    // Written back below, so user-level settings must not leak into the project file
+    let mut config = UiConfig::try_reading_project_config(UI_CONFIG_TOML)?;
+
+    let base_color = match matches.get_one::<String>("base") {
+        Some(label) => BaseColor::parse(label)?,
+        None if skip_prompts => BaseColor::from_str(&config.base_color).unwrap_or_default(),
+        None => prompt_base_color(BaseColor::from_str(&config.base_color).unwrap_or_default())?,
+    };
+    let accent_color = match matches.get_one::<String>("accent") {
+        Some(label) => AccentColor::parse(label)?,
+        None if skip_prompts => AccentColor::from_str(&config.color_theme).unwrap_or_default(),
+        None => prompt_accent_color(AccentColor::from_str(&config.color_theme).unwrap_or_default())?,
+    };
+
+    let theme = NamedTheme {
+        name: name.clone(),
+        base_color: base_color.label().to_lowercase(),
+        color_theme: accent_color.label().to_lowercase(),
+    };
+
+    config.upsert_theme(theme.clone());
+
+    // Only the managed theme region is rewritten; user CSS around it is preserved
+    let tailwind_input_file = get_tailwind_input_file()?;
+    let spinner = TaskSpinner::new(&format!("Writing theme '{name}' to {tailwind_input_file}..."));
+    let existing = match fs::read_to_string(&tailwind_input_file) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let base = BaseColor::from_str(&config.base_color).unwrap_or_default();
+    let accent = AccentColor::from_str(&config.color_theme).unwrap_or_default();
+    let css = MyTemplate::merge_css(&existing, TailwindVersion::detect(), base, accent, &config.themes);
+    if let Some(dir) = Path::new(&tailwind_input_file).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&tailwind_input_file, css)?;
+    spinner.finish_success(&format!("Theme '{name}' written to {tailwind_input_file}."));
+
+    let contents = fs::read_to_string(UI_CONFIG_TOML)?;
+    fs::write(UI_CONFIG_TOML, upsert_theme_entry(&contents, &theme)?)?;
+
+    // Keep an existing switcher in sync with the theme list
+    if matches.get_flag("switcher") || switcher_path(&config).exists() {
+        write_theme_switcher(&config)?;
+    }
+
+    Ok(())
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+fn write_theme_switcher(config: &UiConfig) -> CliResult<()> {
+    let path = switcher_path(config);
+    let ui_dir = ComponentDirs::from_config(config).dir("ui");
+
+    Components::declare_category_modules(&ComponentDirs::from_config(config), vec!["ui".to_string()])?;
+    let mut content = render_theme_switcher(&config.themes);
+    if config.format_on_write == Some(true) {
+        content = format_or_keep(&content, &ui_dir, &format!("{SWITCHER_COMPONENT_NAME}.rs"));
+    }
+    write_component_file(&path, &content, true)?;
+    write_component_name_in_mod_rs_if_not_exists(
+        SWITCHER_COMPONENT_NAME.to_string(),
+        ui_dir.to_string_lossy().to_string(),
+    )?;
+
+    println!("✔️ ThemeSwitcher written to {}", path.display());
+    Ok(())
+}
+
+fn switcher_path(config: &UiConfig) -> std::path::PathBuf {
+    ComponentDirs::from_config(config).dir("ui").join(format!("{SWITCHER_COMPONENT_NAME}.rs"))
+}
+
+/// Theme names end up in CSS selectors and Rust string literals, so keep them to `[a-z0-9-]`.
+fn validate_theme_name(name: &str) -> CliResult<()> {
+    let valid_chars = name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if name.is_empty() || !valid_chars || name.starts_with('-') {
+        return Err(CliError::validation(&format!(
+            "Invalid theme name '{name}'. Use lowercase letters, digits and '-'."
+        )));
+    }
+    if name == DEFAULT_THEME {
+        return Err(CliError::validation("'default' is reserved for the :root / .dark theme"));
+    }
+    Ok(())
+}
+
+/// Add `theme` as a `[[themes]]` entry of the ui_config.toml `contents`, or update the entry with
+/// its name in place, keeping comments, ordering and formatting.
+fn upsert_theme_entry(contents: &str, theme: &NamedTheme) -> CliResult<String> {
+    let mut doc: DocumentMut =
+        contents.parse().map_err(|e| CliError::config(&format!("Failed to parse ui_config.toml: {e}")))?;
+
+    let themes = doc
+        .entry("themes")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| CliError::config("`themes` in ui_config.toml must be a list of [[themes]] tables"))?;
+
+    let is_theme = |entry: &Table| entry.get("name").and_then(Item::as_str) == Some(theme.name.as_str());
+    if !themes.iter().any(is_theme) {
+        let mut entry = Table::new();
+        entry.insert("name", value(&theme.name));
+        themes.push(entry);
+    }
+    for entry in themes.iter_mut().filter(|entry| is_theme(entry)) {
+        entry.insert("base_color", value(&theme.base_color));
+        entry.insert("color_theme", value(&theme.color_theme));
+    }
+
+    let updated = doc.to_string();
+    load_ui_config(&updated)?;
+    Ok(updated)
+}
+
+fn format_theme_list(config: &UiConfig) -> String {
+    let mut lines = vec![format!("  {DEFAULT_THEME:<12} {} / {}", config.base_color, config.color_theme)];
+    for theme in &config.themes {
+        lines.push(format!("  {:<12} {} / {}", theme.name, theme.base_color, theme.color_theme));
+    }
+    lines.join("\n")
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_init::config::{Aliases, ComponentPaths};
+
+    fn config_with_themes(themes: &[&str]) -> UiConfig {
+        UiConfig {
+            version: 1,
+            base_color: "neutral".to_string(),
+            color_theme: "default".to_string(),
+            base_path_components: "src/components".to_string(),
+            themes: themes
+                .iter()
+                .map(|name| NamedTheme {
+                    name: name.to_string(),
+                    base_color: "zinc".to_string(),
+                    color_theme: "blue".to_string(),
+                })
+                .collect(),
+            package_manager: None,
+            leptos_mode: None,
+            package: None,
+            registry: None,
+            mcp_client: None,
+            color: None,
+            concurrency: None,
+            format_on_write: None,
+            aliases: Aliases::default(),
+            paths: ComponentPaths::default(),
+        }
+    }
+
+    #[test]
+    fn command_theme_add_parses_colors_and_switcher() {
+        let m = command_theme()
+            .try_get_matches_from([
+                "theme",
+                "add",
+                "ocean",
+                "--base",
+                "zinc",
+                "--accent",
+                "blue",
+                "--switcher",
+            ])
+            .unwrap();
+        let (name, add) = m.subcommand().unwrap();
+        assert_eq!(name, "add");
+        assert_eq!(add.get_one::<String>("name").unwrap(), "ocean");
+        assert_eq!(add.get_one::<String>("base").unwrap(), "zinc");
+        assert_eq!(add.get_one::<String>("accent").unwrap(), "blue");
+        assert!(add.get_flag("switcher"));
+    }
+
+    #[test]
+    fn command_theme_add_requires_name() {
+        assert!(command_theme().try_get_matches_from(["theme", "add"]).is_err());
+    }
+
+    #[test]
+    fn validate_theme_name_accepts_kebab_case() {
+        assert!(validate_theme_name("ocean").is_ok());
+        assert!(validate_theme_name("high-contrast-2").is_ok());
+    }
+
+    #[test]
+    fn validate_theme_name_rejects_invalid_names() {
+        assert!(validate_theme_name("").is_err());
+        assert!(validate_theme_name("Ocean").is_err());
+        assert!(validate_theme_name("my theme").is_err());
+        assert!(validate_theme_name("-ocean").is_err());
+        assert!(validate_theme_name("default").is_err());
+    }
+
+    #[test]
+    fn parse_colors_reject_unknown_labels() {
+        assert_eq!(BaseColor::parse("Zinc").unwrap(), BaseColor::Zinc);
+        assert!(BaseColor::parse("purple").is_err());
+        assert_eq!(AccentColor::parse("rose").unwrap(), AccentColor::Rose);
+        assert!(AccentColor::parse("zinc").is_err());
+    }
+
+    #[test]
+    fn format_theme_list_starts_with_default() {
+        let out = format_theme_list(&config_with_themes(&["ocean"]));
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("default") && lines[0].contains("neutral / default"));
+        assert!(lines[1].contains("ocean") && lines[1].contains("zinc / blue"));
+    }
+
+    #[test]
+    fn upsert_theme_entry_keeps_the_rest_of_the_file() {
+        const CONFIG: &str = r#"# rust-ui settings
+version = 1
+base_color = "neutral" # gray scale
+color_theme = "default"
+base_path_components = "src/components"
+
+# Extra themes
+[[themes]]
+name = "ocean"
+base_color = "zinc"
+color_theme = "blue"
+"#;
+        let theme = |name: &str, color_theme: &str| NamedTheme {
+            name: name.to_string(),
+            base_color: "stone".to_string(),
+            color_theme: color_theme.to_string(),
+        };
+
+        let added = upsert_theme_entry(CONFIG, &theme("forest", "green")).unwrap();
+        let forest = "[[themes]]\nname = \"forest\"\nbase_color = \"stone\"\ncolor_theme = \"green\"\n";
+        assert_eq!(added, format!("{CONFIG}\n{forest}"));
+
+        let replaced = upsert_theme_entry(&added, &theme("ocean", "rose")).unwrap();
+        let ocean =
+            "# Extra themes\n[[themes]]\nname = \"ocean\"\nbase_color = \"stone\"\ncolor_theme = \"rose\"\n";
+        assert!(replaced.contains(ocean), "{replaced}");
+        assert!(replaced.ends_with(forest));
+        assert!(replaced.contains("base_color = \"neutral\" # gray scale"));
+    }
+
+    #[test]
+    fn switcher_path_is_under_ui_dir() {
+        let path = switcher_path(&config_with_themes(&[]));
+        assert_eq!(path, Path::new("src/components/ui/theme_switcher.rs"));
+    }
+}
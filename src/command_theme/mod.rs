@@ -0,0 +1,3 @@
+pub mod _theme;
+
+mod switcher;
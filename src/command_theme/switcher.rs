@@ -0,0 +1,74 @@
+use crate::command_init::config::NamedTheme;
+
+/// Name of the implicit theme backed by the `:root` / `.dark` variables.
+pub const DEFAULT_THEME: &str = "default";
+
+pub const SWITCHER_COMPONENT_NAME: &str = "theme_switcher";
+
+const SWITCHER_TEMPLATE: &str = r#"// Generated by `ui theme add`. Re-run it (or `ui theme switcher`) after adding themes.
+use leptos::prelude::*;
+
+/// Theme names declared in ui_config.toml. `default` uses the `:root` / `.dark` variables.
+pub const THEMES: &[&str] = &[__THEMES__];
+
+#[component]
+pub fn ThemeSwitcher(#[prop(into, optional)] class: String) -> impl IntoView {
+    let theme = RwSignal::new("default".to_string());
+
+    Effect::new(move |_| {
+        let name = theme.get();
+        if let Some(root) = document().document_element() {
+            let _ = if name == "default" {
+                root.remove_attribute("data-theme")
+            } else {
+                root.set_attribute("data-theme", &name)
+            };
+        }
+    });
+
+    view! {
+        <select class=class on:change=move |ev| theme.set(event_target_value(&ev))>
+            {THEMES.iter().map(|name| view! { <option value=*name>{*name}</option> }).collect_view()}
+        </select>
+    }
+}
+"#;
+
+/// Render the `ThemeSwitcher` Leptos component wired to the given theme names.
+pub fn render_theme_switcher(themes: &[NamedTheme]) -> String {
+    let names: Vec<String> = std::iter::once(DEFAULT_THEME)
+        .chain(themes.iter().map(|t| t.name.as_str()))
+        .map(|name| format!("\"{name}\""))
+        .collect();
+    SWITCHER_TEMPLATE.replace("__THEMES__", &names.join(", "))
+}
+
+/* ========================================================== */
+/*                        🧪 TESTS 🧪                         */
+/* ========================================================== */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(name: &str) -> NamedTheme {
+        NamedTheme { name: name.to_string(), base_color: "zinc".to_string(), color_theme: "blue".to_string() }
+    }
+
+    #[test]
+    fn render_lists_default_first() {
+        let code = render_theme_switcher(&[theme("ocean"), theme("forest")]);
+        assert!(
+            code.contains(r#"pub const THEMES: &[&str] = &["default", "ocean", "forest"];"#),
+            "got: {code}"
+        );
+    }
+
+    #[test]
+    fn render_without_themes_only_has_default() {
+        let code = render_theme_switcher(&[]);
+        assert!(code.contains(r#"&["default"];"#));
+        assert!(code.contains("pub fn ThemeSwitcher"));
+        assert!(!code.contains("__THEMES__"));
+    }
+}
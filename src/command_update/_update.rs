@@ -1,10 +1,11 @@
-use std::path::Path;
-
 use clap::{Arg, ArgMatches, Command};
 use serde::Serialize;
 
-use crate::command_add::component_type::ComponentType;
+use crate::command_add::component_dirs::ComponentDirs;
+use crate::command_add::import_paths::ImportAliases;
 use crate::command_add::installed::get_installed_components;
+use crate::command_add::rustfmt::format_or_same;
+use crate::command_add::tree_parser::TreeParser;
 use crate::command_init::config::UiConfig;
 use crate::shared::cli_error::CliResult;
 use crate::shared::rust_ui_client::RustUIClient;
@@ -37,15 +38,25 @@ pub fn command_update() -> Command {
     Command::new("update")
         .about("Check installed components for updates against the registry")
         .arg(Arg::new("json").long("json").help("Output as JSON").action(clap::ArgAction::SetTrue))
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Compare rustfmt-formatted sources (default: format_on_write from ui_config.toml)")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 pub async fn process_update(matches: &ArgMatches) -> CliResult<()> {
     let json = matches.get_flag("json");
+    let format_flag = matches.get_flag("format");
 
     let config = UiConfig::try_reading_ui_config(UI_CONFIG_TOML)?;
-    let base_path = config.base_path_components;
+    let dirs = ComponentDirs::from_config(&config);
+    let format = format_flag || config.format_on_write == Some(true);
+    let tree_parser = TreeParser::fetch().await?;
 
-    let mut installed: Vec<String> = get_installed_components(&base_path).into_iter().collect();
+    let categories = dirs.categories(&tree_parser.get_categories());
+    let mut installed: Vec<String> = get_installed_components(&dirs, &categories).into_iter().collect();
     installed.sort();
 
     if installed.is_empty() {
@@ -57,10 +68,10 @@ pub async fn process_update(matches: &ArgMatches) -> CliResult<()> {
 
     let mut results: Vec<ComponentUpdateInfo> = Vec::new();
 
-    for name in &installed {
-        let component_type = ComponentType::from_component_name(name);
-        let relative_path = format!("{}/{}.rs", component_type.to_path(), name);
-        let local_path = Path::new(&base_path).join(&relative_path);
+    let fetched = RustUIClient::fetch_styles_default_all(&installed).await;
+    for (name, fetched) in installed.iter().zip(fetched) {
+        let category = tree_parser.category_of(name);
+        let local_path = dirs.component_file(name, &category);
 
         let local_content = match std::fs::read_to_string(&local_path) {
             Ok(c) => c,
@@ -70,8 +81,19 @@ pub async fn process_update(matches: &ArgMatches) -> CliResult<()> {
             }
         };
 
-        let status = match RustUIClient::fetch_styles_default(name).await {
-            Ok(remote_content) => compare_content(&local_content, &remote_content),
+        let status = match fetched {
+            Ok(remote_content) => {
+                let remote_content =
+                    ImportAliases::resolve(&config.aliases, &dirs, &category).rewrite(&remote_content);
+                if format {
+                    let dir = dirs.dir(&category);
+                    let (local, remote) =
+                        (format_or_same(&local_content, &dir), format_or_same(&remote_content, &dir));
+                    compare_content(&local, &remote)
+                } else {
+                    compare_content(&local_content, &remote_content)
+                }
+            }
             Err(_) => ComponentStatus::NotInRegistry,
         };
 
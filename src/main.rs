@@ -9,6 +9,7 @@ use std::process;
 use clap::Command;
 
 mod command_add;
+mod command_config;
 mod command_diff;
 mod command_docs;
 mod command_info;
@@ -17,6 +18,7 @@ mod command_list;
 mod command_mcp;
 mod command_search;
 mod command_starters;
+mod command_theme;
 mod command_update;
 mod command_view;
 mod shared;
@@ -44,17 +46,19 @@ async fn main() {
         .subcommand(command_diff::_diff::command_diff())
         .subcommand(command_docs::_docs::command_docs())
         .subcommand(command_starters::_starters::command_starters())
+        .subcommand(command_theme::_theme::command_theme())
+        .subcommand(command_config::_config::command_config())
         .subcommand(command_view::_view::command_view())
         .subcommand(command_mcp::_mcp::command_mcp());
 
     let matches = mut_program.clone().get_matches();
+    command_config::layers::apply_global_settings();
 
     // Handle commands
     match matches.subcommand() {
         Some(("init", sub_matches)) => {
-            let force = sub_matches.get_flag("yes") || sub_matches.get_flag("force");
-            let reinstall = if sub_matches.get_flag("reinstall") { Some(true) } else { None };
-            match command_init::_init::process_init(force, reinstall).await {
+            let options = command_init::_init::InitOptions::from_matches(sub_matches);
+            match command_init::_init::process_init(options).await {
                 Err(e) => {
                     eprintln!("{e}");
                     process::exit(1);
@@ -121,6 +125,18 @@ async fn main() {
                 process::exit(1);
             }
         }
+        Some(("theme", sub_matches)) => {
+            if let Err(e) = command_theme::_theme::process_theme(sub_matches) {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        Some(("config", sub_matches)) => {
+            if let Err(e) = command_config::_config::process_config(sub_matches) {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
         Some(("view", sub_matches)) => {
             if let Err(e) = command_view::_view::process_view(sub_matches).await {
                 eprintln!("{e}");
@@ -1,24 +1,85 @@
+/// A file a registry component ships next to its `.rs` file, declared by a fenced block
+/// with a target path: ```` ```css path=/style/carousel.css ````.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryFile {
+    /// Relative to the component's directory, or to the project root when it starts with `/`.
+    pub path: String,
+    /// Language of the block (`rust`, `css`, `js`, `svg`, ...), empty when unset.
+    pub language: String,
+    pub content: String,
+}
+
+/// The first ```` ```rust ```` block without a `path`: the component itself.
 pub fn extract_rust_code_from_markdown(markdown: &str) -> Option<String> {
-    let lines: Vec<&str> = markdown.lines().collect();
-    let mut in_rust_block = false;
-    let mut rust_code_lines = Vec::new();
-
-    for line in lines {
-        if line.trim() == "```rust" {
-            in_rust_block = true;
-            continue;
-        }
+    fenced_blocks(markdown)
+        .into_iter()
+        .find(|block| block.language == "rust" && block.path.is_none())
+        .map(|block| block.content)
+        .filter(|content| !content.is_empty())
+}
 
-        if in_rust_block && line.trim() == "```" {
-            break;
-        }
+/// Every fenced block with a `path` attribute, in page order.
+pub fn extract_files_from_markdown(markdown: &str) -> Vec<RegistryFile> {
+    fenced_blocks(markdown)
+        .into_iter()
+        .filter_map(|block| {
+            let path = block.path?;
+            Some(RegistryFile { path, language: block.language, content: block.content })
+        })
+        .collect()
+}
+
+/* ========================================================== */
+/*                     ✨ HELPERS ✨                          */
+/* ========================================================== */
+
+struct FencedBlock {
+    language: String,
+    path: Option<String>,
+    content: String,
+}
 
-        if in_rust_block {
-            rust_code_lines.push(line);
+/// Fenced blocks of `markdown`; a block left open at the end runs to the end of the page.
+fn fenced_blocks(markdown: &str) -> Vec<FencedBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(FencedBlock, Vec<&str>)> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        match current.take() {
+            Some((mut block, lines)) if trimmed == "```" => {
+                block.content = lines.join("\n");
+                blocks.push(block);
+            }
+            Some((block, mut lines)) => {
+                lines.push(line);
+                current = Some((block, lines));
+            }
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    current = Some((parse_info_string(info), Vec::new()));
+                }
+            }
         }
     }
 
-    if rust_code_lines.is_empty() { None } else { Some(rust_code_lines.join("\n")) }
+    if let Some((mut block, lines)) = current {
+        block.content = lines.join("\n");
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// ```` ```css path="/style/carousel.css" ```` -> language `css`, path `/style/carousel.css`.
+fn parse_info_string(info: &str) -> FencedBlock {
+    let mut words = info.split_whitespace();
+    let language = words.next().unwrap_or_default().to_string();
+    let path = words
+        .filter_map(|word| word.strip_prefix("path="))
+        .map(|path| path.trim_matches('"').to_string())
+        .find(|path| !path.is_empty());
+    FencedBlock { language, path, content: String::new() }
 }
 
 /* ========================================================== */
@@ -68,4 +129,41 @@ mod tests {
         assert!(result.contains("use leptos::*;"));
         assert!(result.contains("fn foo() {}"));
     }
+
+    #[test]
+    fn keeps_an_unclosed_block_at_the_end() {
+        let md = "# Title\n\n```rust\nfn main() {}\n";
+        assert_eq!(extract_rust_code_from_markdown(md), Some("fn main() {}".to_string()));
+
+        let files = extract_files_from_markdown("```rust\npub mod a;\n```\n```css path=/a.css\n.a {}");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].content, ".a {}");
+    }
+
+    #[test]
+    fn blocks_with_a_path_are_not_the_component() {
+        let md = "```rust path=carousel/slides.rs\npub struct Slide;\n```\n```rust\npub mod slides;\n```";
+        assert_eq!(extract_rust_code_from_markdown(md), Some("pub mod slides;".to_string()));
+    }
+
+    #[test]
+    fn extracts_files_with_their_path_and_language() {
+        let md = "# Carousel\n\n```rust\npub mod slides;\n```\n\n```rust path=carousel/slides.rs\npub struct Slide;\n```\n\n```css path=\"/style/carousel.css\"\n.carousel {}\n```\n\n```rust\n// usage example\n```";
+        assert_eq!(
+            extract_files_from_markdown(md),
+            vec![
+                RegistryFile {
+                    path: "carousel/slides.rs".to_string(),
+                    language: "rust".to_string(),
+                    content: "pub struct Slide;".to_string(),
+                },
+                RegistryFile {
+                    path: "/style/carousel.css".to_string(),
+                    language: "css".to_string(),
+                    content: ".carousel {}".to_string(),
+                },
+            ]
+        );
+        assert!(extract_files_from_markdown("```rust\nfn main() {}\n```").is_empty());
+    }
 }
@@ -1,19 +1,52 @@
+use std::sync::OnceLock;
+
+use tokio::task::JoinSet;
+
 use crate::shared::cli_error::{CliError, CliResult};
-use crate::shared::markdown_utils::extract_rust_code_from_markdown;
+use crate::shared::markdown_utils::{
+    RegistryFile, extract_files_from_markdown, extract_rust_code_from_markdown,
+};
+
+static REGISTRY_URL: OnceLock<String> = OnceLock::new();
+static CONCURRENCY: OnceLock<usize> = OnceLock::new();
 
 pub struct RustUIClient;
 
 impl RustUIClient {
     const BASE_URL: &str = "https://www.rust-ui.com/registry";
     const SITE_URL: &str = "https://www.rust-ui.com";
+    const DEFAULT_CONCURRENCY: usize = 4;
+
+    /// Use another registry and/or request limit for the rest of the run (`registry` / `concurrency` settings).
+    /// Only the first call has an effect.
+    pub fn configure(registry: Option<String>, concurrency: Option<usize>) {
+        if let Some(registry) = registry {
+            let _ = REGISTRY_URL.set(registry.trim_end_matches('/').to_string());
+        }
+        if let Some(concurrency) = concurrency {
+            let _ = CONCURRENCY.set(concurrency.max(1));
+        }
+    }
+
+    fn base_url() -> &'static str {
+        REGISTRY_URL.get().map(String::as_str).unwrap_or(Self::BASE_URL)
+    }
 
     // URL builders - centralized URL construction
     fn tree_url() -> String {
-        format!("{}/tree.md", Self::BASE_URL)
+        format!("{}/tree.md", Self::base_url())
+    }
+
+    fn registry_json_url() -> String {
+        format!("{}/registry.json", Self::base_url())
+    }
+
+    fn compat_url() -> String {
+        format!("{}/compat.json", Self::base_url())
     }
 
     fn component_url(component_name: &str) -> String {
-        format!("{}/styles/default/{component_name}.md", Self::BASE_URL)
+        format!("{}/styles/default/{component_name}.md", Self::base_url())
     }
 
     fn js_file_url(path: &str) -> String {
@@ -32,6 +65,20 @@ impl RustUIClient {
     }
 
     // Public API methods
+    /// `registry.json`, or `None` when the registry does not publish one.
+    pub async fn fetch_registry_json() -> CliResult<Option<String>> {
+        let response =
+            reqwest::get(Self::registry_json_url()).await.map_err(|_| CliError::registry_request_failed())?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(CliError::registry_request_failed());
+        }
+        let content = response.text().await.map_err(|_| CliError::registry_request_failed())?;
+        Ok(Some(content))
+    }
+
     pub async fn fetch_tree_md() -> CliResult<String> {
         let response = Self::fetch_response(&Self::tree_url()).await?;
         let content = response.text().await.map_err(|_| CliError::registry_request_failed())?;
@@ -44,12 +91,71 @@ impl RustUIClient {
     }
 
     pub async fn fetch_styles_default(component_name: &str) -> CliResult<String> {
-        let response = Self::fetch_response(&Self::component_url(component_name)).await?;
-        let markdown_content = response.text().await.map_err(|_| CliError::registry_request_failed())?;
+        let markdown_content = Self::fetch_component_markdown(component_name).await?;
 
         extract_rust_code_from_markdown(&markdown_content).ok_or_else(CliError::registry_component_missing)
     }
 
+    /// `fetch_styles_default` along with the other files the component page declares.
+    pub async fn fetch_component_files(component_name: &str) -> CliResult<(String, Vec<RegistryFile>)> {
+        let markdown_content = Self::fetch_component_markdown(component_name).await?;
+        let rust_code = extract_rust_code_from_markdown(&markdown_content)
+            .ok_or_else(CliError::registry_component_missing)?;
+
+        Ok((rust_code, extract_files_from_markdown(&markdown_content)))
+    }
+
+    async fn fetch_component_markdown(component_name: &str) -> CliResult<String> {
+        let response = Self::fetch_response(&Self::component_url(component_name)).await?;
+        response.text().await.map_err(|_| CliError::registry_request_failed())
+    }
+
+    /// `fetch_styles_default` for each name, with at most `concurrency` requests in flight.
+    /// Results are in the order of `names`.
+    pub async fn fetch_styles_default_all(names: &[String]) -> Vec<CliResult<String>> {
+        let limit = CONCURRENCY.get().copied().unwrap_or(Self::DEFAULT_CONCURRENCY);
+        let mut results: Vec<Option<CliResult<String>>> = names.iter().map(|_| None).collect();
+        let mut tasks = JoinSet::new();
+
+        for (index, name) in names.iter().cloned().enumerate() {
+            if tasks.len() >= limit {
+                Self::store_next(&mut tasks, &mut results).await;
+            }
+            tasks.spawn(async move { (index, Self::fetch_styles_default(&name).await) });
+        }
+        while !tasks.is_empty() {
+            Self::store_next(&mut tasks, &mut results).await;
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(CliError::registry_request_failed())))
+            .collect()
+    }
+
+    async fn store_next(
+        tasks: &mut JoinSet<(usize, CliResult<String>)>,
+        results: &mut [Option<CliResult<String>>],
+    ) {
+        if let Some(Ok((index, result))) = tasks.join_next().await
+            && let Some(slot) = results.get_mut(index)
+        {
+            *slot = Some(result);
+        }
+    }
+
+    /// Fetch the crate compatibility table (init crate versions per Leptos version)
+    pub async fn fetch_compat_json() -> CliResult<String> {
+        let response = Self::fetch_response(&Self::compat_url()).await?;
+        let content = response.text().await.map_err(|_| CliError::registry_request_failed())?;
+
+        if content.is_empty() {
+            return Err(CliError::registry_request_failed());
+        }
+
+        Ok(content)
+    }
+
     /// Fetch a JS file from the site (e.g., /hooks/lock_scroll.js)
     pub async fn fetch_js_file(path: &str) -> CliResult<String> {
         let response = Self::fetch_response(&Self::js_file_url(path)).await?;